@@ -0,0 +1,29 @@
+//! Usage example of the mkv-rs library. This program checks the structure of the specified
+//! matroska or WebM video file, and exits with a non-zero status if it is invalid.
+//! Usage: ./mkvalidate <file>
+
+extern crate mkv;
+
+use std::fs::File;
+use std::io::BufReader;
+use mkv::validate::{self, Severity};
+
+fn main() {
+    let path = ::std::env::args().nth(1).expect("Please specify a filename");
+    let file = BufReader::new(File::open(path).unwrap());
+
+    let findings = validate::validate(file).unwrap();
+
+    for finding in &findings {
+        let severity = match finding.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        println!("{} at {} (0x{:X}): {}", severity, finding.offset, finding.element, finding.message);
+    }
+
+    if findings.iter().any(|finding| finding.severity == Severity::Error) {
+        ::std::process::exit(1);
+    }
+}
@@ -0,0 +1,23 @@
+//! Usage example of the mkv-rs library. This program prints the DASH manifest of the specified
+//! WebM files, each of which holds a single audio or video track.
+//! Usage: ./mkvdash <file>...
+
+extern crate mkv;
+
+use std::fs::File;
+use std::io::BufReader;
+use mkv::dash::{self, Representation};
+
+fn main() {
+    let paths: Vec<String> = ::std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        panic!("Please specify one or more filenames");
+    }
+
+    let representations: Vec<Representation> = paths.iter().map(|path| {
+        let file = BufReader::new(File::open(path).unwrap());
+        Representation::read(file, path).unwrap()
+    }).collect();
+
+    print!("{}", dash::manifest(&representations).unwrap());
+}
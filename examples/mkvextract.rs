@@ -0,0 +1,23 @@
+//! Usage example of the mkv-rs library. This program extracts the elementary stream of a track of
+//! the specified matroska video file into a raw file.
+//! Usage: ./mkvextract <file> <track number> <output file>
+
+extern crate mkv;
+
+use std::fs::File;
+use mkv::reader::Reader;
+
+fn main() {
+    let mut args = ::std::env::args().skip(1);
+
+    let path = args.next().expect("Please specify a filename");
+    let track = args.next().expect("Please specify a track number");
+    let output = args.next().expect("Please specify an output filename");
+
+    let track = track.parse::<u64>().expect("Invalid track number");
+
+    let mut video = Reader::new(File::open(path).unwrap()).unwrap();
+    let output = File::create(output).unwrap();
+
+    mkv::extract::extract(&mut video, track, output).unwrap();
+}
@@ -1,40 +1,81 @@
 //! Usage example of the mkv-rs library. This program opens and parses the specified matroska video
 //! file and displays the relevant information.
-//! Usage: ./mkvinfo <file>
+//! With `--tree`, all the elements of the file are displayed with their positions instead.
+//! With `--json`, the information is printed in the JSON format of `ffprobe -print_format json
+//! -show_format -show_streams -show_chapters`, and `--packets` adds the list of packets. This mode
+//! requires the `serde` feature.
+//! Usage: ./mkvinfo [--tree | --json [--packets]] <file>
 
 extern crate mkv;
 
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_json;
+
 use std::fs::File;
+use std::io::BufReader;
 use mkv::reader::{Reader, Info};
+use mkv::reader::tree::{self, Node, Value};
 
 fn main() {
-    let path = ::std::env::args().nth(1).expect("Please specify a filename");
-    let file = File::open(path).unwrap();
+    let mut args: Vec<String> = ::std::env::args().skip(1).collect();
+
+    let tree_mode = args.iter().any(|arg| arg == "--tree");
+    let json_mode = args.iter().any(|arg| arg == "--json");
+    let packets = args.iter().any(|arg| arg == "--packets");
+    args.retain(|arg| !arg.starts_with("--"));
+
+    let path = args.into_iter().next().expect("Please specify a filename");
+    let file = File::open(&path).unwrap();
+
+    if json_mode {
+        json::print(&path, file, packets);
+        return;
+    }
+
+    if tree_mode {
+        let tree = tree::read(BufReader::new(file));
+        print_tree(&tree.elements, 0);
+
+        if let Some((position, error)) = tree.error {
+            println!("Error at {}: {}", position, error);
+        }
+
+        return;
+    }
 
     let mut video = Reader::new(file).unwrap();
 
-    println!("Document Type: {}", video.header().doc_type());
-    println!("Document Type Version: {}", video.header().doc_type_version());
-    println!("Document Type Read Version: {}", video.header().doc_type_read_version());
+    // A file may contain several segments, each with its own EBML header.
+    loop {
+        println!("Segment n°{}", video.segment_index());
+        println!("Document Type: {}", video.header().doc_type());
+        println!("Document Type Version: {}", video.header().doc_type_version());
+        println!("Document Type Read Version: {}", video.header().doc_type_read_version());
 
-    // Read metadata: all the information that preceed the actual data blocks.
-    print_info(video.info().unwrap());
+        // Read metadata: all the information that preceed the actual data blocks.
+        print_info(video.info().unwrap());
 
-    // Read all the data blocks in the file.
-    while let Some(mut cluster) = video.next_cluster().unwrap() {
-        for block in cluster.blocks() {
-            let block = block.unwrap();
+        // Read all the data blocks in the segment.
+        while let Some(mut cluster) = video.next_cluster().unwrap() {
+            for block in cluster.blocks() {
+                let block = block.unwrap();
 
-            println!("Block: Track n°{} - Timecode {} - {} bytes", block.track(), block.timecode(), block.size());
+                println!("Block: Track n°{} - Timecode {} - {} bytes", block.track(), block.timecode(), block.size());
 
-            for frame in block.frames().unwrap() {
-                println!("Frame of size {}", frame.len());
+                for frame in block.frames().unwrap() {
+                    println!("Frame of size {}", frame.len());
+                }
             }
         }
-    }
 
-    // Read the information that is located after the clusters.
-    print_info(video.info().unwrap());
+        // Read the information that is located after the clusters.
+        print_info(video.info().unwrap());
+
+        if !video.next_segment().unwrap() {
+            break;
+        }
+    }
 }
 
 /// Prints matroska metadata to stdout.
@@ -54,6 +95,365 @@ fn print_info(infos: Vec<Info>) {
                     println!("Track n°{} - Codec: {}", track.index(), track.codec());
                 }
             },
+
+            Info::Chapters(ref editions) => {
+                for edition in editions {
+                    for atom in edition.atoms() {
+                        let name = atom.displays().first().map_or("", |display| display.string());
+                        println!("Chapter at {} ns: {}", atom.time_start(), name);
+                    }
+                }
+            },
+
+            Info::Tags(ref tags) => {
+                for tag in tags {
+                    for simple in &tag.simple_tags {
+                        println!("Tag: {} = {}", simple.name, simple.string.as_ref().map_or("", |s| s.as_str()));
+                    }
+                }
+            },
+
+            Info::Attachments(ref files) => {
+                for file in files {
+                    println!("Attachment: {} ({}, {} bytes)", file.name, file.mime_type, file.data.len());
+                }
+            },
+
+            Info::Cues(ref cues) => println!("Cues: {} points", cues.len()),
+        };
+    }
+}
+
+/// Prints elements and their children recursively to stdout.
+fn print_tree(nodes: &[Node], depth: usize) {
+    for node in nodes {
+        let size = node.size.map_or(String::from("unknown"), |size| size.to_string());
+        let value = match node.value {
+            Value::Master(_) => String::new(),
+            Value::Unsigned(value) => format!(": {}", value),
+            Value::Signed(value) => format!(": {}", value),
+            Value::Float(value) => format!(": {}", value),
+            Value::String(ref value) => format!(": {}", value),
+            Value::Date(value) => format!(": {} ns since 2001", value),
+            Value::Binary(ref data) => {
+                let hex: Vec<String> = data.iter().map(|byte| format!("{:02x}", byte)).collect();
+                format!(": {}", hex.join(" "))
+            },
+        };
+
+        println!("{}+ {} (0x{:X}) at {}, size {}{}", "|  ".repeat(depth), node.name(), node.id, node.offset, size, value);
+        print_tree(node.children(), depth + 1);
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+mod json {
+    use std::fs::File;
+
+    pub fn print(_: &str, _: File, _: bool) {
+        eprintln!("JSON output requires the serde feature: run with --features serde");
+        ::std::process::exit(1);
+    }
+}
+
+#[cfg(feature = "serde")]
+mod json {
+    use std::collections::HashMap;
+    use std::fs::File;
+
+    use serde_json::{Map, Value};
+
+    use mkv::reader::{chapter, segment, tag, track, Info, Reader};
+
+    /// Prints the information of the file in the JSON format of ffprobe.
+    pub fn print(path: &str, file: File, show_packets: bool) {
+        let size = file.metadata().unwrap().len();
+        let mut video = Reader::new(file).unwrap();
+
+        let mut segment = None;
+        let mut tracks = Vec::new();
+        let mut editions = Vec::new();
+        let mut tags = Vec::new();
+
+        let mut packets = Vec::new();
+        let mut start_pts: HashMap<u64, i64> = HashMap::new();
+        let mut end_pts = 0;
+
+        // Metadata can be located before and after the clusters.
+        loop {
+            for info in video.info().unwrap() {
+                match info {
+                    Info::Segment(info) => segment = Some(info),
+                    Info::Tracks(info) => tracks = info,
+                    Info::Chapters(info) => if editions.is_empty() {
+                        editions = info;
+                    },
+                    Info::Tags(info) => tags.extend(info),
+                    _ => {},
+                };
+            }
+
+            let scale = segment.as_ref().expect("Missing segment information").timecode_scale();
+            let base = video.segment_offset() as u64;
+            let mut clusters = 0;
+
+            while let Some(mut cluster) = video.next_cluster().unwrap() {
+                clusters += 1;
+
+                for block in cluster.blocks() {
+                    let block = block.unwrap();
+
+                    let (index, track) = match tracks.iter().enumerate().find(|&(_, track)| track.index() == block.track()) {
+                        Some(found) => found,
+                        None => continue,
+                    };
+
+                    let pts = block.absolute_timecode();
+                    let default_duration = track.default_duration().map(|duration| duration / scale);
+                    let duration = block.duration().or(default_duration);
+
+                    start_pts.entry(track.index()).or_insert(pts);
+                    end_pts = end_pts.max(pts + duration.unwrap_or(0) as i64);
+
+                    if !show_packets {
+                        continue;
+                    }
+
+                    let position = base + block.position() as u64;
+                    let flags = if block.keyframe() { "K__" } else { "___" };
+
+                    // Laced frames are separate packets, spaced by the default duration.
+                    for (i, frame) in block.frames().unwrap().into_iter().enumerate() {
+                        let pts = pts + i as i64 * default_duration.unwrap_or(0) as i64;
+                        let mut packet = json!({
+                            "codec_type": codec_type(track),
+                            "stream_index": index,
+                            "pts": pts,
+                            "pts_time": time(pts, scale),
+                            "dts": pts,
+                            "dts_time": time(pts, scale),
+                            "size": frame.len().to_string(),
+                            "pos": position.to_string(),
+                            "flags": flags,
+                        });
+
+                        if let Some(duration) = duration {
+                            packet["duration"] = json!(duration);
+                            packet["duration_time"] = json!(time(duration as i64, scale));
+                        }
+
+                        packets.push(packet);
+                    }
+                }
+            }
+
+            if clusters == 0 {
+                break;
+            }
+        }
+
+        let segment = segment.unwrap();
+        let scale = segment.timecode_scale();
+        let duration = segment.duration().map_or(end_pts as f64, |duration| duration) * scale as f64 / 1e9;
+
+        let mut output = Map::new();
+
+        if show_packets {
+            output.insert("packets".to_string(), Value::Array(packets));
+        }
+
+        let streams = tracks.iter().enumerate().map(|(index, track)| stream(index, track, &segment, &tags, &start_pts)).collect();
+        output.insert("streams".to_string(), Value::Array(streams));
+
+        output.insert("chapters".to_string(), Value::Array(chapters(&editions, &tags, duration)));
+
+        let mut format_tags = simple_tags(&tags, |targets| {
+            targets.track_uids.is_empty() && targets.edition_uids.is_empty()
+                && targets.chapter_uids.is_empty() && targets.attachment_uids.is_empty()
+        });
+
+        if let Some(title) = segment.title() {
+            format_tags.insert("title".to_string(), json!(title));
+        }
+
+        if let Some(app) = segment.muxing_app() {
+            format_tags.insert("encoder".to_string(), json!(app));
+        }
+
+        let bit_rate = if duration > 0.0 { (size as f64 * 8.0 / duration) as u64 } else { 0 };
+
+        output.insert("format".to_string(), json!({
+            "filename": path,
+            "nb_streams": tracks.len(),
+            "nb_programs": 0,
+            "format_name": "matroska,webm",
+            "format_long_name": "Matroska / WebM",
+            "start_time": time(start_pts.values().cloned().min().unwrap_or(0), scale),
+            "duration": format!("{:.6}", duration),
+            "size": size.to_string(),
+            "bit_rate": bit_rate.to_string(),
+            "tags": format_tags,
+        }));
+
+        println!("{}", ::serde_json::to_string_pretty(&Value::Object(output)).unwrap());
+    }
+
+    /// Describes a track as an ffprobe stream.
+    fn stream(index: usize, track: &track::Info, segment: &segment::Info, tags: &[tag::Tag], start_pts: &HashMap<u64, i64>) -> Value {
+        let scale = segment.timecode_scale();
+        let start = start_pts.get(&track.index()).cloned().unwrap_or(0);
+
+        let mut stream = json!({
+            "index": index,
+            "codec_name": codec_name(track.codec()),
+            "codec_type": codec_type(track),
+            "codec_tag_string": "[0][0][0][0]",
+            "codec_tag": "0x0000",
+            "time_base": ratio(scale, 1_000_000_000),
+            "start_pts": start,
+            "start_time": time(start, scale),
+            "disposition": {
+                "default": track.default() as u8,
+                "forced": track.forced() as u8,
+            },
+        });
+
+        match *track.kind() {
+            track::Kind::Video(ref video) => {
+                stream["width"] = json!(video.width());
+                stream["height"] = json!(video.height());
+
+                if let Some(duration) = track.default_duration() {
+                    stream["r_frame_rate"] = json!(ratio(1_000_000_000, duration));
+                    stream["avg_frame_rate"] = json!(ratio(1_000_000_000, duration));
+                }
+            },
+
+            track::Kind::Audio(ref audio) => {
+                stream["sample_rate"] = json!((audio.sampling_frequency() as u64).to_string());
+                stream["channels"] = json!(audio.channels());
+
+                if let Some(bit_depth) = audio.bit_depth() {
+                    stream["bits_per_raw_sample"] = json!(bit_depth.to_string());
+                }
+            },
+
+            _ => {},
+        };
+
+        let mut stream_tags = simple_tags(tags, |targets| targets.track_uids.contains(&track.uid()));
+
+        if track.language() != "und" {
+            stream_tags.insert("language".to_string(), json!(track.language()));
+        }
+
+        if let Some(name) = track.name() {
+            stream_tags.insert("title".to_string(), json!(name));
+        }
+
+        stream["tags"] = Value::Object(stream_tags);
+        stream
+    }
+
+    /// Describes the chapters of the default edition as ffprobe chapters. Chapters without an end
+    /// last until the next one, or until the end of the file.
+    fn chapters(editions: &[chapter::Edition], tags: &[tag::Tag], duration: f64) -> Vec<Value> {
+        let edition = match editions.iter().find(|edition| edition.default()).or(editions.first()) {
+            Some(edition) => edition,
+            None => return Vec::new(),
         };
+
+        let atoms: Vec<&chapter::Atom> = edition.atoms().iter().filter(|atom| atom.enabled()).collect();
+        let duration = (duration * 1e9) as u64;
+
+        atoms.iter().enumerate().map(|(i, atom)| {
+            let end = atom.time_end()
+                .or(atoms.get(i + 1).map(|next| next.time_start()))
+                .unwrap_or(duration.max(atom.time_start()));
+
+            let mut chapter_tags = simple_tags(tags, |targets| targets.chapter_uids.contains(&atom.uid()));
+
+            if let Some(display) = atom.displays().first() {
+                chapter_tags.insert("title".to_string(), json!(display.string()));
+            }
+
+            json!({
+                "id": atom.uid(),
+                "time_base": "1/1000000000",
+                "start": atom.time_start(),
+                "start_time": time(atom.time_start() as i64, 1),
+                "end": end,
+                "end_time": time(end as i64, 1),
+                "tags": chapter_tags,
+            })
+        }).collect()
+    }
+
+    /// Collects the string values of the tags whose targets match `filter`.
+    fn simple_tags<F: Fn(&tag::Targets) -> bool>(tags: &[tag::Tag], filter: F) -> Map<String, Value> {
+        let mut values = Map::new();
+
+        for tag in tags.iter().filter(|tag| filter(&tag.targets)) {
+            for simple in &tag.simple_tags {
+                if let Some(ref string) = simple.string {
+                    values.insert(simple.name.clone(), json!(string));
+                }
+            }
+        }
+
+        values
+    }
+
+    /// Name given by FFmpeg to the codec of a matroska codec ID.
+    fn codec_name(codec: &str) -> String {
+        let name = match codec {
+            "V_MPEG4/ISO/AVC" => "h264",
+            "V_MPEGH/ISO/HEVC" => "hevc",
+            "V_AV1" => "av1",
+            "V_VP8" => "vp8",
+            "V_VP9" => "vp9",
+            "A_OPUS" => "opus",
+            "A_VORBIS" => "vorbis",
+            "A_FLAC" => "flac",
+            "A_AC3" => "ac3",
+            "A_EAC3" => "eac3",
+            "A_MPEG/L3" => "mp3",
+            "S_TEXT/UTF8" => "subrip",
+            "S_TEXT/ASS" => "ass",
+            "S_TEXT/SSA" => "ssa",
+            "S_TEXT/WEBVTT" => "webvtt",
+            "S_HDMV/PGS" => "hdmv_pgs_subtitle",
+            codec if codec.starts_with("A_AAC") => "aac",
+            codec => return codec.to_lowercase(),
+        };
+
+        name.to_string()
+    }
+
+    fn codec_type(track: &track::Info) -> &'static str {
+        match *track.kind() {
+            track::Kind::Video(_) => "video",
+            track::Kind::Audio(_) => "audio",
+            track::Kind::Subtitle => "subtitle",
+            _ => "data",
+        }
+    }
+
+    /// Formats a timestamp in segment ticks as seconds.
+    fn time(ticks: i64, scale: u64) -> String {
+        format!("{:.6}", ticks as f64 * scale as f64 / 1e9)
+    }
+
+    /// Formats a reduced fraction.
+    fn ratio(num: u64, den: u64) -> String {
+        let (mut a, mut b) = (num, den);
+        while b != 0 {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+
+        let gcd = a.max(1);
+        format!("{}/{}", num / gcd, den / gcd)
     }
 }
@@ -0,0 +1,49 @@
+//! Usage example of the mkv-rs library. This program rewrites a damaged matroska video file, such
+//! as a recording from a crashed encoder, into a clean and seekable one.
+//! Usage: ./mkvrepair <file> <output file>
+
+extern crate mkv;
+
+use std::fs::File;
+use mkv::reader::Reader;
+
+fn main() {
+    let mut args = ::std::env::args().skip(1);
+
+    let path = args.next().expect("Please specify a filename");
+    let output = args.next().expect("Please specify an output filename");
+
+    let mut video = Reader::new(File::open(path).unwrap()).unwrap();
+    let output = File::create(output).unwrap();
+
+    let (_, report) = mkv::repair::repair(&mut video, output).unwrap();
+
+    if report.clean() {
+        println!("No damage found, the cues and seek head were rebuilt");
+    }
+
+    if report.declared_size != report.actual_size {
+        println!("Segment size: {} bytes declared, {} bytes found", report.declared_size, report.actual_size);
+    }
+
+    if report.declared_duration != Some(report.duration) {
+        println!("Duration: {:?} declared, {} computed", report.declared_duration, report.duration);
+    }
+
+    for event in &report.resyncs {
+        match event.resumed {
+            Some(position) => println!("Skipped damaged data from {} to {}: {}", event.position, position, event.error),
+            None => println!("Dropped damaged data from {} to the end: {}", event.position, event.error),
+        };
+    }
+
+    if report.orphan_blocks > 0 {
+        println!("Dropped {} blocks of unknown tracks", report.orphan_blocks);
+    }
+
+    for error in &report.dropped_elements {
+        println!("Dropped unreadable metadata: {}", error);
+    }
+
+    println!("Copied {} blocks", report.blocks);
+}
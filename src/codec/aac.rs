@@ -0,0 +1,194 @@
+//! AAC audio configuration, for the `A_AAC` codecs.
+
+use elements as el;
+use error::{self, Result};
+
+use super::bits::BitReader;
+
+/// Sampling frequencies indexed by the AAC sampling frequency index.
+pub const SAMPLING_FREQUENCIES: [u32; 13] = [
+    96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350,
+];
+
+const OBJECT_TYPE_SBR: u8 = 5;
+const OBJECT_TYPE_PS: u8 = 29;
+
+/// Parsed `AudioSpecificConfig`, stored in the codec private data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AudioSpecificConfig {
+    /// Object type of the core codec, 2 for AAC LC.
+    pub object_type: u8,
+
+    /// Sampling frequency of the core codec, and its index if it has one.
+    pub sample_rate: u32,
+    pub sampling_frequency_index: Option<u8>,
+
+    /// Channel configuration, 0 if it is defined in the bitstream.
+    pub channel_config: u8,
+
+    /// Whether spectral band replication or parametric stereo are explicitly signaled. The
+    /// extension sampling frequency is the output sampling frequency when SBR is used.
+    pub sbr: bool,
+    pub ps: bool,
+    pub extension_sample_rate: Option<u32>,
+}
+
+impl AudioSpecificConfig {
+    /// Sampling frequency of the decoded audio.
+    pub fn output_sample_rate(&self) -> u32 {
+        self.extension_sample_rate.unwrap_or(self.sample_rate)
+    }
+
+    /// Number of decoded channels, if it is defined by the channel configuration.
+    pub fn channels(&self) -> Option<u8> {
+        match self.channel_config {
+            1 if self.ps => Some(2),
+            1..=6 => Some(self.channel_config),
+            7 | 12 | 14 => Some(8),
+            11 => Some(7),
+            13 => Some(24),
+            _ => None,
+        }
+    }
+}
+
+/// Parse an `AudioSpecificConfig`.
+pub fn parse_config(data: &[u8]) -> Result<AudioSpecificConfig> {
+    let mut r = BitReader::new(data);
+
+    let mut object_type = read_object_type(&mut r)?;
+    let (sample_rate, sampling_frequency_index) = read_sample_rate(&mut r)?;
+    let channel_config = r.read(4)? as u8;
+
+    let mut sbr = false;
+    let mut ps = false;
+    let mut extension_sample_rate = None;
+
+    if object_type == OBJECT_TYPE_SBR || object_type == OBJECT_TYPE_PS {
+        // Explicit hierarchical signaling: the extension is followed by the core object type.
+        sbr = true;
+        ps = object_type == OBJECT_TYPE_PS;
+        extension_sample_rate = Some(read_sample_rate(&mut r)?.0);
+        object_type = read_object_type(&mut r)?;
+    } else if skip_ga_specific_config(&mut r, object_type, channel_config)? && r.bits_left() >= 16 {
+        // Explicit backward compatible signaling, following the core configuration.
+        if r.read(11)? == 0x2B7 && read_object_type(&mut r)? == OBJECT_TYPE_SBR {
+            sbr = r.read_flag()?;
+
+            if sbr {
+                extension_sample_rate = Some(read_sample_rate(&mut r)?.0);
+
+                if r.bits_left() >= 12 && r.read(11)? == 0x548 {
+                    ps = r.read_flag()?;
+                }
+            }
+        }
+    }
+
+    Ok(AudioSpecificConfig {
+        object_type: object_type,
+        sample_rate: sample_rate,
+        sampling_frequency_index: sampling_frequency_index,
+        channel_config: channel_config,
+        sbr: sbr,
+        ps: ps,
+        extension_sample_rate: extension_sample_rate,
+    })
+}
+
+/// Build the configuration signaled by a legacy codec ID such as `A_AAC/MPEG4/LC/SBR`, for a
+/// track without codec private data, from its sampling frequency and number of channels. Returns
+/// `None` if the codec ID does not name a profile.
+pub fn legacy_config(codec: &str, sample_rate: u32, channels: u8) -> Option<AudioSpecificConfig> {
+    let mut parts = codec.split('/');
+
+    if parts.next() != Some("A_AAC") {
+        return None;
+    }
+
+    let object_type = match (parts.next(), parts.next()) {
+        (Some("MPEG2"), Some("MAIN")) | (Some("MPEG4"), Some("MAIN")) => 1,
+        (Some("MPEG2"), Some("LC")) | (Some("MPEG4"), Some("LC")) => 2,
+        (Some("MPEG2"), Some("SSR")) | (Some("MPEG4"), Some("SSR")) => 3,
+        (Some("MPEG4"), Some("LTP")) => 4,
+        _ => return None,
+    };
+
+    // The sampling frequency of the track is the one of the core codec, which spectral band
+    // replication doubles.
+    let sbr = parts.next() == Some("SBR");
+
+    Some(AudioSpecificConfig {
+        object_type: object_type,
+        sample_rate: sample_rate,
+        sampling_frequency_index: SAMPLING_FREQUENCIES.iter().position(|f| *f == sample_rate).map(|i| i as u8),
+        channel_config: match channels {
+            1..=6 => channels,
+            8 => 7,
+            _ => 0,
+        },
+        sbr: sbr,
+        ps: false,
+        extension_sample_rate: if sbr { Some(sample_rate * 2) } else { None },
+    })
+}
+
+fn read_object_type(r: &mut BitReader) -> Result<u8> {
+    Ok(match r.read(5)? as u8 {
+        31 => 32 + r.read(6)? as u8,
+        object_type => object_type,
+    })
+}
+
+fn read_sample_rate(r: &mut BitReader) -> Result<(u32, Option<u8>)> {
+    let index = r.read(4)? as u8;
+
+    match index {
+        15 => Ok((r.read(24)? as u32, None)),
+        _ => match SAMPLING_FREQUENCIES.get(index as usize) {
+            Some(rate) => Ok((*rate, Some(index))),
+            None => bail!(error::invalid_value(el::CODEC_PRIVATE, format!("AAC sampling frequency index {}", index))),
+        },
+    }
+}
+
+/// Skip the `GASpecificConfig` of the general audio object types. Returns `false` if the object
+/// type is not one of them or if the configuration cannot be skipped.
+fn skip_ga_specific_config(r: &mut BitReader, object_type: u8, channel_config: u8) -> Result<bool> {
+    match object_type {
+        1..=4 | 6 | 7 | 17 | 19..=23 => {},
+        _ => return Ok(false),
+    };
+
+    // Frame length flag, then core coder delay.
+    r.skip(1)?;
+    if r.read_flag()? {
+        r.skip(14)?;
+    }
+
+    let extension = r.read_flag()?;
+
+    // The program config element describing the channels is not supported.
+    if channel_config == 0 {
+        return Ok(false);
+    }
+
+    if object_type == 6 || object_type == 20 {
+        r.skip(3)?;
+    }
+
+    if extension {
+        if object_type == 22 {
+            r.skip(16)?;
+        }
+
+        if let 17 | 19 | 20 | 23 = object_type {
+            r.skip(3)?;
+        }
+
+        r.skip(1)?;
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,87 @@
+//! AC-3 and E-AC-3 frame headers, for the `A_AC3` and `A_EAC3` codecs, which have no codec
+//! private data.
+
+use elements as el;
+use error::{self, Result};
+
+use super::bits::BitReader;
+
+const SAMPLE_RATES: [u32; 3] = [48000, 44100, 32000];
+const REDUCED_SAMPLE_RATES: [u32; 3] = [24000, 22050, 16000];
+
+/// Number of full bandwidth channels indexed by the audio coding mode.
+const CHANNELS: [u8; 8] = [2, 1, 2, 3, 3, 4, 4, 5];
+
+/// Summary of the header of a synchronization frame.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameHeader {
+    /// Bitstream identification: up to 10 for AC-3, 16 for E-AC-3.
+    pub bsid: u8,
+    pub sample_rate: u32,
+
+    /// Number of channels, including the low frequency effects channel.
+    pub channels: u8,
+    pub lfe: bool,
+}
+
+/// Parse the header of an AC-3 or E-AC-3 synchronization frame.
+pub fn parse_frame_header(frame: &[u8]) -> Result<FrameHeader> {
+    if frame.len() < 8 {
+        bail!(error::unexpected_eof());
+    }
+
+    if frame[0] != 0x0B || frame[1] != 0x77 {
+        bail!(error::invalid_value(el::BLOCK, "invalid AC-3 sync word"));
+    }
+
+    let bsid = frame[5] >> 3;
+    let mut r = BitReader::new(&frame[2..]);
+
+    let (sample_rate, acmod, lfe) = if bsid <= 10 {
+        // CRC, then sample rate and frame size codes.
+        r.skip(16)?;
+        let fscod = r.read(2)? as usize;
+        r.skip(6 + 5 + 3)?;
+
+        let acmod = r.read(3)? as usize;
+
+        // Center, surround and Dolby surround mix levels.
+        if acmod & 1 != 0 && acmod != 1 {
+            r.skip(2)?;
+        }
+
+        if acmod & 4 != 0 {
+            r.skip(2)?;
+        }
+
+        if acmod == 2 {
+            r.skip(2)?;
+        }
+
+        (SAMPLE_RATES.get(fscod).cloned(), acmod, r.read_flag()?)
+    } else if bsid <= 16 {
+        // Stream type, substream ID and frame size.
+        r.skip(2 + 3 + 11)?;
+        let fscod = r.read(2)? as usize;
+
+        let sample_rate = if fscod == 3 {
+            REDUCED_SAMPLE_RATES.get(r.read(2)? as usize).cloned()
+        } else {
+            r.skip(2)?;
+            SAMPLE_RATES.get(fscod).cloned()
+        };
+
+        let acmod = r.read(3)? as usize;
+        (sample_rate, acmod, r.read_flag()?)
+    } else {
+        bail!(error::invalid_value(el::BLOCK, format!("AC-3 bitstream ID {}", bsid)));
+    };
+
+    Ok(FrameHeader {
+        bsid: bsid,
+        sample_rate: sample_rate.ok_or(error::invalid_value(el::BLOCK, "invalid AC-3 sample rate"))?,
+        channels: CHANNELS[acmod] + lfe as u8,
+        lfe: lfe,
+    })
+}
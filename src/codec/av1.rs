@@ -0,0 +1,272 @@
+//! AV1 codec configuration, for the `V_AV1` codec.
+
+use elements as el;
+use error::{self, Result};
+
+use super::bits::BitReader;
+use super::Colour;
+
+pub const OBU_SEQUENCE_HEADER: u8 = 1;
+
+/// Parsed `AV1CodecConfigurationRecord`, stored in the codec private data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecoderConfig {
+    pub profile: u8,
+    pub level: u8,
+    pub tier: bool,
+    pub high_bitdepth: bool,
+    pub twelve_bit: bool,
+    pub monochrome: bool,
+    pub subsampling_x: bool,
+    pub subsampling_y: bool,
+    pub chroma_sample_position: u8,
+
+    /// Optional. Number of decoded frames to buffer before presenting the first one.
+    pub initial_presentation_delay: Option<u8>,
+
+    /// OBUs following the record, which contain at most one sequence header.
+    pub config_obus: Vec<u8>,
+    pub sequence_header: Option<SequenceHeader>,
+}
+
+impl DecoderConfig {
+    /// Number of bits per sample.
+    pub fn bit_depth(&self) -> u8 {
+        match (self.high_bitdepth, self.twelve_bit) {
+            (true, true) => 12,
+            (true, false) => 10,
+            _ => 8,
+        }
+    }
+}
+
+/// Summary of a sequence header OBU.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SequenceHeader {
+    pub profile: u8,
+    pub still_picture: bool,
+
+    /// Level and tier of the first operating point.
+    pub level: u8,
+    pub tier: bool,
+
+    /// Maximum dimensions of the frames.
+    pub max_width: u64,
+    pub max_height: u64,
+
+    pub bit_depth: u8,
+    pub monochrome: bool,
+    pub subsampling_x: bool,
+    pub subsampling_y: bool,
+    pub colour: Colour,
+}
+
+/// Parse an `AV1CodecConfigurationRecord`.
+pub fn parse_config(data: &[u8]) -> Result<DecoderConfig> {
+    if data.len() < 4 || data[0] != 0x81 {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "invalid AV1 configuration"));
+    }
+
+    let config_obus = data[4..].to_vec();
+    let mut sequence_header = None;
+
+    for (obu_type, payload) in parse_obus(&config_obus)? {
+        if obu_type == OBU_SEQUENCE_HEADER {
+            sequence_header = Some(parse_sequence_header(payload)?);
+        }
+    }
+
+    Ok(DecoderConfig {
+        profile: data[1] >> 5,
+        level: data[1] & 0x1F,
+        tier: data[2] & 0x80 != 0,
+        high_bitdepth: data[2] & 0x40 != 0,
+        twelve_bit: data[2] & 0x20 != 0,
+        monochrome: data[2] & 0x10 != 0,
+        subsampling_x: data[2] & 0x08 != 0,
+        subsampling_y: data[2] & 0x04 != 0,
+        chroma_sample_position: data[2] & 0x03,
+        initial_presentation_delay: if data[3] & 0x10 != 0 { Some((data[3] & 0x0F) + 1) } else { None },
+        config_obus: config_obus,
+        sequence_header: sequence_header,
+    })
+}
+
+/// Split a sequence of low overhead bitstream format OBUs into their types and payloads.
+pub fn parse_obus(data: &[u8]) -> Result<Vec<(u8, &[u8])>> {
+    let mut obus = Vec::new();
+    let mut r = BitReader::new(data);
+
+    while !r.remaining().is_empty() {
+        r.skip(1)?;
+        let obu_type = r.read(4)? as u8;
+        let extension = r.read_flag()?;
+        let has_size = r.read_flag()?;
+        r.skip(1)?;
+
+        if extension {
+            r.skip(8)?;
+        }
+
+        let rest = r.remaining();
+        let size = if has_size { r.read_leb128()? as usize } else { rest.len() };
+
+        let payload = r.remaining();
+        if size > payload.len() {
+            bail!(error::unexpected_eof());
+        }
+
+        obus.push((obu_type, &payload[..size]));
+        r.skip(size * 8)?;
+    }
+
+    Ok(obus)
+}
+
+/// Parse the payload of a sequence header OBU.
+pub fn parse_sequence_header(data: &[u8]) -> Result<SequenceHeader> {
+    let mut r = BitReader::new(data);
+
+    let profile = r.read(3)? as u8;
+    let still_picture = r.read_flag()?;
+    let reduced = r.read_flag()?;
+
+    let mut level = 0;
+    let mut tier = false;
+
+    if reduced {
+        level = r.read(5)? as u8;
+    } else {
+        let mut buffer_delay_bits = None;
+
+        if r.read_flag()? {
+            // Timing information.
+            r.skip(64)?;
+            if r.read_flag()? {
+                r.read_uvlc()?;
+            }
+
+            // Decoder model information.
+            if r.read_flag()? {
+                buffer_delay_bits = Some(r.read(5)? as usize + 1);
+                r.skip(42)?;
+            }
+        }
+
+        let initial_display_delay = r.read_flag()?;
+
+        for i in 0..r.read(5)? + 1 {
+            r.skip(12)?;
+            let op_level = r.read(5)? as u8;
+            let op_tier = op_level > 7 && r.read_flag()?;
+
+            if i == 0 {
+                level = op_level;
+                tier = op_tier;
+            }
+
+            if let Some(bits) = buffer_delay_bits {
+                if r.read_flag()? {
+                    r.skip(2 * bits + 1)?;
+                }
+            }
+
+            if initial_display_delay && r.read_flag()? {
+                r.skip(4)?;
+            }
+        }
+    }
+
+    let width_bits = r.read(4)? as usize + 1;
+    let height_bits = r.read(4)? as usize + 1;
+    let max_width = r.read(width_bits)? + 1;
+    let max_height = r.read(height_bits)? + 1;
+
+    if !reduced && r.read_flag()? {
+        r.skip(7)?;
+    }
+
+    // 128x128 superblocks, filter intra and intra edge filter flags.
+    r.skip(3)?;
+
+    if !reduced {
+        // Inter-intra, masked compound, warped motion and dual filter flags.
+        r.skip(4)?;
+
+        let order_hint = r.read_flag()?;
+        if order_hint {
+            r.skip(2)?;
+        }
+
+        let force_screen_content_tools = if r.read_flag()? { 2 } else { r.read(1)? };
+        if force_screen_content_tools > 0 && !r.read_flag()? {
+            r.skip(1)?;
+        }
+
+        if order_hint {
+            r.skip(3)?;
+        }
+    }
+
+    // Super-resolution, CDEF and loop restoration flags.
+    r.skip(3)?;
+
+    let high_bitdepth = r.read_flag()?;
+    let bit_depth = match (profile, high_bitdepth) {
+        (2, true) => if r.read_flag()? { 12 } else { 10 },
+        (_, true) => 10,
+        _ => 8,
+    };
+
+    let monochrome = profile != 1 && r.read_flag()?;
+
+    let mut colour = Colour {
+        full_range: false,
+        primaries: 2,
+        transfer_characteristics: 2,
+        matrix_coefficients: 2,
+    };
+
+    if r.read_flag()? {
+        colour.primaries = r.read(8)? as u8;
+        colour.transfer_characteristics = r.read(8)? as u8;
+        colour.matrix_coefficients = r.read(8)? as u8;
+    }
+
+    let (subsampling_x, subsampling_y) = if monochrome {
+        colour.full_range = r.read_flag()?;
+        (true, true)
+    } else if (colour.primaries, colour.transfer_characteristics, colour.matrix_coefficients) == (1, 13, 0) {
+        // sRGB.
+        colour.full_range = true;
+        (false, false)
+    } else {
+        colour.full_range = r.read_flag()?;
+
+        match profile {
+            0 => (true, true),
+            1 => (false, false),
+            _ if bit_depth == 12 => {
+                let x = r.read_flag()?;
+                (x, x && r.read_flag()?)
+            },
+            _ => (true, false),
+        }
+    };
+
+    Ok(SequenceHeader {
+        profile: profile,
+        still_picture: still_picture,
+        level: level,
+        tier: tier,
+        max_width: max_width,
+        max_height: max_height,
+        bit_depth: bit_depth,
+        monochrome: monochrome,
+        subsampling_x: subsampling_x,
+        subsampling_y: subsampling_y,
+        colour: colour,
+    })
+}
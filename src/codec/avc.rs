@@ -0,0 +1,229 @@
+//! AVC (H.264) decoder configuration, for the `V_MPEG4/ISO/AVC` codec.
+
+use elements as el;
+use error::{self, Result};
+
+use super::bits::{self, BitReader};
+use super::{read_vui_colour, Colour};
+
+/// Parsed `AVCDecoderConfigurationRecord`, stored in the codec private data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecoderConfig {
+    pub profile: u8,
+    pub profile_compatibility: u8,
+    pub level: u8,
+
+    /// Size in bytes of the length prefix of each NAL unit in the blocks.
+    pub nal_length_size: u8,
+
+    /// Sequence and picture parameter set NAL units.
+    pub sps: Vec<Vec<u8>>,
+    pub pps: Vec<Vec<u8>>,
+}
+
+impl DecoderConfig {
+    /// Parse the summary of the first sequence parameter set.
+    pub fn parse_sps(&self) -> Result<Sps> {
+        let sps = self.sps.first().ok_or(error::invalid_value(el::CODEC_PRIVATE, "no SPS"))?;
+        parse_sps(sps)
+    }
+}
+
+/// Summary of a sequence parameter set.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sps {
+    pub profile: u8,
+    pub level: u8,
+
+    /// 0 for monochrome, 1 for 4:2:0, 2 for 4:2:2 and 3 for 4:4:4.
+    pub chroma_format: u8,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+
+    /// Dimensions of the decoded pictures, after cropping.
+    pub width: u64,
+    pub height: u64,
+
+    pub colour: Option<Colour>,
+}
+
+/// Parse an `AVCDecoderConfigurationRecord`.
+pub fn parse_config(data: &[u8]) -> Result<DecoderConfig> {
+    if data.len() < 7 || data[0] != 1 {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "invalid AVC configuration"));
+    }
+
+    let mut pos = 6;
+    let sps = read_nal_units(data, &mut pos, (data[5] & 0x1F) as usize)?;
+
+    let count = *data.get(pos).ok_or(error::unexpected_eof())? as usize;
+    pos += 1;
+
+    let pps = read_nal_units(data, &mut pos, count)?;
+
+    Ok(DecoderConfig {
+        profile: data[1],
+        profile_compatibility: data[2],
+        level: data[3],
+        nal_length_size: (data[4] & 0x03) + 1,
+        sps: sps,
+        pps: pps,
+    })
+}
+
+/// Parse a sequence parameter set NAL unit, including its header.
+pub fn parse_sps(nal: &[u8]) -> Result<Sps> {
+    let data = bits::unescape(nal);
+    let mut r = BitReader::new(&data);
+
+    // NAL unit header.
+    r.skip(8)?;
+
+    let profile = r.read(8)? as u8;
+    r.skip(8)?;
+    let level = r.read(8)? as u8;
+    r.read_ue()?;
+
+    let mut chroma_format = 1;
+    let mut separate_planes = false;
+    let mut bit_depth_luma = 8;
+    let mut bit_depth_chroma = 8;
+
+    if let 100 | 110 | 122 | 244 | 44 | 83 | 86 | 118 | 128 | 138 | 139 | 134 | 135 = profile {
+        chroma_format = match r.read_ue()? {
+            format @ 0..=3 => format as u8,
+            wtf => bail!(error::invalid_value(el::CODEC_PRIVATE, format!("chroma format {} in SPS", wtf))),
+        };
+
+        if chroma_format == 3 {
+            separate_planes = r.read_flag()?;
+        }
+
+        bit_depth_luma = read_bit_depth(&mut r)?;
+        bit_depth_chroma = read_bit_depth(&mut r)?;
+
+        // Transform bypass flag.
+        r.skip(1)?;
+
+        if r.read_flag()? {
+            let lists = if chroma_format != 3 { 8 } else { 12 };
+
+            for i in 0..lists {
+                if r.read_flag()? {
+                    skip_scaling_list(&mut r, if i < 6 { 16 } else { 64 })?;
+                }
+            }
+        }
+    }
+
+    // Maximum frame number.
+    r.read_ue()?;
+
+    match r.read_ue()? {
+        0 => {
+            r.read_ue()?;
+        },
+
+        1 => {
+            r.skip(1)?;
+            r.read_se()?;
+            r.read_se()?;
+
+            for _ in 0..r.read_ue()? {
+                r.read_se()?;
+            }
+        },
+
+        _ => {},
+    };
+
+    // Maximum number of reference frames and gaps flag.
+    r.read_ue()?;
+    r.skip(1)?;
+
+    let width_mbs = r.read_ue()? + 1;
+    let height_units = r.read_ue()? + 1;
+
+    let frame_mbs_only = r.read_flag()?;
+    if !frame_mbs_only {
+        r.skip(1)?;
+    }
+
+    // Direct 8x8 inference flag.
+    r.skip(1)?;
+
+    let mut crop = (0, 0, 0, 0);
+    if r.read_flag()? {
+        crop = (r.read_ue()?, r.read_ue()?, r.read_ue()?, r.read_ue()?);
+    }
+
+    let colour = if r.read_flag()? { read_vui_colour(&mut r)? } else { None };
+
+    let field_factor = if frame_mbs_only { 1 } else { 2 };
+    let (crop_x, crop_y) = match (separate_planes, chroma_format) {
+        (false, 1) => (2, 2 * field_factor),
+        (false, 2) => (2, field_factor),
+        _ => (1, field_factor),
+    };
+
+    Ok(Sps {
+        profile: profile,
+        level: level,
+        chroma_format: chroma_format,
+        bit_depth_luma: bit_depth_luma,
+        bit_depth_chroma: bit_depth_chroma,
+        width: (width_mbs * 16).saturating_sub(crop_x * (crop.0 + crop.1)),
+        height: (height_units * 16 * field_factor).saturating_sub(crop_y * (crop.2 + crop.3)),
+        colour: colour,
+    })
+}
+
+/// Read a `bit_depth_*_minus8` field, which is at most 6, and return the bit depth.
+pub(crate) fn read_bit_depth(r: &mut BitReader) -> Result<u8> {
+    match r.read_ue()? {
+        depth @ 0..=6 => Ok(depth as u8 + 8),
+        wtf => bail!(error::invalid_value(el::CODEC_PRIVATE, format!("bit depth of {} bits in SPS", wtf.saturating_add(8)))),
+    }
+}
+
+fn skip_scaling_list(r: &mut BitReader, size: usize) -> Result<()> {
+    let mut last = 8;
+    let mut next = 8;
+
+    for _ in 0..size {
+        if next != 0 {
+            next = (last + r.read_se()? + 256) % 256;
+        }
+
+        if next != 0 {
+            last = next;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read `count` NAL units prefixed by their 16-bit size, starting at `pos`.
+pub(crate) fn read_nal_units(data: &[u8], pos: &mut usize, count: usize) -> Result<Vec<Vec<u8>>> {
+    let mut units = Vec::with_capacity(count);
+
+    for _ in 0..count {
+        if *pos + 2 > data.len() {
+            bail!(error::unexpected_eof());
+        }
+
+        let len = ((data[*pos] as usize) << 8) | data[*pos + 1] as usize;
+        *pos += 2;
+
+        if *pos + len > data.len() {
+            bail!(error::unexpected_eof());
+        }
+
+        units.push(data[*pos..*pos + len].to_vec());
+        *pos += len;
+    }
+
+    Ok(units)
+}
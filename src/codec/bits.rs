@@ -0,0 +1,129 @@
+//! Bit-level reading of codec bitstreams.
+
+use error::{self, Result};
+
+/// Reads a bitstream MSB first.
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            data: data,
+            pos: 0,
+        }
+    }
+
+    /// Read an unsigned integer of `n` bits, with `n` up to 64.
+    pub fn read(&mut self, n: usize) -> Result<u64> {
+        if self.pos + n > self.data.len() * 8 {
+            bail!(error::unexpected_eof());
+        }
+
+        let mut value = 0u64;
+
+        for _ in 0..n {
+            let bit = (self.data[self.pos / 8] >> (7 - self.pos % 8)) & 1;
+            value = (value << 1) | bit as u64;
+            self.pos += 1;
+        }
+
+        Ok(value)
+    }
+
+    /// Read a single bit as a flag.
+    pub fn read_flag(&mut self) -> Result<bool> {
+        Ok(self.read(1)? == 1)
+    }
+
+    /// Skip `n` bits.
+    pub fn skip(&mut self, n: usize) -> Result<()> {
+        if self.pos + n > self.data.len() * 8 {
+            bail!(error::unexpected_eof());
+        }
+
+        self.pos += n;
+        Ok(())
+    }
+
+    /// Read an unsigned Exp-Golomb code.
+    pub fn read_ue(&mut self) -> Result<u64> {
+        let mut zeros = 0;
+        while !self.read_flag()? {
+            zeros += 1;
+
+            if zeros > 32 {
+                bail!(error::unexpected_eof());
+            }
+        }
+
+        Ok((1 << zeros) - 1 + self.read(zeros)?)
+    }
+
+    /// Read a signed Exp-Golomb code.
+    pub fn read_se(&mut self) -> Result<i64> {
+        let value = self.read_ue()? as i64;
+
+        Ok(if value & 1 == 1 { (value + 1) / 2 } else { -(value / 2) })
+    }
+
+    /// Read an unsigned LEB128 value of up to 8 bytes, as used in AV1.
+    pub fn read_leb128(&mut self) -> Result<u64> {
+        let mut value = 0;
+
+        for i in 0..8 {
+            let byte = self.read(8)?;
+            value |= (byte & 0x7F) << (7 * i);
+
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Read an unsigned variable length code, as used in AV1.
+    pub fn read_uvlc(&mut self) -> Result<u64> {
+        let mut zeros = 0;
+        while !self.read_flag()? {
+            zeros += 1;
+        }
+
+        if zeros >= 32 {
+            return Ok(u32::max_value() as u64);
+        }
+
+        Ok((1 << zeros) - 1 + self.read(zeros)?)
+    }
+
+    /// Return the number of bits left to read.
+    pub fn bits_left(&self) -> usize {
+        self.data.len() * 8 - self.pos
+    }
+
+    /// Return the data from the next byte boundary to the end.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[(self.pos + 7) / 8..]
+    }
+}
+
+/// Remove the emulation prevention bytes of a NAL unit: every `0x03` following two zero bytes.
+pub fn unescape(nal: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(nal.len());
+    let mut zeros = 0;
+
+    for byte in nal {
+        if zeros >= 2 && *byte == 0x03 {
+            zeros = 0;
+            continue;
+        }
+
+        zeros = if *byte == 0 { zeros + 1 } else { 0 };
+        data.push(*byte);
+    }
+
+    data
+}
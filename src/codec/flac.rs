@@ -0,0 +1,111 @@
+//! FLAC audio configuration, for the `A_FLAC` codec.
+
+use elements as el;
+use error::{self, Result};
+
+use super::bits::BitReader;
+
+pub const BLOCK_STREAMINFO: u8 = 0;
+pub const BLOCK_VORBIS_COMMENT: u8 = 4;
+
+/// Parsed `STREAMINFO` metadata block.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StreamInfo {
+    /// Minimum and maximum block sizes in samples.
+    pub min_block_size: u16,
+    pub max_block_size: u16,
+
+    /// Minimum and maximum frame sizes in bytes, 0 if unknown.
+    pub min_frame_size: u32,
+    pub max_frame_size: u32,
+
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub bits_per_sample: u8,
+
+    /// Total number of samples per channel, 0 if unknown.
+    pub total_samples: u64,
+    pub md5: Vec<u8>,
+}
+
+/// The `fLaC` marker and metadata blocks stored in the codec private data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Config {
+    pub stream_info: StreamInfo,
+
+    /// Metadata blocks following `STREAMINFO`, as type and content pairs.
+    pub blocks: Vec<(u8, Vec<u8>)>,
+}
+
+/// Parse the FLAC header and metadata blocks.
+pub fn parse_config(data: &[u8]) -> Result<Config> {
+    if data.len() < 4 || &data[..4] != b"fLaC" {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "invalid FLAC header"));
+    }
+
+    let mut stream_info = None;
+    let mut blocks = Vec::new();
+    let mut pos = 4;
+
+    while pos + 4 <= data.len() {
+        let last = data[pos] & 0x80 != 0;
+        let block_type = data[pos] & 0x7F;
+        let len = (1..4).fold(0, |len, i| (len << 8) | data[pos + i] as usize);
+        pos += 4;
+
+        if pos + len > data.len() {
+            bail!(error::unexpected_eof());
+        }
+
+        let content = &data[pos..pos + len];
+        pos += len;
+
+        if block_type == BLOCK_STREAMINFO {
+            stream_info = Some(parse_stream_info(content)?);
+        } else {
+            blocks.push((block_type, content.to_vec()));
+        }
+
+        if last {
+            break;
+        }
+    }
+
+    Ok(Config {
+        stream_info: stream_info.ok_or(error::invalid_value(el::CODEC_PRIVATE, "no FLAC STREAMINFO"))?,
+        blocks: blocks,
+    })
+}
+
+/// Parse the content of a `STREAMINFO` metadata block.
+pub fn parse_stream_info(data: &[u8]) -> Result<StreamInfo> {
+    let mut r = BitReader::new(data);
+
+    let min_block_size = r.read(16)? as u16;
+    let max_block_size = r.read(16)? as u16;
+    let min_frame_size = r.read(24)? as u32;
+    let max_frame_size = r.read(24)? as u32;
+    let sample_rate = r.read(20)? as u32;
+    let channels = r.read(3)? as u8 + 1;
+    let bits_per_sample = r.read(5)? as u8 + 1;
+    let total_samples = r.read(36)?;
+
+    let md5 = r.remaining();
+    if md5.len() < 16 {
+        bail!(error::unexpected_eof());
+    }
+
+    Ok(StreamInfo {
+        min_block_size: min_block_size,
+        max_block_size: max_block_size,
+        min_frame_size: min_frame_size,
+        max_frame_size: max_frame_size,
+        sample_rate: sample_rate,
+        channels: channels,
+        bits_per_sample: bits_per_sample,
+        total_samples: total_samples,
+        md5: md5[..16].to_vec(),
+    })
+}
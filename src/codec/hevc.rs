@@ -0,0 +1,290 @@
+//! HEVC (H.265) decoder configuration, for the `V_MPEGH/ISO/HEVC` codec.
+
+use elements as el;
+use error::{self, Result};
+
+use super::avc::{read_bit_depth, read_nal_units};
+use super::bits::{self, BitReader};
+use super::{read_vui_colour, Colour};
+
+pub const NAL_VPS: u8 = 32;
+pub const NAL_SPS: u8 = 33;
+pub const NAL_PPS: u8 = 34;
+
+/// Maximum number of short-term reference picture sets in a sequence parameter set.
+const MAX_SHORT_TERM_REF_PIC_SETS: u64 = 64;
+
+/// Parsed `HEVCDecoderConfigurationRecord`, stored in the codec private data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DecoderConfig {
+    pub profile_space: u8,
+    pub tier: bool,
+    pub profile: u8,
+    pub level: u8,
+
+    /// 0 for monochrome, 1 for 4:2:0, 2 for 4:2:2 and 3 for 4:4:4.
+    pub chroma_format: u8,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+
+    /// Size in bytes of the length prefix of each NAL unit in the blocks.
+    pub nal_length_size: u8,
+
+    /// NAL units of the configuration, with their type, in their order of appearance.
+    pub nal_units: Vec<(u8, Vec<u8>)>,
+}
+
+impl DecoderConfig {
+    /// Return the NAL units of the given type.
+    pub fn units(&self, nal_type: u8) -> Vec<&[u8]> {
+        self.nal_units.iter()
+            .filter(|&&(t, _)| t == nal_type)
+            .map(|&(_, ref unit)| &unit[..])
+            .collect()
+    }
+
+    /// Parse the summary of the first sequence parameter set.
+    pub fn parse_sps(&self) -> Result<Sps> {
+        match self.units(NAL_SPS).first() {
+            Some(sps) => parse_sps(sps),
+            None => bail!(error::invalid_value(el::CODEC_PRIVATE, "no SPS")),
+        }
+    }
+}
+
+/// Summary of a sequence parameter set.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sps {
+    pub profile_space: u8,
+    pub tier: bool,
+    pub profile: u8,
+    pub level: u8,
+
+    pub chroma_format: u8,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+
+    /// Dimensions of the decoded pictures, after applying the conformance window.
+    pub width: u64,
+    pub height: u64,
+
+    pub colour: Option<Colour>,
+}
+
+/// Parse an `HEVCDecoderConfigurationRecord`.
+pub fn parse_config(data: &[u8]) -> Result<DecoderConfig> {
+    if data.len() < 23 || data[0] != 1 {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "invalid HEVC configuration"));
+    }
+
+    let mut nal_units = Vec::new();
+    let mut pos = 23;
+
+    for _ in 0..data[22] {
+        if pos + 3 > data.len() {
+            bail!(error::unexpected_eof());
+        }
+
+        let nal_type = data[pos] & 0x3F;
+        let count = ((data[pos + 1] as usize) << 8) | data[pos + 2] as usize;
+        pos += 3;
+
+        for unit in read_nal_units(data, &mut pos, count)? {
+            nal_units.push((nal_type, unit));
+        }
+    }
+
+    Ok(DecoderConfig {
+        profile_space: data[1] >> 6,
+        tier: data[1] & 0x20 != 0,
+        profile: data[1] & 0x1F,
+        level: data[12],
+        chroma_format: data[16] & 0x03,
+        bit_depth_luma: (data[17] & 0x07) + 8,
+        bit_depth_chroma: (data[18] & 0x07) + 8,
+        nal_length_size: (data[21] & 0x03) + 1,
+        nal_units: nal_units,
+    })
+}
+
+/// Parse a sequence parameter set NAL unit, including its header.
+pub fn parse_sps(nal: &[u8]) -> Result<Sps> {
+    let data = bits::unescape(nal);
+    let mut r = BitReader::new(&data);
+
+    // NAL unit header and video parameter set ID.
+    r.skip(20)?;
+    let max_sub_layers = r.read(3)? as usize;
+    r.skip(1)?;
+
+    // General profile, tier and level.
+    let profile_space = r.read(2)? as u8;
+    let tier = r.read_flag()?;
+    let profile = r.read(5)? as u8;
+    r.skip(32 + 48)?;
+    let level = r.read(8)? as u8;
+
+    let mut sub_layers = Vec::with_capacity(max_sub_layers);
+    for _ in 0..max_sub_layers {
+        sub_layers.push((r.read_flag()?, r.read_flag()?));
+    }
+
+    if max_sub_layers > 0 {
+        r.skip(2 * (8 - max_sub_layers))?;
+    }
+
+    for (profile_present, level_present) in sub_layers {
+        if profile_present {
+            r.skip(88)?;
+        }
+
+        if level_present {
+            r.skip(8)?;
+        }
+    }
+
+    r.read_ue()?;
+
+    let chroma_format = match r.read_ue()? {
+        format @ 0..=3 => format as u8,
+        wtf => bail!(error::invalid_value(el::CODEC_PRIVATE, format!("chroma format {} in SPS", wtf))),
+    };
+
+    let separate_planes = chroma_format == 3 && r.read_flag()?;
+
+    let width = r.read_ue()?;
+    let height = r.read_ue()?;
+
+    let mut window = (0, 0, 0, 0);
+    if r.read_flag()? {
+        window = (r.read_ue()?, r.read_ue()?, r.read_ue()?, r.read_ue()?);
+    }
+
+    let bit_depth_luma = read_bit_depth(&mut r)?;
+    let bit_depth_chroma = read_bit_depth(&mut r)?;
+    let poc_lsb_bits = r.read_ue()? as usize + 4;
+
+    // Sub-layer ordering information.
+    let first = if r.read_flag()? { 0 } else { max_sub_layers };
+    for _ in first..max_sub_layers + 1 {
+        r.read_ue()?;
+        r.read_ue()?;
+        r.read_ue()?;
+    }
+
+    // Coding and transform block sizes, transform hierarchy depths.
+    for _ in 0..6 {
+        r.read_ue()?;
+    }
+
+    if r.read_flag()? && r.read_flag()? {
+        skip_scaling_list_data(&mut r)?;
+    }
+
+    // Asymmetric motion partitions and sample adaptive offset flags.
+    r.skip(2)?;
+
+    if r.read_flag()? {
+        r.skip(8)?;
+        r.read_ue()?;
+        r.read_ue()?;
+        r.skip(1)?;
+    }
+
+    let count = match r.read_ue()? {
+        count @ 0..=MAX_SHORT_TERM_REF_PIC_SETS => count as usize,
+        wtf => bail!(error::invalid_value(el::CODEC_PRIVATE, format!("{} short-term reference picture sets in SPS", wtf))),
+    };
+
+    let mut delta_pocs: Vec<u64> = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let n = skip_short_term_ref_pic_set(&mut r, i, &delta_pocs)?;
+        delta_pocs.push(n);
+    }
+
+    if r.read_flag()? {
+        for _ in 0..r.read_ue()? {
+            r.skip(poc_lsb_bits + 1)?;
+        }
+    }
+
+    // Temporal motion vector prediction and strong intra smoothing flags.
+    r.skip(2)?;
+
+    let colour = if r.read_flag()? { read_vui_colour(&mut r)? } else { None };
+
+    let (sub_width, sub_height) = match (separate_planes, chroma_format) {
+        (false, 1) => (2, 2),
+        (false, 2) => (2, 1),
+        _ => (1, 1),
+    };
+
+    Ok(Sps {
+        profile_space: profile_space,
+        tier: tier,
+        profile: profile,
+        level: level,
+        chroma_format: chroma_format,
+        bit_depth_luma: bit_depth_luma,
+        bit_depth_chroma: bit_depth_chroma,
+        width: width.saturating_sub(sub_width * (window.0 + window.1)),
+        height: height.saturating_sub(sub_height * (window.2 + window.3)),
+        colour: colour,
+    })
+}
+
+fn skip_scaling_list_data(r: &mut BitReader) -> Result<()> {
+    for size in 0..4 {
+        let step = if size == 3 { 3 } else { 1 };
+
+        for _ in (0..6).filter(|m| m % step == 0) {
+            if !r.read_flag()? {
+                r.read_ue()?;
+                continue;
+            }
+
+            if size > 1 {
+                r.read_se()?;
+            }
+
+            for _ in 0..(1 << (4 + (size << 1))).min(64) {
+                r.read_se()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Skip the short-term reference picture set `index`, and return its number of delta POCs.
+/// `delta_pocs` holds the number of delta POCs of the preceding sets.
+fn skip_short_term_ref_pic_set(r: &mut BitReader, index: usize, delta_pocs: &[u64]) -> Result<u64> {
+    if index != 0 && r.read_flag()? {
+        // Predicted from the previous set: sign and absolute delta, then one entry per delta
+        // POC of the reference set, plus one.
+        r.skip(1)?;
+        r.read_ue()?;
+
+        let mut count = 0;
+        for _ in 0..delta_pocs[index - 1] + 1 {
+            if r.read_flag()? || r.read_flag()? {
+                count += 1;
+            }
+        }
+
+        return Ok(count);
+    }
+
+    let negative = r.read_ue()?;
+    let positive = r.read_ue()?;
+
+    for _ in 0..negative + positive {
+        r.read_ue()?;
+        r.skip(1)?;
+    }
+
+    Ok(negative + positive)
+}
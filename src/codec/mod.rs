@@ -0,0 +1,133 @@
+//! Interpretation of the codec-specific data of matroska tracks (`CodecPrivate`), and of the
+//! bitstream headers it refers to.
+
+pub mod aac;
+pub mod ac3;
+pub mod av1;
+pub mod avc;
+pub mod flac;
+pub mod hevc;
+pub mod opus;
+pub mod vorbis;
+pub mod vp8;
+pub mod vp9;
+
+pub(crate) mod bits;
+
+use error::Result;
+
+/// Parsed codec private data of a track.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CodecConfig {
+    Avc(avc::DecoderConfig),
+    Hevc(hevc::DecoderConfig),
+    Av1(av1::DecoderConfig),
+    Vp9(vp9::Features),
+    Aac(aac::AudioSpecificConfig),
+    Opus(opus::Head),
+    Vorbis(vorbis::Headers),
+    Flac(flac::Config),
+}
+
+impl CodecConfig {
+    /// Parse the codec private data of a track using the `codec` identifier. Returns `None` if
+    /// the codec has no known configuration or if there is no private data.
+    pub fn parse(codec: &str, private: Option<&[u8]>) -> Result<Option<CodecConfig>> {
+        let private = match private {
+            Some(private) => private,
+            None => return Ok(None),
+        };
+
+        Ok(Some(match codec {
+            "V_MPEG4/ISO/AVC" => CodecConfig::Avc(avc::parse_config(private)?),
+            "V_MPEGH/ISO/HEVC" => CodecConfig::Hevc(hevc::parse_config(private)?),
+            "V_AV1" => CodecConfig::Av1(av1::parse_config(private)?),
+            "V_VP9" => CodecConfig::Vp9(vp9::parse_features(private)?),
+            "A_OPUS" => CodecConfig::Opus(opus::parse_head(private)?),
+            "A_VORBIS" => CodecConfig::Vorbis(vorbis::parse_headers(private)?),
+            "A_FLAC" => CodecConfig::Flac(flac::parse_config(private)?),
+            _ if codec.starts_with("A_AAC") => CodecConfig::Aac(aac::parse_config(private)?),
+            _ => return Ok(None),
+        }))
+    }
+
+    /// Sampling frequency of the coded audio, if this is an audio configuration. For AAC with
+    /// spectral band replication, this is the frequency of the core stream.
+    pub fn sample_rate(&self) -> Option<u32> {
+        match *self {
+            CodecConfig::Aac(ref config) => Some(config.sample_rate),
+            CodecConfig::Opus(_) => Some(opus::SAMPLE_RATE),
+            CodecConfig::Vorbis(ref headers) => Some(headers.sample_rate),
+            CodecConfig::Flac(ref config) => Some(config.stream_info.sample_rate),
+            _ => None,
+        }
+    }
+
+    /// Sampling frequency of the decoded audio, if this is an audio configuration.
+    pub fn output_sample_rate(&self) -> Option<u32> {
+        match *self {
+            CodecConfig::Aac(ref config) => Some(config.output_sample_rate()),
+            _ => self.sample_rate(),
+        }
+    }
+
+    /// Number of decoded audio channels, if this is an audio configuration that defines it.
+    pub fn channels(&self) -> Option<u8> {
+        match *self {
+            CodecConfig::Aac(ref config) => config.channels(),
+            CodecConfig::Opus(ref head) => Some(head.channels),
+            CodecConfig::Vorbis(ref headers) => Some(headers.channels),
+            CodecConfig::Flac(ref config) => Some(config.stream_info.channels),
+            _ => None,
+        }
+    }
+}
+
+/// Colour description of a video stream, as signaled in its VUI parameters. The values are the
+/// ones defined by ISO/IEC 23091-4 (ITU-T H.273).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Colour {
+    pub full_range: bool,
+    pub primaries: u8,
+    pub transfer_characteristics: u8,
+    pub matrix_coefficients: u8,
+}
+
+/// Read the fields of VUI parameters up to the colour description, which is common to AVC and
+/// HEVC. Returns `None` if no video signal type is present.
+pub(crate) fn read_vui_colour(r: &mut bits::BitReader) -> Result<Option<Colour>> {
+    // Aspect ratio information.
+    if r.read_flag()? && r.read(8)? == 255 {
+        r.skip(32)?;
+    }
+
+    // Overscan information.
+    if r.read_flag()? {
+        r.skip(1)?;
+    }
+
+    // Video signal type.
+    if !r.read_flag()? {
+        return Ok(None);
+    }
+
+    r.skip(3)?;
+    let full_range = r.read_flag()?;
+
+    let mut colour = Colour {
+        full_range: full_range,
+        primaries: 2,
+        transfer_characteristics: 2,
+        matrix_coefficients: 2,
+    };
+
+    if r.read_flag()? {
+        colour.primaries = r.read(8)? as u8;
+        colour.transfer_characteristics = r.read(8)? as u8;
+        colour.matrix_coefficients = r.read(8)? as u8;
+    }
+
+    Ok(Some(colour))
+}
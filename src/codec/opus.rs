@@ -0,0 +1,63 @@
+//! Opus audio configuration, for the `A_OPUS` codec.
+
+use elements as el;
+use error::{self, Result};
+
+/// Sampling frequency of decoded Opus streams.
+pub const SAMPLE_RATE: u32 = 48000;
+
+/// Parsed identification header (`OpusHead`), stored in the codec private data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Head {
+    pub version: u8,
+    pub channels: u8,
+
+    /// Number of samples to discard at the start of the decoded stream.
+    pub pre_skip: u16,
+
+    /// Sampling frequency of the original input, for information only.
+    pub input_sample_rate: u32,
+
+    /// Gain to apply to the decoded output, in Q7.8 dB.
+    pub output_gain: i16,
+
+    /// Channel mapping family and, for families other than 0, the mapping table.
+    pub mapping_family: u8,
+    pub stream_count: u8,
+    pub coupled_count: u8,
+    pub channel_mapping: Vec<u8>,
+}
+
+/// Parse an `OpusHead` header.
+pub fn parse_head(data: &[u8]) -> Result<Head> {
+    if data.len() < 19 || &data[..8] != b"OpusHead" {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "invalid OpusHead"));
+    }
+
+    let channels = data[9];
+    let mapping_family = data[18];
+
+    // Family 0 is mono or stereo in a single stream, with an implicit mapping.
+    let (stream_count, coupled_count, channel_mapping) = if mapping_family == 0 {
+        (1, if channels == 2 { 1 } else { 0 }, (0..channels).collect())
+    } else {
+        if data.len() < 21 + channels as usize {
+            bail!(error::unexpected_eof());
+        }
+
+        (data[19], data[20], data[21..21 + channels as usize].to_vec())
+    };
+
+    Ok(Head {
+        version: data[8],
+        channels: channels,
+        pre_skip: data[10] as u16 | (data[11] as u16) << 8,
+        input_sample_rate: (0..4).fold(0, |rate, i| rate | (data[12 + i] as u32) << (8 * i)),
+        output_gain: (data[16] as u16 | (data[17] as u16) << 8) as i16,
+        mapping_family: mapping_family,
+        stream_count: stream_count,
+        coupled_count: coupled_count,
+        channel_mapping: channel_mapping,
+    })
+}
@@ -0,0 +1,93 @@
+//! Vorbis audio configuration, for the `A_VORBIS` codec.
+
+use elements as el;
+use error::{self, Result};
+use reader::cluster;
+
+/// The three Vorbis headers, stored Xiph-laced in the codec private data.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Headers {
+    pub channels: u8,
+    pub sample_rate: u32,
+
+    /// Bitrate hints in bits per second, 0 if unset.
+    pub bitrate_maximum: i32,
+    pub bitrate_nominal: i32,
+    pub bitrate_minimum: i32,
+
+    /// Short and long block sizes in samples.
+    pub block_sizes: (u16, u16),
+
+    /// Vendor string and `NAME=value` comments of the comment header.
+    pub vendor: String,
+    pub comments: Vec<String>,
+
+    /// Identification, comment and setup header packets, as stored.
+    pub packets: Vec<Vec<u8>>,
+}
+
+/// Parse the Xiph-laced Vorbis headers of the codec private data.
+pub fn parse_headers(data: &[u8]) -> Result<Headers> {
+    let packets = cluster::parse_xiph_frames(data.to_vec())?;
+
+    if packets.len() != 3 {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "expected three Vorbis headers"));
+    }
+
+    let id = &packets[0];
+    if id.len() < 30 || &id[..7] != b"\x01vorbis" {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "invalid Vorbis identification header"));
+    }
+
+    let comment = &packets[1];
+    if comment.len() < 7 || &comment[..7] != b"\x03vorbis" {
+        bail!(error::invalid_value(el::CODEC_PRIVATE, "invalid Vorbis comment header"));
+    }
+
+    let mut pos = 7;
+    let vendor = read_string(comment, &mut pos)?;
+
+    let count = read_u32(comment, pos)?;
+    pos += 4;
+
+    let mut comments = Vec::new();
+    for _ in 0..count {
+        comments.push(read_string(comment, &mut pos)?);
+    }
+
+    Ok(Headers {
+        channels: id[11],
+        sample_rate: read_u32(id, 12)?,
+        bitrate_maximum: read_u32(id, 16)? as i32,
+        bitrate_nominal: read_u32(id, 20)? as i32,
+        bitrate_minimum: read_u32(id, 24)? as i32,
+        block_sizes: (1 << (id[28] & 0x0F), 1 << (id[28] >> 4)),
+        vendor: vendor,
+        comments: comments,
+        packets: packets,
+    })
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Result<u32> {
+    if pos + 4 > data.len() {
+        bail!(error::unexpected_eof());
+    }
+
+    Ok((0..4).fold(0, |value, i| value | (data[pos + i] as u32) << (8 * i)))
+}
+
+/// Read a string prefixed by its 32-bit length at `pos`, and move `pos` past it.
+fn read_string(data: &[u8], pos: &mut usize) -> Result<String> {
+    let len = read_u32(data, *pos)? as usize;
+    *pos += 4;
+
+    if *pos + len > data.len() {
+        bail!(error::unexpected_eof());
+    }
+
+    let string = String::from_utf8_lossy(&data[*pos..*pos + len]).into_owned();
+    *pos += len;
+
+    Ok(string)
+}
@@ -0,0 +1,48 @@
+//! VP8 frame headers, for the `V_VP8` codec.
+
+use elements as el;
+use error::{self, Result};
+
+/// Summary of the header of a keyframe.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameHeader {
+    pub version: u8,
+    pub width: u64,
+    pub height: u64,
+
+    /// Upscaling factors to apply to the decoded frames: 0 for none, 1 for 5/4, 2 for 5/3 and 3
+    /// for 2.
+    pub horizontal_scale: u8,
+    pub vertical_scale: u8,
+}
+
+/// Parse the header of a frame. Returns `None` if the frame is not a keyframe.
+pub fn parse_frame_header(frame: &[u8]) -> Result<Option<FrameHeader>> {
+    if frame.len() < 3 {
+        bail!(error::unexpected_eof());
+    }
+
+    if frame[0] & 0x01 != 0 {
+        return Ok(None);
+    }
+
+    if frame.len() < 10 {
+        bail!(error::unexpected_eof());
+    }
+
+    if &frame[3..6] != &[0x9D, 0x01, 0x2A] {
+        bail!(error::invalid_value(el::BLOCK, "invalid VP8 start code"));
+    }
+
+    let width = frame[6] as u64 | (frame[7] as u64) << 8;
+    let height = frame[8] as u64 | (frame[9] as u64) << 8;
+
+    Ok(Some(FrameHeader {
+        version: (frame[0] >> 1) & 0x07,
+        width: width & 0x3FFF,
+        height: height & 0x3FFF,
+        horizontal_scale: (width >> 14) as u8,
+        vertical_scale: (height >> 14) as u8,
+    }))
+}
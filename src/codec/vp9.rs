@@ -0,0 +1,131 @@
+//! VP9 codec features and frame headers, for the `V_VP9` codec.
+
+use elements as el;
+use error::{self, Result};
+
+use super::bits::BitReader;
+
+/// Codec features stored in the codec private data. Each of them is optional.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Features {
+    pub profile: Option<u8>,
+    pub level: Option<u8>,
+    pub bit_depth: Option<u8>,
+
+    /// 0 for 4:2:0 vertical, 1 for 4:2:0 colocated, 2 for 4:2:2 and 3 for 4:4:4.
+    pub chroma_subsampling: Option<u8>,
+}
+
+/// Summary of the uncompressed header of a keyframe.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameHeader {
+    pub profile: u8,
+    pub bit_depth: u8,
+    pub colour_space: u8,
+    pub full_range: bool,
+    pub subsampling_x: bool,
+    pub subsampling_y: bool,
+    pub width: u64,
+    pub height: u64,
+}
+
+/// Parse the list of features in the codec private data, made of ID, length and value triplets.
+pub fn parse_features(data: &[u8]) -> Result<Features> {
+    let mut features = Features::default();
+    let mut pos = 0;
+
+    while pos + 2 <= data.len() {
+        let (id, len) = (data[pos], data[pos + 1] as usize);
+        pos += 2;
+
+        if pos + len > data.len() {
+            bail!(error::unexpected_eof());
+        }
+
+        if len != 1 {
+            bail!(error::invalid_value(el::CODEC_PRIVATE, format!("VP9 feature {} of length {}", id, len)));
+        }
+
+        let value = Some(data[pos]);
+        match id {
+            1 => features.profile = value,
+            2 => features.level = value,
+            3 => features.bit_depth = value,
+            4 => features.chroma_subsampling = value,
+            _ => {},
+        };
+
+        pos += len;
+    }
+
+    Ok(features)
+}
+
+/// Parse the uncompressed header of a frame. Returns `None` if the frame is not a keyframe.
+pub fn parse_frame_header(frame: &[u8]) -> Result<Option<FrameHeader>> {
+    let mut r = BitReader::new(frame);
+
+    if r.read(2)? != 2 {
+        bail!(error::invalid_value(el::BLOCK, "invalid VP9 frame marker"));
+    }
+
+    let low = r.read(1)?;
+    let profile = (r.read(1)? << 1 | low) as u8;
+    if profile == 3 {
+        r.skip(1)?;
+    }
+
+    // Existing frame, then non-key frames.
+    if r.read_flag()? || r.read_flag()? {
+        return Ok(None);
+    }
+
+    // Show frame and error resilient flags.
+    r.skip(2)?;
+
+    if r.read(24)? != 0x498342 {
+        bail!(error::invalid_value(el::BLOCK, "invalid VP9 sync code"));
+    }
+
+    let bit_depth = if profile < 2 { 8 } else if r.read_flag()? { 12 } else { 10 };
+
+    let colour_space = r.read(3)? as u8;
+    let odd_profile = profile == 1 || profile == 3;
+
+    let (full_range, subsampling_x, subsampling_y) = if colour_space != 7 {
+        let full_range = r.read_flag()?;
+
+        if odd_profile {
+            let x = r.read_flag()?;
+            let y = r.read_flag()?;
+            r.skip(1)?;
+
+            (full_range, x, y)
+        } else {
+            (full_range, true, true)
+        }
+    } else {
+        // RGB.
+        if odd_profile {
+            r.skip(1)?;
+        }
+
+        (true, false, false)
+    };
+
+    let width = r.read(16)? + 1;
+    let height = r.read(16)? + 1;
+
+    Ok(Some(FrameHeader {
+        profile: profile,
+        bit_depth: bit_depth,
+        colour_space: colour_space,
+        full_range: full_range,
+        subsampling_x: subsampling_x,
+        subsampling_y: subsampling_y,
+        width: width,
+        height: height,
+    }))
+}
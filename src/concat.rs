@@ -0,0 +1,127 @@
+//! Concatenate matroska files with compatible tracks into a single file.
+
+use std::io::{Read, Seek, Write};
+
+use elements as el;
+use error::{self, Result};
+use reader::Reader;
+use reader::track::{self, Kind};
+use writer::Writer;
+
+/// Check that the track `b`, found in the input number `input`, can be concatenated after the
+/// track `a`. Returns an error naming the first field that differs.
+pub fn check_compatibility(input: usize, a: &track::Info, b: &track::Info) -> Result<()> {
+    let track = a.index();
+
+    if a.kind().track_type() != b.kind().track_type() {
+        bail!(error::incompatible(input, track, "TrackType"));
+    }
+
+    if a.codec() != b.codec() {
+        bail!(error::incompatible(input, track, "CodecID"));
+    }
+
+    if a.codec_private() != b.codec_private() {
+        bail!(error::incompatible(input, track, "CodecPrivate"));
+    }
+
+    match (a.kind(), b.kind()) {
+        (&Kind::Video(ref a), &Kind::Video(ref b)) => {
+            if a.width() != b.width() {
+                bail!(error::incompatible(input, track, "PixelWidth"));
+            }
+
+            if a.height() != b.height() {
+                bail!(error::incompatible(input, track, "PixelHeight"));
+            }
+        },
+
+        (&Kind::Audio(ref a), &Kind::Audio(ref b)) => {
+            if a.sampling_frequency() != b.sampling_frequency() {
+                bail!(error::incompatible(input, track, "SamplingFrequency"));
+            }
+
+            if a.channels() != b.channels() {
+                bail!(error::incompatible(input, track, "Channels"));
+            }
+        },
+
+        _ => {},
+    };
+
+    Ok(())
+}
+
+/// Concatenate all the `inputs` into a single matroska file written to `output`. All the inputs
+/// must contain the same tracks, with compatible parameters. The timestamps of each input are
+/// offset by the duration of the previous ones, and the duration of the output is the sum of
+/// their durations. The cues are rebuilt for the whole output. Returns the output once the file
+/// is complete.
+pub fn concat<R, W>(inputs: &mut [Reader<R>], output: W) -> Result<W>
+    where R: Read, W: Write + Seek
+{
+    if inputs.is_empty() {
+        bail!(error::invalid_value(el::SEGMENT, "no input to concatenate"));
+    }
+
+    let mut infos = Vec::with_capacity(inputs.len());
+
+    for input in inputs.iter_mut() {
+        infos.push(input.header_info()?);
+    }
+
+    // Every input must have the same set of tracks as the first one.
+    for (i, &(_, ref tracks)) in infos.iter().enumerate().skip(1) {
+        for a in &infos[0].1 {
+            match tracks.iter().find(|b| b.index() == a.index()) {
+                Some(b) => check_compatibility(i, a, b)?,
+                None => bail!(error::incompatible(i, a.index(), "TrackNumber")),
+            };
+        }
+
+        // A track missing from the first input, or a track number used more times than in the
+        // first input, which the reader does not reject.
+        if tracks.len() != infos[0].1.len() {
+            let count = |tracks: &[track::Info], number| tracks.iter().filter(|track| track.index() == number).count();
+
+            let number = tracks.iter().map(|track| track.index())
+                .find(|&number| count(tracks, number) != count(&infos[0].1, number))
+                .unwrap_or(0);
+
+            bail!(error::incompatible(i, number, "TrackNumber"));
+        }
+    }
+
+    let doc_type = inputs[0].header().doc_type().to_string();
+
+    let mut segment = infos[0].0.clone();
+    segment.set_duration(None);
+
+    let scale = segment.timecode_scale();
+    let mut writer = Writer::new(output, &doc_type, &segment, &infos[0].1)?;
+
+    // Offset of the current input in the output, in output ticks.
+    let mut offset = 0;
+
+    for (input, &(ref info, _)) in inputs.iter_mut().zip(infos.iter()) {
+        let input_scale = info.timecode_scale();
+
+        while let Some(mut cluster) = input.next_cluster()? {
+            for block in cluster.blocks() {
+                let block = block?;
+                let timecode = offset as i64 + block.absolute_timecode() * input_scale as i64 / scale as i64;
+
+                writer.write_block(block, timecode)?;
+            }
+        }
+
+        // The next input starts at the end of the current one: either its declared duration or
+        // the end of its last block, whichever is later.
+        let duration = info.duration().map_or(0, |d| (d * input_scale as f64 / scale as f64) as u64);
+
+        offset = ::std::cmp::max(offset + duration, writer.end_timecode());
+    }
+
+    writer.set_duration(offset as f64);
+    writer.finish()
+}
@@ -0,0 +1,304 @@
+//! Generate DASH manifests for WebM files following the WebM on-demand profile, where each file
+//! holds a single track and is addressed by the byte ranges of its headers and of its cues.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use ebml as libebml;
+
+use codec::CodecConfig;
+use elements as el;
+use error::{self, Result};
+use reader::{Info, Reader};
+use reader::cues;
+use reader::limits::ReaderLimits;
+use reader::track::Kind;
+use reader::tree;
+
+const PROFILE: &'static str = "urn:mpeg:dash:profile:webm-on-demand:2012";
+
+/// Properties of the track of a representation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Media {
+    Video { width: u64, height: u64 },
+    Audio { sampling_frequency: u64, channels: u64 },
+}
+
+impl Media {
+    /// Returns the content type of the adaptation sets holding this media.
+    pub fn content_type(&self) -> &'static str {
+        match *self {
+            Media::Video { .. } => "video",
+            Media::Audio { .. } => "audio",
+        }
+    }
+}
+
+/// A WebM file that can be streamed as a representation of an adaptation set.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Representation {
+    /// URL of the file in the manifest, usually relative to the manifest.
+    pub url: String,
+
+    pub track: u64,
+    pub media: Media,
+
+    /// Codec string, as used in the `codecs` parameter of MIME types.
+    pub codecs: String,
+
+    /// Average bitrate in bits per second.
+    pub bandwidth: u64,
+
+    /// Duration in nanoseconds.
+    pub duration: u64,
+
+    /// First and last bytes of the initialization segment, from the EBML header to the end of
+    /// the tracks.
+    pub init_range: (u64, u64),
+
+    /// First and last bytes of the cues.
+    pub index_range: (u64, u64),
+
+    /// Times of the clusters referenced by the cues, in nanoseconds. Each of them starts a
+    /// subsegment.
+    pub cue_times: Vec<u64>,
+}
+
+impl Representation {
+    /// Read the metadata and the cues of the file `r`, which is referenced by `url` in the
+    /// manifest. The tracks and the cues must be listed in the seek head.
+    pub fn read<R: Read + Seek>(mut r: R, url: &str) -> Result<Representation> {
+        r.seek(SeekFrom::Start(0))?;
+
+        let (meta_seek, segment, mut tracks, segment_offset) = {
+            let mut reader = Reader::new(&mut r)?;
+            let mut meta_seek = None;
+            let mut segment = None;
+            let mut tracks = None;
+
+            for info in reader.info()? {
+                match info {
+                    Info::MetaSeek(info) => meta_seek = Some(info),
+                    Info::Segment(info) => segment = Some(info),
+                    Info::Tracks(info) => tracks = Some(info),
+                    _ => {},
+                };
+            }
+
+            (meta_seek.ok_or(error::not_found(el::SEEK_HEAD))?,
+             segment.ok_or(error::not_found(el::INFO))?,
+             tracks.ok_or(error::not_found(el::TRACKS))?,
+             reader.segment_offset() as u64)
+        };
+
+        if tracks.len() != 1 {
+            bail!(error::invalid_value(el::TRACKS, format!("{} tracks instead of one", tracks.len())));
+        }
+
+        let track = tracks.remove(0);
+
+        let media = match *track.kind() {
+            Kind::Video(ref video) => Media::Video {
+                width: video.width(),
+                height: video.height(),
+            },
+
+            Kind::Audio(ref audio) => Media::Audio {
+                sampling_frequency: audio.sampling_frequency() as u64,
+                channels: audio.channels(),
+            },
+
+            ref kind => bail!(error::invalid_value(el::TRACK_TYPE, kind.track_type())),
+        };
+
+        let codecs = codec_string(track.codec(), track.codec_config()?.as_ref())
+            .ok_or(error::invalid_value(el::CODEC_ID, track.codec()))?;
+
+        let scale = segment.timecode_scale();
+        let duration = segment.duration().map(|d| (d * scale as f64) as u64).unwrap_or(0);
+
+        if duration == 0 {
+            bail!(error::not_found(el::DURATION));
+        }
+
+        // The initialization segment ends with the tracks.
+        let position = segment_offset + *meta_seek.get(&el::TRACKS).ok_or(error::not_found(el::TRACKS))?;
+        let (content, size) = read_header_at(&mut r, el::TRACKS, position)?;
+        let init_end = content + size;
+
+        let cues_position = segment_offset + *meta_seek.get(&el::CUES).ok_or(error::not_found(el::CUES))?;
+        let (content, size) = read_header_at(&mut r, el::CUES, cues_position)?;
+
+        let limits = ReaderLimits::default();
+        limits.check("element size", size as usize, limits.max_element_size)?;
+
+        let data = tree::read_data(&mut r, size)?;
+        let children = libebml::reader::read_element_content(&mut &data[..], size as usize)
+            .and_then(|(content, _)| content.children())?;
+
+        // Only the first cue point of each cluster starts a subsegment.
+        let mut clusters: Vec<(u64, u64)> = Vec::new();
+
+        for cue in cues::read(children)? {
+            for position in cue.positions.iter().filter(|position| position.track == track.index()) {
+                match clusters.iter_mut().find(|&&mut (cluster, _)| cluster == position.cluster_position) {
+                    Some(cluster) => cluster.1 = cluster.1.min(cue.time),
+                    None => clusters.push((position.cluster_position, cue.time)),
+                };
+            }
+        }
+
+        let mut cue_times: Vec<u64> = clusters.iter().map(|&(_, time)| time * scale).collect();
+        cue_times.sort();
+
+        let file_size = r.seek(SeekFrom::End(0))?;
+
+        Ok(Representation {
+            url: url.to_string(),
+            track: track.index(),
+            media: media,
+            codecs: codecs,
+            bandwidth: (file_size as f64 * 8e9 / duration as f64) as u64,
+            duration: duration,
+            init_range: (0, init_end - 1),
+            index_range: (cues_position, content + size - 1),
+            cue_times: cue_times,
+        })
+    }
+}
+
+/// Read the header of the element `id` located at `position` in `r`. Returns the position of
+/// its content and its size.
+fn read_header_at<R: Read + Seek>(r: &mut R, id: u64, position: u64) -> Result<(u64, u64)> {
+    r.seek(SeekFrom::Start(position))?;
+
+    let (found, size, len) = tree::read_header(r)?.ok_or(error::unexpected_eof())?;
+    if found != id {
+        bail!(error::unexpected(id, found));
+    }
+
+    let size = size.ok_or(error::invalid_value(id, "unknown size"))?;
+    Ok((position + len as u64, size))
+}
+
+/// Returns the codec string of the codec `codec`, a matroska codec ID, with the configuration
+/// `config` parsed from the codec private data. The profile and level are only given when the
+/// configuration is known. Returns `None` for codecs that cannot be streamed in WebM.
+pub fn codec_string(codec: &str, config: Option<&CodecConfig>) -> Option<String> {
+    let codecs = match (codec, config) {
+        ("V_VP8", _) => "vp8".to_string(),
+
+        ("V_VP9", Some(&CodecConfig::Vp9(ref features))) => {
+            match (features.profile, features.level, features.bit_depth) {
+                (Some(profile), Some(level), Some(bit_depth)) => format!("vp09.{:02}.{:02}.{:02}", profile, level, bit_depth),
+                _ => "vp9".to_string(),
+            }
+        },
+
+        ("V_VP9", _) => "vp9".to_string(),
+
+        ("V_AV1", Some(&CodecConfig::Av1(ref config))) => {
+            let tier = if config.tier { 'H' } else { 'M' };
+            format!("av01.{}.{:02}{}.{:02}", config.profile, config.level, tier, config.bit_depth())
+        },
+
+        ("V_AV1", _) => "av01".to_string(),
+        ("A_OPUS", _) => "opus".to_string(),
+        ("A_VORBIS", _) => "vorbis".to_string(),
+        _ => return None,
+    };
+
+    Some(codecs)
+}
+
+/// Check that the representations of each adaptation set, which have the same content type,
+/// start their subsegments at the same times, so that players can switch between them at any
+/// cluster boundary.
+pub fn check_alignment(representations: &[Representation]) -> Result<()> {
+    for (i, representation) in representations.iter().enumerate() {
+        let first = representations.iter()
+            .find(|other| other.media.content_type() == representation.media.content_type())
+            .unwrap();
+
+        if representation.cue_times != first.cue_times {
+            bail!(error::incompatible(i, representation.track, "CueTime"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Generate the manifest of a static presentation made of `representations`, which are grouped
+/// into one adaptation set per content type. The subsegments of the representations of each set
+/// must be aligned.
+pub fn manifest(representations: &[Representation]) -> Result<String> {
+    check_alignment(representations)?;
+
+    let duration = representations.iter().map(|representation| representation.duration).max().unwrap_or(0);
+
+    let mut mpd = String::new();
+    mpd.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    mpd.push_str(&format!("<MPD xmlns=\"urn:mpeg:dash:schema:mpd:2011\" type=\"static\" mediaPresentationDuration=\"{}\" \
+                           minBufferTime=\"PT1S\" profiles=\"{}\">\n", time(duration), PROFILE));
+    mpd.push_str(&format!("  <Period id=\"0\" start=\"PT0S\" duration=\"{}\">\n", time(duration)));
+
+    for (set, content_type) in ["video", "audio"].iter().enumerate() {
+        let members: Vec<(usize, &Representation)> = representations.iter().enumerate()
+            .filter(|&(_, representation)| representation.media.content_type() == *content_type)
+            .collect();
+
+        if members.is_empty() {
+            continue;
+        }
+
+        mpd.push_str(&format!("    <AdaptationSet id=\"{}\" mimeType=\"{}/webm\" subsegmentAlignment=\"true\" \
+                               subsegmentStartsWithSAP=\"1\" bitstreamSwitching=\"true\">\n", set, content_type));
+
+        for (id, representation) in members {
+            mpd.push_str(&format!("      <Representation id=\"{}\" bandwidth=\"{}\" codecs=\"{}\"",
+                                  id, representation.bandwidth, escape(&representation.codecs)));
+
+            match representation.media {
+                Media::Video { width, height } => {
+                    mpd.push_str(&format!(" width=\"{}\" height=\"{}\">\n", width, height));
+                },
+
+                Media::Audio { sampling_frequency, channels } => {
+                    mpd.push_str(&format!(" audioSamplingRate=\"{}\">\n", sampling_frequency));
+                    mpd.push_str(&format!("        <AudioChannelConfiguration \
+                                           schemeIdUri=\"urn:mpeg:dash:23003:3:audio_channel_configuration:2011\" \
+                                           value=\"{}\"/>\n", channels));
+                },
+            };
+
+            mpd.push_str(&format!("        <BaseURL>{}</BaseURL>\n", escape(&representation.url)));
+            mpd.push_str(&format!("        <SegmentBase indexRange=\"{}-{}\">\n",
+                                  representation.index_range.0, representation.index_range.1));
+            mpd.push_str(&format!("          <Initialization range=\"{}-{}\"/>\n",
+                                  representation.init_range.0, representation.init_range.1));
+            mpd.push_str("        </SegmentBase>\n");
+            mpd.push_str("      </Representation>\n");
+        }
+
+        mpd.push_str("    </AdaptationSet>\n");
+    }
+
+    mpd.push_str("  </Period>\n");
+    mpd.push_str("</MPD>\n");
+
+    Ok(mpd)
+}
+
+/// Format a duration in nanoseconds as an XML schema duration.
+fn time(duration: u64) -> String {
+    format!("PT{:.3}S", duration as f64 / 1e9)
+}
+
+/// Escape the characters of `text` that are not allowed in XML attributes and text.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,374 @@
+//! Edit the metadata of a matroska file in place, without rewriting its clusters.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use ::ebml as libebml;
+use self::libebml::types::*;
+
+use elements as el;
+use error::{self, Result};
+use reader::{meta_seek, segment, tag, track};
+use writer::{self, element};
+
+/// Location of a top-level element in the segment.
+#[derive(Clone, Copy)]
+struct Entry {
+    id: ElementId,
+
+    // Position relative to the segment data, and total size including the ID and size.
+    position: u64,
+    len: u64,
+}
+
+/// Editor of the segment information, the tracks and the tags of a matroska file. Modified
+/// elements are written back in place when they fit in their previous space, including any
+/// adjacent `Void` element. Otherwise, they are moved to the end of the segment and the `SeekHead`
+/// is updated.
+pub struct Editor<F: Read + Write + Seek> {
+    f: F,
+
+    // Absolute position of the segment data, and length of the segment size VINT that precedes
+    // it. The length is `None` if the segment size is unknown.
+    segment_offset: u64,
+    segment_size_len: Option<usize>,
+
+    // End of the segment data relative to the segment data, and wether new elements can be
+    // written there.
+    segment_end: u64,
+    appendable: bool,
+
+    elements: Vec<Entry>,
+    // Entries of the first `SeekHead`, in their original order.
+    seek_head: Option<Vec<(ElementId, u64)>>,
+
+    info: segment::Info,
+    tracks: Vec<track::Info>,
+    tags: Vec<tag::Tag>,
+}
+
+impl<F: Read + Write + Seek> Editor<F> {
+    /// Open a matroska file for edition. This function reads the location of all the top-level
+    /// elements of the first segment, skipping over the clusters.
+    pub fn open(mut f: F) -> Result<Editor<F>> {
+        let file_len = f.seek(SeekFrom::End(0))?;
+        f.seek(SeekFrom::Start(0))?;
+
+        libebml::reader::read_header(&mut f)?;
+
+        let (id, size, c) = libebml::reader::read_element_info(&mut f)?;
+        if id != el::SEGMENT {
+            bail!(error::unexpected(el::SEGMENT, id));
+        }
+
+        let segment_offset = f.seek(SeekFrom::Current(0))?;
+        let size_len = c - element::id(el::SEGMENT).len();
+
+        // An unknown segment size extends to the end of the file.
+        let (segment_end, segment_size_len) = if size as u64 == (1 << (7 * size_len)) - 1 || segment_offset + size as u64 > file_len {
+            (file_len - segment_offset, None)
+        } else {
+            (size as u64, Some(size_len))
+        };
+
+        let mut elements = Vec::new();
+        let mut seek_head = None;
+        let mut info = None;
+        let mut tracks = None;
+        let mut tags = None;
+
+        let mut position = 0;
+
+        while position < segment_end {
+            let (id, size, c) = libebml::reader::read_element_info(&mut f)?;
+            let len = c as u64 + size as u64;
+
+            elements.push(Entry {
+                id: id,
+                position: position,
+                len: len,
+            });
+
+            match id {
+                el::SEEK_HEAD | el::INFO | el::TRACKS | el::TAGS => {
+                    let (content, _) = libebml::reader::read_element_content(&mut f, size)?;
+                    let children = content.children()?;
+
+                    match id {
+                        el::SEEK_HEAD if seek_head.is_none() => seek_head = Some(meta_seek::read_entries(children)?),
+                        el::INFO if info.is_none() => info = Some(segment::read(children)?),
+                        el::TRACKS if tracks.is_none() => tracks = Some(track::read(children)?),
+                        el::TAGS if tags.is_none() => tags = Some(tag::read(children)?),
+                        _ => {},
+                    };
+                },
+
+                _ => {
+                    f.seek(SeekFrom::Start(segment_offset + position + len))?;
+                },
+            };
+
+            position += len;
+        }
+
+        Ok(Editor {
+            f: f,
+            segment_offset: segment_offset,
+            segment_size_len: segment_size_len,
+            // Elements can only be appended if the segment ends exactly at the end of the file.
+            appendable: position == segment_end && segment_offset + segment_end == file_len,
+            segment_end: segment_end,
+            elements: elements,
+            seek_head: seek_head,
+            info: info.ok_or(error::not_found(el::INFO))?,
+            tracks: tracks.ok_or(error::not_found(el::TRACKS))?,
+            tags: tags.unwrap_or_else(Vec::new),
+        })
+    }
+
+    /// Segment information of the file.
+    pub fn info(&self) -> &segment::Info {
+        &self.info
+    }
+
+    /// Mutable segment information of the file. Call `write_info` to save the modifications.
+    pub fn info_mut(&mut self) -> &mut segment::Info {
+        &mut self.info
+    }
+
+    /// Tracks of the file.
+    pub fn tracks(&self) -> &[track::Info] {
+        self.tracks.as_slice()
+    }
+
+    /// Mutable tracks of the file. Call `write_tracks` to save the modifications.
+    pub fn tracks_mut(&mut self) -> &mut Vec<track::Info> {
+        &mut self.tracks
+    }
+
+    /// Tags of the file.
+    pub fn tags(&self) -> &[tag::Tag] {
+        self.tags.as_slice()
+    }
+
+    /// Mutable tags of the file. Call `write_tags` to save the modifications.
+    pub fn tags_mut(&mut self) -> &mut Vec<tag::Tag> {
+        &mut self.tags
+    }
+
+    /// Write the segment information back to the file.
+    pub fn write_info(&mut self) -> Result<()> {
+        let data = writer::encode_info(&self.info);
+        self.rewrite(el::INFO, data)
+    }
+
+    /// Write the tracks back to the file.
+    pub fn write_tracks(&mut self) -> Result<()> {
+        let data = writer::encode_tracks(&self.tracks);
+        self.rewrite(el::TRACKS, data)
+    }
+
+    /// Write the tags back to the file.
+    pub fn write_tags(&mut self) -> Result<()> {
+        let data = writer::encode_tags(&self.tags);
+        self.rewrite(el::TAGS, data)
+    }
+
+    /// Return the underlying file. Consumes `self`.
+    pub fn into_inner(self) -> F {
+        self.f
+    }
+
+    /// Replace the top-level element `id` with the encoded element `data`. All the changes are
+    /// planned and checked before the file is written, so that it is left unchanged on error.
+    fn rewrite(&mut self, id: ElementId, data: Vec<u8>) -> Result<()> {
+        let mut elements = self.elements.clone();
+
+        // Writes to make, at positions relative to the segment data, in order.
+        let mut writes = Vec::new();
+
+        // Previous position of the element, if it is moved.
+        let mut previous = None;
+
+        if let Some(i) = elements.iter().position(|entry| entry.id == id) {
+            if let Some(write) = place(&mut elements, i, &data) {
+                writes.push(write);
+                return self.commit(elements, writes, self.segment_end);
+            }
+
+            if !self.appendable {
+                bail!(error::invalid_value(id, "not enough space to rewrite the element in place"));
+            }
+
+            // Blank out the previous location of the element, including the adjacent voids.
+            let (end, available) = available_space(&elements, i);
+            let position = elements[i].position;
+            previous = Some(position);
+
+            writes.push((position, element::void(available as usize)));
+            elements.splice(i..end, Some(Entry { id: el::VOID, position: position, len: available }));
+        } else if !self.appendable {
+            bail!(error::invalid_value(id, "cannot append the element to the segment"));
+        }
+
+        // Append the element at the end of the segment.
+        let position = self.segment_end;
+        let segment_end = position + data.len() as u64;
+
+        if let Some(size_len) = self.segment_size_len {
+            if segment_end >= (1 << (7 * size_len)) - 1 {
+                bail!(error::invalid_value(el::SEGMENT, segment_end));
+            }
+        }
+
+        elements.push(Entry {
+            id: id,
+            position: position,
+            len: data.len() as u64,
+        });
+
+        writes.insert(0, (position, data));
+
+        // Point the `SeekHead` entry of the element at its new position, or add one if the element
+        // was not referenced. The other entries are kept as they are, including the ones that
+        // reference other elements with the same ID. Without a `SeekHead`, readers have to scan
+        // the segment to find the elements.
+        let seek_head = match self.seek_head {
+            Some(ref seek_head) => {
+                let mut seek_head = seek_head.clone();
+
+                let mut referenced = false;
+
+                for entry in seek_head.iter_mut().filter(|entry| entry.0 == id && Some(entry.1) == previous) {
+                    entry.1 = position;
+                    referenced = true;
+                }
+
+                if !referenced {
+                    seek_head.push((id, position));
+                }
+
+                let i = elements.iter().position(|entry| entry.id == el::SEEK_HEAD)
+                    .ok_or(error::not_found(el::SEEK_HEAD))?;
+
+                match place(&mut elements, i, &encode_seek_head(&seek_head)) {
+                    Some(write) => writes.push(write),
+                    None => bail!(error::invalid_value(el::SEEK_HEAD, "not enough space to update the SeekHead")),
+                };
+
+                Some(seek_head)
+            },
+
+            None => None,
+        };
+
+        self.commit(elements, writes, segment_end)?;
+        self.seek_head = seek_head;
+
+        Ok(())
+    }
+
+    /// Make the planned `writes`, update the segment size to `segment_end`, and replace the
+    /// locations of the elements with `elements`.
+    fn commit(&mut self, elements: Vec<Entry>, writes: Vec<(u64, Vec<u8>)>, segment_end: u64) -> Result<()> {
+        for (position, data) in writes {
+            self.write_at(position, &data)?;
+        }
+
+        if let (Some(size_len), true) = (self.segment_size_len, segment_end != self.segment_end) {
+            let size = element::vint(segment_end, size_len);
+            self.f.seek(SeekFrom::Start(self.segment_offset - size_len as u64))?;
+            self.f.write_all(&size)?;
+        }
+
+        self.elements = elements;
+        self.segment_end = segment_end;
+
+        Ok(())
+    }
+
+    fn write_at(&mut self, position: u64, data: &[u8]) -> Result<()> {
+        self.f.seek(SeekFrom::Start(self.segment_offset + position))?;
+        self.f.write_all(data)?;
+
+        Ok(())
+    }
+}
+
+/// Return the index following the `Void` elements adjacent to the element at index `i`, and the
+/// total space occupied by the element and these voids.
+fn available_space(elements: &[Entry], i: usize) -> (usize, u64) {
+    let mut end = i + 1;
+    let mut available = elements[i].len;
+
+    while end < elements.len() && elements[end].id == el::VOID {
+        available += elements[end].len;
+        end += 1;
+    }
+
+    (end, available)
+}
+
+/// Place the encoded element `data` over the element at index `i` of `elements` if it fits in its
+/// space, and update `elements` accordingly. Returns the position and the data to write.
+fn place(elements: &mut Vec<Entry>, i: usize, data: &[u8]) -> Option<(u64, Vec<u8>)> {
+    let (end, available) = available_space(elements, i);
+    let position = elements[i].position;
+
+    let fitted = fit(data, available as usize)?;
+    let mut entries = vec![Entry { id: elements[i].id, position: position, len: available }];
+
+    if data.len() + 2 <= available as usize {
+        entries[0].len = data.len() as u64;
+        entries.push(Entry {
+            id: el::VOID,
+            position: position + data.len() as u64,
+            len: available - data.len() as u64,
+        });
+    }
+
+    elements.splice(i..end, entries);
+    Some((position, fitted))
+}
+
+/// Encode the `SeekHead` element with the given entries.
+fn encode_seek_head(entries: &[(ElementId, u64)]) -> Vec<u8> {
+    element::master(el::SEEK_HEAD, entries.iter().map(|&(id, position)| {
+        element::master(el::SEEK, vec![
+            element::binary(el::SEEK_ID, &element::id(id)),
+            element::uint(el::SEEK_POSITION, position),
+        ])
+    }).collect())
+}
+
+/// Fit the encoded element `data` in exactly `available` bytes, by following it with a `Void`
+/// element or by widening its size VINT. Returns `None` if it does not fit.
+fn fit(data: &[u8], available: usize) -> Option<Vec<u8>> {
+    if data.len() == available {
+        return Some(data.to_vec());
+    }
+
+    if data.len() + 2 <= available {
+        let mut fitted = data.to_vec();
+        fitted.extend(element::void(available - data.len()));
+
+        return Some(fitted);
+    }
+
+    if data.len() + 1 == available {
+        // A `Void` element takes at least 2 bytes: store the size on one more byte instead.
+        let id_len = (data[0].leading_zeros() + 1) as usize;
+        let size_len = (data[id_len].leading_zeros() + 1) as usize;
+
+        if size_len < 8 {
+            let content = &data[id_len + size_len..];
+
+            let mut fitted = data[..id_len].to_vec();
+            fitted.extend(element::vint(content.len() as u64, size_len + 1));
+            fitted.extend_from_slice(content);
+
+            return Some(fitted);
+        }
+    }
+
+    None
+}
@@ -2,6 +2,17 @@
 
 use ebml::types::UnsignedInt;
 
+// EBML header.
+
+pub const EBML: UnsignedInt = 0x1A45DFA3;
+pub const EBML_VERSION: UnsignedInt = 0x4286;
+pub const EBML_READ_VERSION: UnsignedInt = 0x42F7;
+pub const EBML_MAX_ID_LENGTH: UnsignedInt = 0x42F2;
+pub const EBML_MAX_SIZE_LENGTH: UnsignedInt = 0x42F3;
+pub const DOC_TYPE: UnsignedInt = 0x4282;
+pub const DOC_TYPE_VERSION: UnsignedInt = 0x4287;
+pub const DOC_TYPE_READ_VERSION: UnsignedInt = 0x4285;
+
 // Global elements.
 
 pub const VOID: UnsignedInt = 0xEC;
@@ -217,11 +228,52 @@ pub const ENCRYPTED_BLOCK: UnsignedInt = 0xAF;
 // Attachements.
 
 pub const ATTACHEMENTS: UnsignedInt = 0x1941A469;
+pub const ATTACHED_FILE: UnsignedInt = 0x61A7;
+pub const FILE_DESCRIPTION: UnsignedInt = 0x467E;
+pub const FILE_NAME: UnsignedInt = 0x466E;
+pub const FILE_MIME_TYPE: UnsignedInt = 0x4660;
+pub const FILE_DATA: UnsignedInt = 0x465C;
+pub const FILE_UID: UnsignedInt = 0x46AE;
 
 // Chapters.
 
 pub const CHAPTERS: UnsignedInt = 0x1043A770;
+pub const EDITION_ENTRY: UnsignedInt = 0x45B9;
+pub const EDITION_UID: UnsignedInt = 0x45BC;
+pub const EDITION_FLAG_HIDDEN: UnsignedInt = 0x45BD;
+pub const EDITION_FLAG_DEFAULT: UnsignedInt = 0x45DB;
+pub const EDITION_FLAG_ORDERED: UnsignedInt = 0x45DD;
+pub const CHAPTER_ATOM: UnsignedInt = 0xB6;
+pub const CHAPTER_UID: UnsignedInt = 0x73C4;
+pub const CHAPTER_STRING_UID: UnsignedInt = 0x5654;
+pub const CHAPTER_TIME_START: UnsignedInt = 0x91;
+pub const CHAPTER_TIME_END: UnsignedInt = 0x92;
+pub const CHAPTER_FLAG_HIDDEN: UnsignedInt = 0x98;
+pub const CHAPTER_FLAG_ENABLED: UnsignedInt = 0x4598;
+pub const CHAPTER_SEGMENT_UID: UnsignedInt = 0x6E67;
+pub const CHAPTER_SEGMENT_EDITION_UID: UnsignedInt = 0x6EBC;
+pub const CHAPTER_PHYSICAL_EQUIV: UnsignedInt = 0x63C3;
+pub const CHAPTER_TRACK: UnsignedInt = 0x8F;
+pub const CHAPTER_TRACK_NUMBER: UnsignedInt = 0x89;
+pub const CHAPTER_DISPLAY: UnsignedInt = 0x80;
+pub const CHAP_STRING: UnsignedInt = 0x85;
+pub const CHAP_LANGUAGE: UnsignedInt = 0x437C;
+pub const CHAP_COUNTRY: UnsignedInt = 0x437E;
 
 // Tagging.
 
 pub const TAGS: UnsignedInt = 0x1254C367;
+pub const TAG: UnsignedInt = 0x7373;
+pub const TARGETS: UnsignedInt = 0x63C0;
+pub const TARGET_TYPE_VALUE: UnsignedInt = 0x68CA;
+pub const TARGET_TYPE: UnsignedInt = 0x63CA;
+pub const TAG_TRACK_UID: UnsignedInt = 0x63C5;
+pub const TAG_EDITION_UID: UnsignedInt = 0x63C9;
+pub const TAG_CHAPTER_UID: UnsignedInt = 0x63C4;
+pub const TAG_ATTACHMENT_UID: UnsignedInt = 0x63C6;
+pub const SIMPLE_TAG: UnsignedInt = 0x67C8;
+pub const TAG_NAME: UnsignedInt = 0x45A3;
+pub const TAG_LANGUAGE: UnsignedInt = 0x447A;
+pub const TAG_DEFAULT: UnsignedInt = 0x4484;
+pub const TAG_STRING: UnsignedInt = 0x4487;
+pub const TAG_BINARY: UnsignedInt = 0x4485;
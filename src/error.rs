@@ -2,6 +2,8 @@
 
 use std::fmt::Display;
 
+use schema;
+
 error_chain! {
     errors {
         UnexpectedEof {
@@ -22,6 +24,31 @@ error_chain! {
             description("Got an invalid value for an element"),
             display("Invalid value for element '0x{:X}': {}", el, val)
         }
+
+        IncompatibleTrack(input: usize, track: u64, field: String) {
+            description("Incompatible track"),
+            display("Track {} of input #{} is incompatible: different {}", track, input, field)
+        }
+
+        ChecksumMismatch(parent: u64, expected: u32, actual: u32) {
+            description("Checksum mismatch"),
+            display("Checksum mismatch in element '0x{:X}': expected {:08X}, got {:08X}", parent, expected, actual)
+        }
+
+        InvalidIndex(reason: String) {
+            description("Invalid sample table index"),
+            display("Invalid sample table index: {}", reason)
+        }
+
+        LimitExceeded(limit: String, value: u64, max: u64) {
+            description("Reader limit exceeded"),
+            display("The {} of {} exceeds the limit of {}", limit, value, max)
+        }
+
+        Located(kind: Box<ErrorKind>, offset: Option<u64>, path: String) {
+            description("Error in an element of the file"),
+            display("{} (in {}{})", kind, path, offset.map_or(String::new(), |offset| format!(" at byte {}", offset)))
+        }
     }
 
     foreign_links {
@@ -49,3 +76,80 @@ pub fn unexpected(expected: u64, got: u64) -> Error {
 pub fn invalid_value<D: Display>(el: u64, val: D) -> Error {
     Error::from(ErrorKind::InvalidElementValue(el, format!("{}", val)))
 }
+
+/// Construct an `IncompatibleTrack` error.
+pub fn incompatible<D: Display>(input: usize, track: u64, field: D) -> Error {
+    Error::from(ErrorKind::IncompatibleTrack(input, track, format!("{}", field)))
+}
+
+/// Construct a `ChecksumMismatch` error.
+pub fn checksum_mismatch(parent: u64, expected: u32, actual: u32) -> Error {
+    Error::from(ErrorKind::ChecksumMismatch(parent, expected, actual))
+}
+
+/// Construct an `InvalidIndex` error.
+pub fn invalid_index<D: Display>(reason: D) -> Error {
+    Error::from(ErrorKind::InvalidIndex(format!("{}", reason)))
+}
+
+/// Construct a `LimitExceeded` error.
+pub fn limit_exceeded<D: Display>(limit: D, value: u64, max: u64) -> Error {
+    Error::from(ErrorKind::LimitExceeded(format!("{}", limit), value, max))
+}
+
+/// Add the element `id` at the start of the path of the element where `err` occurred. `index` is
+/// the 1-based position of the element among its siblings with the same ID, for elements that
+/// can be repeated.
+pub(crate) fn within(err: Error, id: u64, index: Option<usize>) -> Error {
+    let name = match index {
+        Some(index) => format!("{}[{}]", schema::name(id), index),
+        None => schema::name(id),
+    };
+
+    match err {
+        Error(ErrorKind::Located(kind, offset, path), state) => {
+            Error(ErrorKind::Located(kind, offset, format!("{}/{}", name, path)), state)
+        },
+
+        Error(kind, state) => Error(ErrorKind::Located(Box::new(kind), None, name), state),
+    }
+}
+
+/// Set the absolute position of the element where `err` occurred, unless it is already known.
+pub(crate) fn at(err: Error, position: u64) -> Error {
+    match err {
+        Error(ErrorKind::Located(kind, None, path), state) => {
+            Error(ErrorKind::Located(kind, Some(position), path), state)
+        },
+
+        err => err,
+    }
+}
+
+impl Error {
+    /// Absolute position in bytes of the element where the error occurred, if known.
+    pub fn offset(&self) -> Option<u64> {
+        match self.0 {
+            ErrorKind::Located(_, offset, _) => offset,
+            _ => None,
+        }
+    }
+
+    /// Path of the element where the error occurred, such as
+    /// `Segment/Tracks/TrackEntry[2]/Video`, if known.
+    pub fn path(&self) -> Option<&str> {
+        match self.0 {
+            ErrorKind::Located(_, _, ref path) => Some(path.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Kind of the error, regardless of its location. Errors found in an element of the file have
+    /// the `Located` kind, which wraps this one: match on this kind rather than on `kind()`.
+    pub fn base_kind(&self) -> &ErrorKind {
+        match self.0 {
+            ErrorKind::Located(ref kind, _, _) => kind,
+            ref kind => kind,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+//! ADTS stream, for AAC audio.
+
+use std::io::{Seek, Write};
+
+use codec::{aac, CodecConfig};
+use elements as el;
+use error::{self, Result};
+use reader::track;
+
+use super::{Format, Packet};
+
+/// Prefixes each AAC frame with an ADTS header.
+pub struct Adts {
+    profile: u8,
+    frequency_index: u8,
+    channels: u8,
+}
+
+impl Adts {
+    /// Create the format from the `AudioSpecificConfig` of `track` or, if there is none, from its
+    /// codec ID and audio parameters.
+    pub fn new(track: &track::Info) -> Result<Adts> {
+        if let Some(CodecConfig::Aac(config)) = track.codec_config()? {
+            let frequency_index = config.sampling_frequency_index
+                .ok_or(error::invalid_value(el::SAMPLING_FREQUENCY, config.sample_rate))?;
+
+            // ADTS can only signal the first four object types: SBR and PS streams are signaled
+            // as their AAC LC core.
+            let profile = match config.object_type {
+                1..=4 => config.object_type - 1,
+                _ => 1,
+            };
+
+            return Ok(Adts {
+                profile: profile,
+                frequency_index: frequency_index,
+                channels: config.channel_config,
+            });
+        }
+
+        // Without a profile in the codec ID, the stream is assumed to be AAC LC.
+        let (frequency, channels) = match *track.kind() {
+            track::Kind::Audio(ref audio) => (audio.sampling_frequency() as u32, audio.channels() as u8),
+            _ => bail!(error::not_found(el::AUDIO)),
+        };
+
+        let frequency_index = aac::SAMPLING_FREQUENCIES.iter().position(|f| *f == frequency)
+            .ok_or(error::invalid_value(el::SAMPLING_FREQUENCY, frequency))?;
+
+        Ok(Adts {
+            profile: 1,
+            frequency_index: frequency_index as u8,
+            channels: channels,
+        })
+    }
+}
+
+impl<W: Write + Seek> Format<W> for Adts {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], _: &Packet) -> Result<()> {
+        let len = frame.len() + 7;
+        if len >= 1 << 13 {
+            bail!(error::invalid_value(el::SIMPLE_BLOCK, "AAC frame too large for ADTS"));
+        }
+
+        // Header without CRC, with a buffer fullness of 0x7FF (variable bitrate).
+        w.write_all(&[
+            0xFF,
+            0xF1,
+            (self.profile << 6) | (self.frequency_index << 2) | (self.channels >> 2),
+            ((self.channels & 0x03) << 6) | (len >> 11) as u8,
+            (len >> 3) as u8,
+            ((len & 0x07) << 5) as u8 | 0x1F,
+            0xFC,
+        ])?;
+
+        w.write_all(frame)?;
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        w.flush()?;
+        Ok(())
+    }
+}
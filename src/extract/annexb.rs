@@ -0,0 +1,75 @@
+//! Annex-B byte stream, for AVC and HEVC video.
+
+use std::io::{Seek, Write};
+
+use codec::{avc, hevc};
+use elements as el;
+use error::{self, Result};
+
+use super::{Format, Packet};
+
+const START_CODE: &'static [u8] = &[0x00, 0x00, 0x00, 0x01];
+
+/// Converts length-prefixed NAL units to start code-prefixed NAL units.
+pub struct AnnexB {
+    length_size: usize,
+}
+
+impl AnnexB {
+    /// Write the parameter sets of an `AVCDecoderConfigurationRecord` and return the format.
+    pub fn avc<W: Write>(w: &mut W, private: Option<&[u8]>) -> Result<AnnexB> {
+        let config = avc::parse_config(private.ok_or(error::not_found(el::CODEC_PRIVATE))?)?;
+
+        for unit in config.sps.iter().chain(config.pps.iter()) {
+            w.write_all(START_CODE)?;
+            w.write_all(unit)?;
+        }
+
+        Ok(AnnexB {
+            length_size: config.nal_length_size as usize,
+        })
+    }
+
+    /// Write the parameter sets of an `HEVCDecoderConfigurationRecord` and return the format.
+    pub fn hevc<W: Write>(w: &mut W, private: Option<&[u8]>) -> Result<AnnexB> {
+        let config = hevc::parse_config(private.ok_or(error::not_found(el::CODEC_PRIVATE))?)?;
+
+        for &(_, ref unit) in &config.nal_units {
+            w.write_all(START_CODE)?;
+            w.write_all(unit)?;
+        }
+
+        Ok(AnnexB {
+            length_size: config.nal_length_size as usize,
+        })
+    }
+}
+
+impl<W: Write + Seek> Format<W> for AnnexB {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], _: &Packet) -> Result<()> {
+        let mut pos = 0;
+
+        while pos + self.length_size <= frame.len() {
+            let len = frame[pos..pos + self.length_size].iter()
+                .fold(0, |len, byte| (len << 8) | *byte as usize);
+
+            pos += self.length_size;
+
+            if pos + len > frame.len() {
+                bail!(error::unexpected_eof());
+            }
+
+            w.write_all(START_CODE)?;
+            w.write_all(&frame[pos..pos + len])?;
+
+            pos += len;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+//! IVF container, for VP8, VP9 and AV1 video.
+
+use std::io::{Seek, SeekFrom, Write};
+
+use error::Result;
+use reader::track;
+
+use super::{write_le, Format, Packet};
+
+/// Writes frames in an IVF file. Timestamps are stored in milliseconds.
+pub struct Ivf {
+    frames: u64,
+}
+
+impl Ivf {
+    /// Write the IVF file header for `track`.
+    pub fn new<W: Write>(w: &mut W, track: &track::Info) -> Result<Ivf> {
+        let fourcc = match track.codec() {
+            "V_VP8" => b"VP80",
+            "V_VP9" => b"VP90",
+            _ => b"AV01",
+        };
+
+        let (width, height) = match *track.kind() {
+            track::Kind::Video(ref video) => (video.width(), video.height()),
+            _ => (0, 0),
+        };
+
+        w.write_all(b"DKIF")?;
+        write_le(w, 0, 2)?;
+        write_le(w, 32, 2)?;
+        w.write_all(fourcc)?;
+        write_le(w, width, 2)?;
+        write_le(w, height, 2)?;
+
+        // Time base of 1/1000 second.
+        write_le(w, 1000, 4)?;
+        write_le(w, 1, 4)?;
+
+        // The frame count is updated once all the frames are written.
+        write_le(w, 0, 4)?;
+        write_le(w, 0, 4)?;
+
+        Ok(Ivf {
+            frames: 0,
+        })
+    }
+}
+
+impl<W: Write + Seek> Format<W> for Ivf {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], packet: &Packet) -> Result<()> {
+        write_le(w, frame.len() as u64, 4)?;
+        write_le(w, packet.timestamp / 1000000, 8)?;
+        w.write_all(frame)?;
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        let end = w.seek(SeekFrom::Current(0))?;
+
+        w.seek(SeekFrom::Start(24))?;
+        write_le(w, self.frames, 4)?;
+        w.seek(SeekFrom::Start(end))?;
+
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+//! Extract the elementary stream of a track into its natural raw container.
+
+pub mod ivf;
+pub mod annexb;
+pub mod adts;
+pub mod ogg;
+pub mod wav;
+pub mod text;
+
+use std::io::{Read, Seek, Write};
+
+use elements as el;
+use error::{self, Result};
+use reader::Reader;
+use reader::track;
+
+/// Timing information of a frame.
+pub struct Packet {
+    /// Presentation timestamp in nanoseconds.
+    pub timestamp: u64,
+
+    /// Optional. Duration in nanoseconds.
+    pub duration: Option<u64>,
+
+    pub keyframe: bool,
+}
+
+/// Raw container format that frames are written into.
+pub trait Format<W: Write + Seek> {
+    /// Write a frame of the track.
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], packet: &Packet) -> Result<()>;
+
+    /// Complete the output once all the frames have been written.
+    fn finish(&mut self, w: &mut W) -> Result<()>;
+}
+
+/// Format that writes frames as-is after an optional header, e.g. FLAC.
+pub struct Raw;
+
+impl Raw {
+    /// Write `header` and return the format.
+    pub fn new<W: Write>(w: &mut W, header: &[u8]) -> Result<Raw> {
+        w.write_all(header)?;
+        Ok(Raw)
+    }
+}
+
+impl<W: Write + Seek> Format<W> for Raw {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], _: &Packet) -> Result<()> {
+        w.write_all(frame)?;
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Create the format corresponding to the codec of `track`, writing its header to `w`.
+pub fn format<W: Write + Seek>(w: &mut W, track: &track::Info) -> Result<Box<dyn Format<W>>> {
+    let codec = track.codec();
+
+    Ok(match codec {
+        "V_VP8" | "V_VP9" | "V_AV1" => Box::new(ivf::Ivf::new(w, track)?),
+        "V_MPEG4/ISO/AVC" => Box::new(annexb::AnnexB::avc(w, track.codec_private())?),
+        "V_MPEGH/ISO/HEVC" => Box::new(annexb::AnnexB::hevc(w, track.codec_private())?),
+        "A_OPUS" => Box::new(ogg::Ogg::opus(w, track)?),
+        "A_VORBIS" => Box::new(ogg::Ogg::vorbis(w, track)?),
+        "A_FLAC" => Box::new(Raw::new(w, track.codec_private().unwrap_or(&[]))?),
+        "S_TEXT/UTF8" => Box::new(text::Srt::new()),
+        "S_TEXT/ASS" | "S_TEXT/SSA" => Box::new(text::Ass::new(w, track.codec_private())?),
+        "S_TEXT/WEBVTT" => Box::new(text::WebVtt::new(w, track.codec_private())?),
+
+        _ if codec.starts_with("A_AAC") => Box::new(adts::Adts::new(track)?),
+        _ if codec.starts_with("A_PCM/") => Box::new(wav::Wav::new(w, track)?),
+
+        _ => bail!(error::invalid_value(el::CODEC_ID, codec)),
+    })
+}
+
+/// Extract the frames of the track number `track` from `input` into `output`. The output format
+/// is chosen from the codec of the track. Frames compressed with header stripping are restored,
+/// while other content encodings return an error. Returns the output once complete.
+pub fn extract<R, W>(input: &mut Reader<R>, track: u64, mut output: W) -> Result<W>
+    where R: Read, W: Write + Seek
+{
+    let (segment, tracks) = input.header_info()?;
+    let scale = segment.timecode_scale();
+
+    let mut info = tracks.into_iter().find(|info| info.index() == track)
+        .ok_or(error::invalid_value(el::TRACK_NUMBER, track))?;
+
+    // Encodings are reverted on the codec private data before the format reads its headers, and
+    // on each frame before it is written.
+    let encodings = info.content_encodings()?;
+
+    if encodings.iter().any(|encoding| encoding.codec_private()) {
+        let private = match info.codec_private() {
+            Some(private) => Some(decode(encodings.iter().filter(|encoding| encoding.codec_private()), private)?),
+            None => None,
+        };
+
+        info.set_codec_private(private);
+    }
+
+    let mut format = format(&mut output, &info)?;
+    let default_duration = info.default_duration();
+
+    while let Some(mut cluster) = input.next_cluster()? {
+        for block in cluster.blocks() {
+            let block = block?;
+
+            if block.track() != track {
+                continue;
+            }
+
+            let timestamp = block.absolute_timecode().max(0) as u64 * scale;
+            let duration = block.duration().map(|d| d * scale);
+            let keyframe = block.keyframe();
+
+            let frames = block.frames()?;
+            let count = frames.len() as u64;
+
+            // Laced frames share the timestamp of their block: spread them using the default
+            // duration of the track when it is known.
+            for (i, frame) in frames.into_iter().enumerate() {
+                let offset = default_duration.map_or(0, |d| d * i as u64);
+
+                let packet = Packet {
+                    timestamp: timestamp + offset,
+                    duration: duration.map(|d| d / count).or(default_duration),
+                    keyframe: keyframe,
+                };
+
+                let frame = decode(encodings.iter().filter(|encoding| encoding.frames()), &frame)?;
+                format.write_frame(&mut output, &frame, &packet)?;
+            }
+        }
+    }
+
+    format.finish(&mut output)?;
+    Ok(output)
+}
+
+/// Revert `encodings` on `data`, in order.
+fn decode<'a, I>(encodings: I, data: &[u8]) -> Result<Vec<u8>>
+    where I: Iterator<Item = &'a track::ContentEncoding>
+{
+    let mut data = data.to_vec();
+
+    for encoding in encodings {
+        data = encoding.decode(&data)?;
+    }
+
+    Ok(data)
+}
+
+/// Write a little-endian integer of `len` bytes.
+pub(crate) fn write_le<W: Write>(w: &mut W, value: u64, len: usize) -> Result<()> {
+    let bytes: Vec<u8> = (0..len).map(|i| (value >> (8 * i)) as u8).collect();
+    w.write_all(&bytes)?;
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+//! Ogg container, for Opus and Vorbis audio.
+
+use std::io::{Seek, Write};
+
+use codec::{opus, vorbis};
+use elements as el;
+use error::{self, Result};
+use reader::track;
+
+use super::{Format, Packet};
+
+/// Writes each packet in its own Ogg page. Granule positions are derived from the timestamps.
+pub struct Ogg {
+    serial: u32,
+    sequence: u32,
+
+    // Granule positions are expressed at this rate, and offset by the number of samples to skip.
+    rate: u64,
+    pre_skip: u64,
+
+    // The last packet is held back so that it can be written with the end-of-stream flag.
+    pending: Option<(Vec<u8>, u64)>,
+}
+
+impl Ogg {
+    /// Write the Opus headers of `track` and return the format.
+    pub fn opus<W: Write>(w: &mut W, track: &track::Info) -> Result<Ogg> {
+        let head = track.codec_private().ok_or(error::not_found(el::CODEC_PRIVATE))?;
+        let pre_skip = opus::parse_head(head)?.pre_skip as u64;
+
+        let mut ogg = Ogg::new(opus::SAMPLE_RATE as u64, pre_skip);
+        ogg.write_page(w, head, 0, 0x02)?;
+
+        let mut tags = b"OpusTags".to_vec();
+        tags.extend_from_slice(&[6, 0, 0, 0]);
+        tags.extend_from_slice(b"mkv-rs");
+        tags.extend_from_slice(&[0, 0, 0, 0]);
+
+        ogg.write_page(w, &tags, 0, 0x00)?;
+
+        Ok(ogg)
+    }
+
+    /// Write the three Vorbis headers of `track` and return the format.
+    pub fn vorbis<W: Write>(w: &mut W, track: &track::Info) -> Result<Ogg> {
+        let private = track.codec_private().ok_or(error::not_found(el::CODEC_PRIVATE))?;
+        let headers = vorbis::parse_headers(private)?;
+
+        let mut ogg = Ogg::new(headers.sample_rate as u64, 0);
+
+        for (i, header) in headers.packets.iter().enumerate() {
+            ogg.write_page(w, header, 0, if i == 0 { 0x02 } else { 0x00 })?;
+        }
+
+        Ok(ogg)
+    }
+
+    fn new(rate: u64, pre_skip: u64) -> Ogg {
+        Ogg {
+            serial: 0x6D6B7673,
+            sequence: 0,
+            rate: rate,
+            pre_skip: pre_skip,
+            pending: None,
+        }
+    }
+
+    /// Write `packet` in one or more pages, the last of which gets the `granule` position.
+    fn write_page<W: Write>(&mut self, w: &mut W, packet: &[u8], granule: u64, flags: u8) -> Result<()> {
+        // A page holds at most 255 segments of 255 bytes, including the terminating segment.
+        let chunks: Vec<&[u8]> = if packet.is_empty() { vec![packet] } else { packet.chunks(254 * 255).collect() };
+        let count = chunks.len();
+
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let last = i + 1 == count;
+
+            let mut lacing = vec![255u8; chunk.len() / 255];
+            if last {
+                lacing.push((chunk.len() % 255) as u8);
+            }
+
+            // Continuation, beginning of stream and end of stream flags.
+            let mut header_type = if i > 0 { 0x01 } else { flags & 0x02 };
+            if last {
+                header_type |= flags & 0x04;
+            }
+
+            let mut page = b"OggS".to_vec();
+            page.push(0);
+            page.push(header_type);
+
+            // Pages that do not complete a packet have no granule position.
+            let granule = if last { granule } else { u64::max_value() };
+            page.extend((0..8).map(|i| (granule >> (8 * i)) as u8));
+            page.extend((0..4).map(|i| (self.serial >> (8 * i)) as u8));
+            page.extend((0..4).map(|i| (self.sequence >> (8 * i)) as u8));
+            page.extend_from_slice(&[0, 0, 0, 0]);
+            page.push(lacing.len() as u8);
+            page.extend(lacing);
+            page.extend_from_slice(chunk);
+
+            let crc = crc(&page);
+            for j in 0..4 {
+                page[22 + j] = (crc >> (8 * j)) as u8;
+            }
+
+            w.write_all(&page)?;
+            self.sequence += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> Format<W> for Ogg {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], packet: &Packet) -> Result<()> {
+        // The granule position of a page is the position of the last sample it ends, which is
+        // the start of the next packet.
+        let start = self.pre_skip + packet.timestamp * self.rate / 1000000000;
+
+        if let Some((data, _)) = self.pending.take() {
+            self.write_page(w, &data, start, 0x00)?;
+        }
+
+        let end = start + packet.duration.map_or(0, |d| d * self.rate / 1000000000);
+        self.pending = Some((frame.to_vec(), end));
+
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        if let Some((data, end)) = self.pending.take() {
+            self.write_page(w, &data, end, 0x04)?;
+        }
+
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Compute the CRC-32 of an Ogg page: polynomial 0x04C11DB7, no reflection, initial value 0.
+fn crc(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+
+    for byte in data {
+        crc ^= (*byte as u32) << 24;
+
+        for _ in 0..8 {
+            crc = if crc & 0x80000000 != 0 { (crc << 1) ^ 0x04C11DB7 } else { crc << 1 };
+        }
+    }
+
+    crc
+}
@@ -0,0 +1,127 @@
+//! Text subtitle files: SRT, ASS/SSA and WebVTT.
+
+use std::io::{Seek, Write};
+
+use error::Result;
+
+use super::{Format, Packet};
+
+/// Writes `S_TEXT/UTF8` subtitles in a SubRip file.
+pub struct Srt {
+    index: u64,
+}
+
+impl Srt {
+    pub fn new() -> Srt {
+        Srt {
+            index: 0,
+        }
+    }
+}
+
+impl<W: Write + Seek> Format<W> for Srt {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], packet: &Packet) -> Result<()> {
+        self.index += 1;
+
+        let end = packet.timestamp + packet.duration.unwrap_or(0);
+        let text = String::from_utf8_lossy(frame);
+
+        write!(w, "{}\n{} --> {}\n{}\n\n", self.index, srt_time(packet.timestamp), srt_time(end), text.trim_end())?;
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes `S_TEXT/ASS` and `S_TEXT/SSA` subtitles in a script file, after the script header
+/// stored in the codec private data.
+pub struct Ass;
+
+impl Ass {
+    /// Write the script header and return the format.
+    pub fn new<W: Write>(w: &mut W, private: Option<&[u8]>) -> Result<Ass> {
+        if let Some(header) = private {
+            let header = String::from_utf8_lossy(header);
+            write!(w, "{}\n", header.trim_end())?;
+        }
+
+        Ok(Ass)
+    }
+}
+
+impl<W: Write + Seek> Format<W> for Ass {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], packet: &Packet) -> Result<()> {
+        // Blocks store `ReadOrder, Layer, Style, Name, MarginL, MarginR, MarginV, Effect, Text`.
+        // Dialogue lines store the layer, then the start and end times, then the rest.
+        let line = String::from_utf8_lossy(frame);
+        let fields: Vec<&str> = line.splitn(3, ',').collect();
+
+        if fields.len() < 3 {
+            return Ok(());
+        }
+
+        let end = packet.timestamp + packet.duration.unwrap_or(0);
+
+        write!(w, "Dialogue: {},{},{},{}\n", fields[1], ass_time(packet.timestamp), ass_time(end), fields[2].trim_end())?;
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes `S_TEXT/WEBVTT` subtitles in a WebVTT file.
+pub struct WebVtt;
+
+impl WebVtt {
+    /// Write the file header, stored in the codec private data, and return the format.
+    pub fn new<W: Write>(w: &mut W, private: Option<&[u8]>) -> Result<WebVtt> {
+        match private {
+            Some(header) => write!(w, "{}\n\n", String::from_utf8_lossy(header).trim_end())?,
+            None => write!(w, "WEBVTT\n\n")?,
+        };
+
+        Ok(WebVtt)
+    }
+}
+
+impl<W: Write + Seek> Format<W> for WebVtt {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], packet: &Packet) -> Result<()> {
+        let end = packet.timestamp + packet.duration.unwrap_or(0);
+        let text = String::from_utf8_lossy(frame);
+
+        write!(w, "{} --> {}\n{}\n\n", vtt_time(packet.timestamp), vtt_time(end), text.trim_end())?;
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Split a timestamp in nanoseconds into hours, minutes, seconds and milliseconds.
+fn split_time(ns: u64) -> (u64, u64, u64, u64) {
+    let ms = ns / 1000000;
+    (ms / 3600000, (ms / 60000) % 60, (ms / 1000) % 60, ms % 1000)
+}
+
+fn srt_time(ns: u64) -> String {
+    let (h, m, s, ms) = split_time(ns);
+    format!("{:02}:{:02}:{:02},{:03}", h, m, s, ms)
+}
+
+fn vtt_time(ns: u64) -> String {
+    let (h, m, s, ms) = split_time(ns);
+    format!("{:02}:{:02}:{:02}.{:03}", h, m, s, ms)
+}
+
+fn ass_time(ns: u64) -> String {
+    let (h, m, s, ms) = split_time(ns);
+    format!("{}:{:02}:{:02}.{:02}", h, m, s, ms / 10)
+}
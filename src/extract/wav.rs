@@ -0,0 +1,91 @@
+//! WAV container, for PCM audio.
+
+use std::io::{Seek, SeekFrom, Write};
+
+use elements as el;
+use error::{self, Result};
+use reader::track;
+
+use super::{write_le, Format, Packet};
+
+/// Writes PCM samples in a WAV file.
+pub struct Wav {
+    // Size in bytes of a sample, if samples must be converted from big-endian.
+    swap: Option<usize>,
+    data_len: u64,
+}
+
+impl Wav {
+    /// Write the WAV header of `track` and return the format.
+    pub fn new<W: Write>(w: &mut W, track: &track::Info) -> Result<Wav> {
+        let (rate, channels, depth) = match *track.kind() {
+            track::Kind::Audio(ref audio) => {
+                let depth = audio.bit_depth().ok_or(error::not_found(el::BIT_DEPTH))?;
+                (audio.sampling_frequency() as u64, audio.channels(), depth)
+            },
+
+            _ => bail!(error::not_found(el::AUDIO)),
+        };
+
+        let (format, swap) = match track.codec() {
+            "A_PCM/INT/LIT" => (1, None),
+            "A_PCM/INT/BIG" => (1, Some(depth as usize / 8)),
+            "A_PCM/FLOAT/IEEE" => (3, None),
+            codec => bail!(error::invalid_value(el::CODEC_ID, codec)),
+        };
+
+        let block_align = channels * ((depth + 7) / 8);
+
+        // The RIFF and data sizes are updated once all the samples are written.
+        w.write_all(b"RIFF")?;
+        write_le(w, 0, 4)?;
+        w.write_all(b"WAVEfmt ")?;
+        write_le(w, 16, 4)?;
+        write_le(w, format, 2)?;
+        write_le(w, channels, 2)?;
+        write_le(w, rate, 4)?;
+        write_le(w, rate * block_align, 4)?;
+        write_le(w, block_align, 2)?;
+        write_le(w, depth, 2)?;
+        w.write_all(b"data")?;
+        write_le(w, 0, 4)?;
+
+        Ok(Wav {
+            swap: swap,
+            data_len: 0,
+        })
+    }
+}
+
+impl<W: Write + Seek> Format<W> for Wav {
+    fn write_frame(&mut self, w: &mut W, frame: &[u8], _: &Packet) -> Result<()> {
+        match self.swap {
+            Some(size) if size > 1 => {
+                let mut data = frame.to_vec();
+                for sample in data.chunks_mut(size) {
+                    sample.reverse();
+                }
+
+                w.write_all(&data)?;
+            },
+
+            _ => w.write_all(frame)?,
+        };
+
+        self.data_len += frame.len() as u64;
+        Ok(())
+    }
+
+    fn finish(&mut self, w: &mut W) -> Result<()> {
+        let end = w.seek(SeekFrom::Current(0))?;
+
+        w.seek(SeekFrom::Start(4))?;
+        write_le(w, 36 + self.data_len, 4)?;
+        w.seek(SeekFrom::Start(40))?;
+        write_le(w, self.data_len, 4)?;
+        w.seek(SeekFrom::Start(end))?;
+
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+//! Per-track sample tables, giving direct access to any frame of a file.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use elements as el;
+use error::{self, Result};
+use reader::Reader;
+
+const MAGIC: &'static [u8] = b"MKVIDX";
+const VERSION: u8 = 1;
+
+/// Serialized size of a sample in bytes.
+const SAMPLE_LEN: usize = 33;
+
+/// Maximum size in bytes of the serialized samples of a track for which memory is reserved before
+/// reading them.
+const MAX_RESERVED_LEN: usize = 1 << 20;
+
+/// Location and timing of a frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sample {
+    /// Presentation timestamp in nanoseconds.
+    pub pts: i64,
+
+    /// Optional. Duration in nanoseconds.
+    pub duration: Option<u64>,
+
+    /// Position of the frame data in the file, and its size in bytes.
+    pub offset: u64,
+    pub size: u32,
+
+    pub keyframe: bool,
+
+    /// Index of the cluster containing the frame, from the start of the segment.
+    pub cluster: u32,
+}
+
+/// Sample tables of all the tracks of a file. Samples are stored in the order of the file,
+/// which is the decoding order.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Index {
+    tracks: Vec<(u64, Vec<Sample>)>,
+}
+
+impl Index {
+    /// Scan all the clusters of `input` and build the sample table of each track.
+    pub fn build<R: Read>(input: &mut Reader<R>) -> Result<Index> {
+        let (segment, tracks) = input.header_info()?;
+        let scale = segment.timecode_scale();
+        let base = input.segment_offset() as u64;
+
+        let mut index = Index {
+            tracks: tracks.iter().map(|track| (track.index(), Vec::new())).collect(),
+        };
+
+        let mut cluster_index = 0;
+
+        while let Some(mut cluster) = input.next_cluster()? {
+            for block in cluster.blocks() {
+                let block = block?;
+
+                let default_duration = match tracks.iter().find(|track| track.index() == block.track()) {
+                    Some(track) => track.default_duration(),
+                    None => continue,
+                };
+
+                let keyframe = block.keyframe();
+                let track = block.track();
+
+                // Frames are stored contiguously at the end of the block, after the lacing header.
+                let end = base + (block.position() + block.size()) as u64;
+                let frames = block.timed_frames(scale, default_duration)?;
+                let mut offset = end - frames.iter().map(|&(_, _, ref frame)| frame.len() as u64).sum::<u64>();
+
+                let samples = index.samples_mut(track);
+
+                for (pts, duration, frame) in frames {
+                    samples.push(Sample {
+                        pts: pts,
+                        duration: duration,
+                        offset: offset,
+                        size: frame.len() as u32,
+                        keyframe: keyframe,
+                        cluster: cluster_index,
+                    });
+
+                    offset += frame.len() as u64;
+                }
+            }
+
+            cluster_index += 1;
+        }
+
+        Ok(index)
+    }
+
+    /// Return the numbers of the indexed tracks.
+    pub fn tracks(&self) -> Vec<u64> {
+        self.tracks.iter().map(|&(number, _)| number).collect()
+    }
+
+    /// Return the samples of the track number `track`.
+    pub fn samples(&self, track: u64) -> Option<&[Sample]> {
+        self.tracks.iter()
+            .find(|&&(number, _)| number == track)
+            .map(|&(_, ref samples)| samples.as_slice())
+    }
+
+    /// Return the `n`-th sample of the track number `track`, in decoding order.
+    pub fn sample(&self, track: u64, n: usize) -> Option<&Sample> {
+        self.samples(track).and_then(|samples| samples.get(n))
+    }
+
+    /// Read the data of the `n`-th frame of the track number `track` from `r`, which must be the
+    /// indexed file.
+    pub fn read_frame<R: Read + Seek>(&self, r: &mut R, track: u64, n: usize) -> Result<Vec<u8>> {
+        let sample = self.sample(track, n).ok_or(error::invalid_value(el::TRACK_NUMBER, track))?;
+
+        let mut data = vec![0; sample.size as usize];
+        r.seek(SeekFrom::Start(sample.offset))?;
+        r.read_exact(&mut data)?;
+
+        Ok(data)
+    }
+
+    /// Serialize the index into `w`.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        write_be(w, self.tracks.len() as u64, 4)?;
+
+        for &(number, ref samples) in &self.tracks {
+            write_be(w, number, 8)?;
+            write_be(w, samples.len() as u64, 4)?;
+
+            for sample in samples {
+                let flags = if sample.keyframe { 0x01 } else { 0x00 } | if sample.duration.is_some() { 0x02 } else { 0x00 };
+
+                write_be(w, sample.pts as u64, 8)?;
+                write_be(w, sample.duration.unwrap_or(0), 8)?;
+                write_be(w, sample.offset, 8)?;
+                write_be(w, sample.size as u64, 4)?;
+                write_be(w, sample.cluster as u64, 4)?;
+                w.write_all(&[flags])?;
+            }
+        }
+
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Deserialize an index written by `write`.
+    pub fn read<R: Read>(r: &mut R) -> Result<Index> {
+        let mut header = vec![0; MAGIC.len() + 1];
+        r.read_exact(&mut header)?;
+
+        if &header[..MAGIC.len()] != MAGIC {
+            bail!(error::invalid_index("bad magic"));
+        }
+
+        if header[MAGIC.len()] != VERSION {
+            bail!(error::invalid_index(format!("unsupported version {}", header[MAGIC.len()])));
+        }
+
+        let mut tracks = Vec::new();
+
+        for _ in 0..read_be(r, 4)? {
+            let number = read_be(r, 8)?;
+            let count = read_be(r, 4)? as usize;
+
+            // The count is read from the input: memory is only reserved for the samples that
+            // fit in a bounded amount of data, and the vector grows as the others are read.
+            let mut samples = Vec::with_capacity(count.min(MAX_RESERVED_LEN / SAMPLE_LEN));
+            let mut buf = vec![0; SAMPLE_LEN];
+
+            for _ in 0..count {
+                r.read_exact(&mut buf)?;
+
+                let field = |pos: usize, len: usize| buf[pos..pos + len].iter().fold(0, |v, b| (v << 8) | *b as u64);
+                let flags = buf[32];
+
+                samples.push(Sample {
+                    pts: field(0, 8) as i64,
+                    duration: if flags & 0x02 != 0 { Some(field(8, 8)) } else { None },
+                    offset: field(16, 8),
+                    size: field(24, 4) as u32,
+                    cluster: field(28, 4) as u32,
+                    keyframe: flags & 0x01 != 0,
+                });
+            }
+
+            tracks.push((number, samples));
+        }
+
+        Ok(Index {
+            tracks: tracks,
+        })
+    }
+
+    fn samples_mut(&mut self, track: u64) -> &mut Vec<Sample> {
+        let pos = self.tracks.iter().position(|&(number, _)| number == track).unwrap();
+        &mut self.tracks[pos].1
+    }
+}
+
+fn write_be<W: Write>(w: &mut W, value: u64, len: usize) -> Result<()> {
+    let bytes: Vec<u8> = (0..len).rev().map(|i| (value >> (8 * i)) as u8).collect();
+    w.write_all(&bytes)?;
+
+    Ok(())
+}
+
+fn read_be<R: Read>(r: &mut R, len: usize) -> Result<u64> {
+    let mut bytes = vec![0; len];
+    r.read_exact(&mut bytes)?;
+
+    Ok(bytes.iter().fold(0, |value, byte| (value << 8) | *byte as u64))
+}
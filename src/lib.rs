@@ -7,9 +7,33 @@ extern crate error_chain;
 
 extern crate ebml;
 
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
 pub mod error;
 pub mod elements;
+pub mod schema;
 pub mod reader;
+pub mod writer;
+pub mod remux;
+pub mod split;
+pub mod concat;
+pub mod editor;
+pub mod extract;
+pub mod codec;
+pub mod subtitles;
+pub mod index;
+pub mod repair;
+pub mod validate;
+pub mod link;
+pub mod playlist;
+pub mod stereo;
+pub mod trick;
+pub mod dash;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,320 @@
+//! Resolve chains of hard-linked segments, which split a single presentation across several files
+//! by referencing the UIDs of the previous and next segments.
+
+use std::cmp;
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use elements as el;
+use error::{self, Result};
+use reader::{Info, Reader};
+use reader::cluster::Block;
+use reader::segment;
+use reader::track;
+
+/// Extensions of the files considered when resolving a chain in a directory.
+const EXTENSIONS: &'static [&'static str] = &["mkv", "mka", "mks", "mk3d", "webm"];
+
+/// A segment of a chain, with the file containing it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Link {
+    pub path: PathBuf,
+    pub info: segment::Info,
+}
+
+impl Link {
+    /// Read the segment information of the file at `path`.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Link> {
+        let mut reader = Reader::new(BufReader::new(File::open(path.as_ref())?))?;
+
+        for info in reader.info()? {
+            if let Info::Segment(info) = info {
+                return Ok(Link {
+                    path: path.as_ref().to_path_buf(),
+                    info: info,
+                });
+            }
+        }
+
+        bail!(error::not_found(el::INFO));
+    }
+
+    /// Check whether `next` follows this segment: either UID references the other segment, and
+    /// segments that both declare families share one of them.
+    fn followed_by(&self, next: &Link) -> bool {
+        let linked = match (self.info.next_uid(), next.info.uid()) {
+            (Some(next_uid), Some(uid)) => next_uid == uid,
+            _ => false,
+        } || match (self.info.uid(), next.info.prev_uid()) {
+            (Some(uid), Some(prev_uid)) => uid == prev_uid,
+            _ => false,
+        };
+
+        let families = self.info.families();
+        let next_families = next.info.families();
+
+        linked && (families.is_empty() || next_families.is_empty()
+            || families.iter().any(|family| next_families.contains(family)))
+    }
+
+    /// Check whether the file name of this segment is `name`.
+    fn named(&self, name: Option<&str>) -> bool {
+        match (name, self.path.file_name()) {
+            (Some(name), Some(file_name)) => file_name == name,
+            _ => false,
+        }
+    }
+}
+
+/// Ordered segments of a hard-linked chain.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Chain {
+    links: Vec<Link>,
+}
+
+impl Chain {
+    /// Build the chain that the segment in the file `start` belongs to, from the segments in the
+    /// `candidates` files. Candidates that cannot be read are ignored.
+    pub fn resolve<P: AsRef<Path>>(start: P, candidates: &[P]) -> Result<Chain> {
+        let mut links = vec![Link::read(start.as_ref())?];
+
+        for path in candidates {
+            if path.as_ref() == start.as_ref() {
+                continue;
+            }
+
+            if let Ok(link) = Link::read(path) {
+                links.push(link);
+            }
+        }
+
+        Chain::from_links(links, 0)
+    }
+
+    /// Build the chain that the segment in the file `start` belongs to, from the matroska files
+    /// of the same directory.
+    pub fn resolve_in_dir<P: AsRef<Path>>(start: P) -> Result<Chain> {
+        let dir = match start.as_ref().parent() {
+            Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let mut candidates = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let extension = path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_lowercase());
+
+            if extension.map_or(false, |extension| EXTENSIONS.contains(&extension.as_str())) {
+                candidates.push(path);
+            }
+        }
+
+        candidates.sort();
+        Chain::resolve(start.as_ref().to_path_buf(), &candidates)
+    }
+
+    /// Build the chain that `links[start]` belongs to. Among several candidates for the same
+    /// place, the one whose file name matches `PrevFilename` or `NextFilename` is preferred.
+    pub fn from_links(mut links: Vec<Link>, start: usize) -> Result<Chain> {
+        let mut order = vec![start];
+
+        // Walk back to the first segment of the chain, then forward to the last one.
+        loop {
+            let first = &links[order[0]];
+            let prev = (0..links.len())
+                .filter(|&i| links[i].followed_by(first))
+                .max_by_key(|&i| links[i].named(first.info.prev_filename()));
+
+            match prev {
+                Some(i) if order.contains(&i) => bail!(error::invalid_value(el::PREV_UID, "segments linked in a loop")),
+                Some(i) => order.insert(0, i),
+                None => break,
+            };
+        }
+
+        loop {
+            let last = &links[order[order.len() - 1]];
+            let next = (0..links.len())
+                .filter(|&i| last.followed_by(&links[i]))
+                .max_by_key(|&i| links[i].named(last.info.next_filename()));
+
+            match next {
+                Some(i) if order.contains(&i) => bail!(error::invalid_value(el::NEXT_UID, "segments linked in a loop")),
+                Some(i) => order.push(i),
+                None => break,
+            };
+        }
+
+        let mut links: Vec<Option<Link>> = links.drain(..).map(Some).collect();
+
+        Ok(Chain {
+            links: order.iter().map(|&i| links[i].take().unwrap()).collect(),
+        })
+    }
+
+    /// Returns the segments of the chain, in playback order.
+    pub fn links(&self) -> &[Link] {
+        self.links.as_slice()
+    }
+
+    /// Check whether no segment of the chain is missing: the first one does not reference a
+    /// previous segment, and the last one does not reference a next segment.
+    pub fn is_complete(&self) -> bool {
+        self.links.first().map_or(false, |link| link.info.prev_uid().is_none())
+            && self.links.last().map_or(false, |link| link.info.next_uid().is_none())
+    }
+
+    /// Returns the frames of all the segments of the chain as a single stream. The timestamps of
+    /// each segment are offset by the duration of the previous ones.
+    pub fn packets(&self) -> Packets {
+        Packets {
+            paths: self.links.iter().map(|link| link.path.clone()).collect(),
+            next: 0,
+            current: None,
+            offset: 0,
+            end: 0,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+/// A frame of a chain of segments or of a playlist.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Packet {
+    /// Index of the segment in the chain, or of the range in a playlist.
+    pub segment: usize,
+
+    pub track: u64,
+
+    /// Presentation timestamp in nanoseconds, from the start of the chain.
+    pub timestamp: i64,
+
+    /// Optional. Duration in nanoseconds.
+    pub duration: Option<u64>,
+
+    pub keyframe: bool,
+    pub data: Vec<u8>,
+}
+
+/// Segment being read by `Packets`.
+struct Current {
+    reader: Reader<BufReader<File>>,
+    segment: usize,
+    info: segment::Info,
+    tracks: Vec<track::Info>,
+}
+
+/// Iterator over the frames of a chain of segments, which are read one cluster at a time.
+pub struct Packets {
+    paths: Vec<PathBuf>,
+    next: usize,
+    current: Option<Current>,
+
+    // Offset of the current segment and end of the last frame read so far, in nanoseconds.
+    offset: i64,
+    end: i64,
+
+    pending: VecDeque<Packet>,
+}
+
+impl Packets {
+    /// Read the next cluster of the chain, opening the next segment when needed. Returns `false`
+    /// once all the segments have been read.
+    fn fill(&mut self) -> Result<bool> {
+        loop {
+            if self.current.is_none() {
+                if self.next >= self.paths.len() {
+                    return Ok(false);
+                }
+
+                let mut reader = Reader::new(BufReader::new(File::open(&self.paths[self.next])?))?;
+                let (info, tracks) = reader.header_info()?;
+
+                self.current = Some(Current {
+                    reader: reader,
+                    segment: self.next,
+                    info: info,
+                    tracks: tracks,
+                });
+
+                self.next += 1;
+            }
+
+            let current = self.current.as_mut().unwrap();
+            let scale = current.info.timecode_scale();
+
+            match current.reader.next_cluster()? {
+                Some(mut cluster) => {
+                    for block in cluster.blocks() {
+                        for mut packet in block_packets(block?, current.segment, &current.tracks, scale)? {
+                            packet.timestamp += self.offset;
+
+                            self.end = cmp::max(self.end, packet.timestamp + packet.duration.unwrap_or(0) as i64);
+                            self.pending.push_back(packet);
+                        }
+                    }
+
+                    return Ok(true);
+                },
+
+                None => {
+                    // The next segment starts at the end of the current one: either its declared
+                    // duration or the end of its last frame, whichever is later.
+                    let duration = current.info.duration().map_or(0, |d| (d * scale as f64) as i64);
+
+                    self.offset = cmp::max(self.offset + duration, self.end);
+                    self.current = None;
+                },
+            };
+        }
+    }
+}
+
+/// Split `block`, read from the segment number `segment` with timestamp scale `scale`, into
+/// packets whose timestamps are relative to the segment.
+pub(crate) fn block_packets(block: Block, segment: usize, tracks: &[track::Info], scale: u64) -> Result<Vec<Packet>> {
+    let default_duration = tracks.iter()
+        .find(|track| track.index() == block.track())
+        .and_then(|track| track.default_duration());
+
+    let track = block.track();
+    let keyframe = block.keyframe();
+
+    Ok(block.timed_frames(scale, default_duration)?.into_iter().map(|(timestamp, duration, frame)| Packet {
+        segment: segment,
+        track: track,
+        timestamp: timestamp,
+        duration: duration,
+        keyframe: keyframe,
+        data: frame,
+    }).collect())
+}
+
+impl Iterator for Packets {
+    type Item = Result<Packet>;
+
+    fn next(&mut self) -> Option<Result<Packet>> {
+        while self.pending.is_empty() {
+            match self.fill() {
+                Ok(true) => {},
+                Ok(false) => return None,
+
+                // Stop the iteration after an error.
+                Err(err) => {
+                    self.current = None;
+                    self.next = self.paths.len();
+
+                    return Some(Err(err));
+                },
+            };
+        }
+
+        self.pending.pop_front().map(Ok)
+    }
+}
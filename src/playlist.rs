@@ -0,0 +1,236 @@
+//! Playback of ordered editions, whose chapters define a virtual timeline made of ranges of the
+//! segment, or of other segments referenced by their UID.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use elements as el;
+use error::{self, Result};
+use link::{self, Link, Packet};
+use reader::Reader;
+use reader::chapter::Edition;
+use reader::segment;
+use reader::track;
+
+/// A range of a segment played by an ordered edition.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Range {
+    /// UID of the chapter defining the range.
+    pub chapter: u64,
+
+    /// Optional. UID of the segment to play, or `None` for the segment of the edition.
+    pub segment_uid: Option<Vec<u8>>,
+
+    /// Start and end of the range in the segment, in nanoseconds. The end is excluded.
+    pub start: u64,
+    pub end: u64,
+
+    /// Start of the range in the virtual timeline, in nanoseconds.
+    pub timestamp: u64,
+}
+
+/// Ranges of segments played in order by an edition.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Playlist {
+    ranges: Vec<Range>,
+}
+
+impl Playlist {
+    /// Build the playlist of `edition`, an ordered edition of the segment described by `info`.
+    /// The enabled top-level chapters are played in order, hidden ones included. A chapter that
+    /// references an edition of another segment plays the range of that segment directly.
+    pub fn new(edition: &Edition, info: &segment::Info) -> Result<Playlist> {
+        if !edition.ordered() {
+            bail!(error::invalid_value(el::EDITION_FLAG_ORDERED, 0));
+        }
+
+        let mut ranges = Vec::new();
+        let mut timestamp = 0;
+
+        for (i, atom) in edition.atoms().iter().enumerate() {
+            if !atom.enabled() {
+                continue;
+            }
+
+            let end = atom.time_end()
+                .ok_or_else(|| error::within(error::not_found(el::CHAPTER_TIME_END), el::CHAPTER_ATOM, Some(i + 1)))?;
+
+            if end <= atom.time_start() {
+                continue;
+            }
+
+            // A chapter referencing the segment of the edition is a local range.
+            let segment_uid = atom.segment_uid()
+                .filter(|&uid| Some(uid) != info.uid())
+                .map(|uid| uid.to_vec());
+
+            ranges.push(Range {
+                chapter: atom.uid(),
+                segment_uid: segment_uid,
+                start: atom.time_start(),
+                end: end,
+                timestamp: timestamp,
+            });
+
+            timestamp += end - atom.time_start();
+        }
+
+        Ok(Playlist {
+            ranges: ranges,
+        })
+    }
+
+    /// Returns the ranges of the playlist, in playback order.
+    pub fn ranges(&self) -> &[Range] {
+        self.ranges.as_slice()
+    }
+
+    /// Returns the duration of the virtual timeline in nanoseconds.
+    pub fn duration(&self) -> u64 {
+        self.ranges.last().map_or(0, |range| range.timestamp + range.end - range.start)
+    }
+
+    /// Returns the frames of the playlist, with timestamps in the virtual timeline. `path` is the
+    /// file containing the segment of the edition, and `links` the other segments that may be
+    /// referenced, which can be read with `Link::read`.
+    pub fn packets<P: AsRef<Path>>(&self, path: P, links: &[Link]) -> Result<Packets> {
+        let mut sources = Vec::with_capacity(self.ranges.len());
+
+        for range in &self.ranges {
+            let path = match range.segment_uid {
+                Some(ref uid) => {
+                    match links.iter().find(|link| link.info.uid() == Some(uid.as_slice())) {
+                        Some(link) => link.path.clone(),
+                        None => bail!(error::invalid_value(el::CHAPTER_SEGMENT_UID, "no file contains the segment")),
+                    }
+                },
+
+                None => path.as_ref().to_path_buf(),
+            };
+
+            sources.push((path, range.clone()));
+        }
+
+        Ok(Packets {
+            sources: sources,
+            next: 0,
+            current: None,
+            pending: VecDeque::new(),
+        })
+    }
+}
+
+/// Range being read by `Packets`.
+struct Current {
+    reader: Reader<BufReader<File>>,
+    index: usize,
+    scale: u64,
+    tracks: Vec<track::Info>,
+
+    // Tracks whose first keyframe in the range has been read.
+    started: Vec<u64>,
+}
+
+/// Iterator over the frames of a playlist. Each range is read from the start of its file, and
+/// frames of a track are only played from its first keyframe in the range.
+pub struct Packets {
+    sources: Vec<(PathBuf, Range)>,
+    next: usize,
+    current: Option<Current>,
+    pending: VecDeque<Packet>,
+}
+
+impl Packets {
+    /// Read the next cluster of the current range, opening the file of the next range when
+    /// needed. Returns `false` once all the ranges have been read.
+    fn fill(&mut self) -> Result<bool> {
+        loop {
+            if self.current.is_none() {
+                if self.next >= self.sources.len() {
+                    return Ok(false);
+                }
+
+                let mut reader = Reader::new(BufReader::new(File::open(&self.sources[self.next].0)?))?;
+                let (info, tracks) = reader.header_info()?;
+
+                self.current = Some(Current {
+                    reader: reader,
+                    index: self.next,
+                    scale: info.timecode_scale(),
+                    tracks: tracks,
+                    started: Vec::new(),
+                });
+
+                self.next += 1;
+            }
+
+            let current = self.current.as_mut().unwrap();
+            let range = &self.sources[current.index].1;
+
+            let done = match current.reader.next_cluster()? {
+                Some(mut cluster) => {
+                    // Clusters starting after the end of the range are not needed.
+                    let mut first = None;
+
+                    for block in cluster.blocks() {
+                        for mut packet in link::block_packets(block?, current.index, &current.tracks, current.scale)? {
+                            first = first.or(Some(packet.timestamp));
+
+                            if packet.timestamp < range.start as i64 || packet.timestamp >= range.end as i64 {
+                                continue;
+                            }
+
+                            if !current.started.contains(&packet.track) {
+                                if !packet.keyframe {
+                                    continue;
+                                }
+
+                                current.started.push(packet.track);
+                            }
+
+                            packet.timestamp += range.timestamp as i64 - range.start as i64;
+                            self.pending.push_back(packet);
+                        }
+                    }
+
+                    first.map_or(false, |timestamp| timestamp >= range.end as i64)
+                },
+
+                None => true,
+            };
+
+            if !done {
+                return Ok(true);
+            }
+
+            self.current = None;
+        }
+    }
+}
+
+impl Iterator for Packets {
+    type Item = Result<Packet>;
+
+    fn next(&mut self) -> Option<Result<Packet>> {
+        while self.pending.is_empty() {
+            match self.fill() {
+                Ok(true) => {},
+                Ok(false) => return None,
+
+                // Stop the iteration after an error.
+                Err(err) => {
+                    self.current = None;
+                    self.next = self.sources.len();
+
+                    return Some(Err(err));
+                },
+            };
+        }
+
+        self.pending.pop_front().map(Ok)
+    }
+}
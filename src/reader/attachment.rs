@@ -0,0 +1,60 @@
+//! Read matroska attachments.
+
+use ebml::types::*;
+use ebml::{Element, ElementArray};
+
+use elements as el;
+use error::{self, Result};
+
+/// A file attached to the segment, such as a font or a cover picture.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct File {
+    pub uid: UnsignedInt,
+    pub name: Utf8,
+    pub mime_type: String,
+    pub description: Option<Utf8>,
+    pub data: Binary,
+}
+
+/// Read all the attached files of the matroska file. Expected input: children of the
+/// `Attachments` master element.
+pub fn read(elems: ElementArray) -> Result<Vec<File>> {
+    let mut files = Vec::new();
+
+    for entry in elems.vec() {
+        if entry.id() != el::ATTACHED_FILE {
+            continue;
+        }
+
+        let file = read_file(entry)
+            .map_err(|err| error::within(err, el::ATTACHED_FILE, Some(files.len() + 1)))?;
+
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+fn read_file(entry: Element) -> Result<File> {
+    let mut file = File {
+        uid: 0,
+        name: String::new(),
+        mime_type: String::new(),
+        description: None,
+        data: Vec::new(),
+    };
+
+    for elem in entry.content().children()?.vec() {
+        match elem.id() {
+            el::FILE_UID => file.uid = elem.content().into_uint(),
+            el::FILE_NAME => file.name = elem.content().into_utf8()?,
+            el::FILE_MIME_TYPE => file.mime_type = elem.content().into_utf8()?,
+            el::FILE_DESCRIPTION => file.description = Some(elem.content().into_utf8()?),
+            el::FILE_DATA => file.data = elem.content().into_binary(),
+            _ => {},
+        };
+    }
+
+    Ok(file)
+}
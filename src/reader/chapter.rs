@@ -0,0 +1,245 @@
+//! Read matroska chapters.
+
+use ebml::types::*;
+use ebml::{Element, ElementArray};
+
+use elements as el;
+use error::{self, Result};
+
+/// A chapter name in a given language.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Display {
+    string: Utf8,
+    languages: Vec<Utf8>,
+}
+
+impl Display {
+    /// Name of the chapter.
+    pub fn string(&self) -> &str {
+        self.string.as_str()
+    }
+
+    /// Languages of the name, in the ISO-639-2 form.
+    pub fn languages(&self) -> &[String] {
+        self.languages.as_slice()
+    }
+}
+
+/// Represents a chapter, which can contain nested chapters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Atom {
+    uid: UnsignedInt,
+    time_start: UnsignedInt,
+    time_end: Option<UnsignedInt>,
+    hidden: bool,
+    enabled: bool,
+    segment_uid: Option<Binary>,
+    segment_edition_uid: Option<UnsignedInt>,
+    displays: Vec<Display>,
+    children: Vec<Atom>,
+}
+
+impl Atom {
+    /// Unique identifier of the chapter.
+    pub fn uid(&self) -> u64 {
+        self.uid
+    }
+
+    /// Start time of the chapter in nanoseconds, unscaled.
+    pub fn time_start(&self) -> u64 {
+        self.time_start
+    }
+
+    /// Optional. End time of the chapter in nanoseconds, unscaled. The end time is excluded.
+    pub fn time_end(&self) -> Option<u64> {
+        self.time_end
+    }
+
+    /// Wether the chapter should be hidden from the user interface.
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Wether the chapter is enabled.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Optional. UID of the segment to play during this chapter, for ordered editions.
+    pub fn segment_uid(&self) -> Option<&[u8]> {
+        if let Some(ref uid) = self.segment_uid {
+            Some(uid.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Optional. UID of the edition to play from the segment referenced by `segment_uid`.
+    pub fn segment_edition_uid(&self) -> Option<u64> {
+        self.segment_edition_uid
+    }
+
+    /// Names of the chapter.
+    pub fn displays(&self) -> &[Display] {
+        self.displays.as_slice()
+    }
+
+    /// Nested chapters.
+    pub fn children(&self) -> &[Atom] {
+        self.children.as_slice()
+    }
+}
+
+/// Represents a set of chapters.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Edition {
+    uid: Option<UnsignedInt>,
+    hidden: bool,
+    default: bool,
+    ordered: bool,
+    atoms: Vec<Atom>,
+}
+
+impl Edition {
+    /// Optional. Unique identifier of the edition.
+    pub fn uid(&self) -> Option<u64> {
+        self.uid
+    }
+
+    /// Wether the edition should be hidden from the user interface.
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Wether the edition should be used by default.
+    pub fn default(&self) -> bool {
+        self.default
+    }
+
+    /// Wether the chapters of the edition define the playback order of the segment.
+    pub fn ordered(&self) -> bool {
+        self.ordered
+    }
+
+    /// Top-level chapters of the edition.
+    pub fn atoms(&self) -> &[Atom] {
+        self.atoms.as_slice()
+    }
+}
+
+/// Read all the editions of the matroska file. Expected input: children of the `Chapters` master
+/// element.
+pub fn read(elems: ElementArray) -> Result<Vec<Edition>> {
+    let mut editions = Vec::new();
+
+    for entry in elems.vec() {
+        if entry.id() != el::EDITION_ENTRY {
+            continue;
+        }
+
+        let edition = read_edition(entry)
+            .map_err(|err| error::within(err, el::EDITION_ENTRY, Some(editions.len() + 1)))?;
+
+        editions.push(edition);
+    }
+
+    Ok(editions)
+}
+
+fn read_edition(entry: Element) -> Result<Edition> {
+    let mut edition = Edition {
+        uid: None,
+        hidden: false,
+        default: false,
+        ordered: false,
+        atoms: Vec::new(),
+    };
+
+    for elem in entry.content().children()?.vec() {
+        match elem.id() {
+            el::EDITION_UID => edition.uid = Some(elem.content().into_uint()),
+            el::EDITION_FLAG_HIDDEN => edition.hidden = elem.content().into_uint() != 0,
+            el::EDITION_FLAG_DEFAULT => edition.default = elem.content().into_uint() != 0,
+            el::EDITION_FLAG_ORDERED => edition.ordered = elem.content().into_uint() != 0,
+
+            el::CHAPTER_ATOM => {
+                let atom = read_atom(elem)
+                    .map_err(|err| error::within(err, el::CHAPTER_ATOM, Some(edition.atoms.len() + 1)))?;
+
+                edition.atoms.push(atom);
+            },
+
+            _ => {},
+        };
+    }
+
+    Ok(edition)
+}
+
+fn read_atom(elem: Element) -> Result<Atom> {
+    let mut atom = Atom {
+        uid: 0,
+        time_start: 0,
+        time_end: None,
+        hidden: false,
+        enabled: true,
+        segment_uid: None,
+        segment_edition_uid: None,
+        displays: Vec::new(),
+        children: Vec::new(),
+    };
+
+    for elem in elem.content().children()?.vec() {
+        match elem.id() {
+            el::CHAPTER_UID => atom.uid = elem.content().into_uint(),
+            el::CHAPTER_TIME_START => atom.time_start = elem.content().into_uint(),
+            el::CHAPTER_TIME_END => atom.time_end = Some(elem.content().into_uint()),
+            el::CHAPTER_FLAG_HIDDEN => atom.hidden = elem.content().into_uint() != 0,
+            el::CHAPTER_FLAG_ENABLED => atom.enabled = elem.content().into_uint() != 0,
+            el::CHAPTER_SEGMENT_UID => atom.segment_uid = Some(elem.content().into_binary()),
+            el::CHAPTER_SEGMENT_EDITION_UID => atom.segment_edition_uid = Some(elem.content().into_uint()),
+
+            el::CHAPTER_ATOM => {
+                let child = read_atom(elem)
+                    .map_err(|err| error::within(err, el::CHAPTER_ATOM, Some(atom.children.len() + 1)))?;
+
+                atom.children.push(child);
+            },
+
+            el::CHAPTER_DISPLAY => {
+                let display = read_display(elem)
+                    .map_err(|err| error::within(err, el::CHAPTER_DISPLAY, Some(atom.displays.len() + 1)))?;
+
+                atom.displays.push(display);
+            },
+
+            _ => {},
+        };
+    }
+
+    Ok(atom)
+}
+
+fn read_display(elem: Element) -> Result<Display> {
+    let mut display = Display {
+        string: String::new(),
+        languages: Vec::new(),
+    };
+
+    for elem in elem.content().children()?.vec() {
+        match elem.id() {
+            el::CHAP_STRING => display.string = elem.content().into_utf8()?,
+            el::CHAP_LANGUAGE => display.languages.push(elem.content().into_utf8()?),
+            _ => {},
+        };
+    }
+
+    if display.languages.is_empty() {
+        display.languages.push(String::from("eng"));
+    }
+
+    Ok(display)
+}
@@ -0,0 +1,50 @@
+//! Verification of `CRC-32` elements.
+
+use ebml::types::*;
+
+/// How the reader handles `CRC-32` elements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Mode {
+    /// Do not verify checksums.
+    Ignore,
+
+    /// Record mismatches in the integrity report of the reader and go on reading.
+    Report,
+
+    /// Fail with a `ChecksumMismatch` error on the first mismatch.
+    Strict,
+}
+
+/// Checksum that does not match the content of its parent element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Mismatch {
+    /// ID of the parent element and position of its content relative to the segment data.
+    pub parent: ElementId,
+    pub position: usize,
+
+    /// Stored and computed checksums.
+    pub expected: u32,
+    pub actual: u32,
+}
+
+/// Compute the CRC-32 of `data` as defined by IEEE 802.3, which EBML uses.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+
+    for byte in data {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+/// Decode the content of a `CRC-32` element, stored in little-endian order.
+pub fn decode(content: &[u8]) -> u32 {
+    content.iter().take(4).enumerate().fold(0, |crc, (i, byte)| crc | (*byte as u32) << (8 * i))
+}
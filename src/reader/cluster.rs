@@ -4,10 +4,13 @@ use std::io::{Cursor, Read};
 
 use ::ebml as libebml;
 use self::libebml::types::*;
+use self::libebml::Element;
 
 use elements as el;
-use error::{self, Error, Result};
+use error::{self, Result};
 
+use super::checksum;
+use super::limits::ReaderLimits;
 use super::Reader;
 
 /// Represents a matroska cluster.
@@ -15,17 +18,102 @@ pub struct Cluster<'a, R: Read + 'a> {
     reader: &'a mut Reader<R>,
     pos: usize,
     size: usize,
+    timecode: UnsignedInt,
+
+    // When the cluster starts with a checksum, its remaining content is read in memory to be
+    // verified. Otherwise, the information of the first child element may have been read.
+    verify: bool,
+    buffer: Option<Cursor<Vec<u8>>>,
+    queued_element: Option<(ElementId, ElementSize, usize)>,
+
+    // Numbers of the tracks without any block in the cluster, from the legacy `SilentTracks`.
+    silent_tracks: Vec<UnsignedInt>,
+
+    // ID, once read, and position in the segment of the child element being read, or of the
+    // cluster itself before its children are read. Errors are located there.
+    child: (Option<ElementId>, usize),
 }
 
 impl<'a, R: Read + 'a> Cluster<'a, R> {
-    pub(crate) fn new(reader: &'a mut Reader<R>, size: usize) -> Cluster<'a, R> {
+    pub(crate) fn new(reader: &'a mut Reader<R>, size: usize, position: usize) -> Cluster<'a, R> {
+        let verify = reader.checksums != checksum::Mode::Ignore;
+
         Cluster {
             reader: reader,
             pos: 0,
             size: size,
+            timecode: 0,
+            verify: verify,
+            buffer: None,
+            queued_element: None,
+            silent_tracks: Vec::new(),
+            child: (None, position),
+        }
+    }
+
+    /// Verify the checksum of the cluster, if its first child is a `CRC-32` element.
+    fn verify_checksum(&mut self) -> Result<()> {
+        self.verify = false;
+
+        if self.size == 0 {
+            return Ok(());
+        }
+
+        let position = self.reader.segment_position;
+
+        let (id, size, c) = libebml::reader::read_element_info(&mut self.reader.r)?;
+        self.advance(c);
+
+        if id != el::CRC32 {
+            self.queued_element = Some((id, size, position));
+            return Ok(());
+        }
+
+        let limits = self.reader.limits;
+        limits.check("element size", size, limits.max_element_size)?;
+
+        let mut crc = vec![0; size];
+        self.reader.r.read_exact(&mut crc)?;
+        self.advance(size);
+
+        // The rest of the content is accounted for as it is parsed.
+        limits.check("element size", self.size - self.pos, limits.max_element_size)?;
+
+        let mut data = vec![0; self.size - self.pos];
+        self.reader.r.read_exact(&mut data)?;
+
+        self.reader.verify_checksum(el::CLUSTER, position, &crc, &data)?;
+        self.buffer = Some(Cursor::new(data));
+
+        Ok(())
+    }
+
+    /// Return the source of the child elements.
+    fn input(&mut self) -> &mut dyn Read {
+        match self.buffer {
+            Some(ref mut buffer) => buffer,
+            None => &mut self.reader.r,
         }
     }
 
+    fn advance(&mut self, len: usize) {
+        self.pos += len;
+        self.reader.segment_position += len;
+    }
+
+    /// Return the absolute timecode of the cluster. Only known once the `Timecode` element, which
+    /// comes first in the cluster, has been read by the block iterator.
+    pub fn timecode(&self) -> u64 {
+        self.timecode
+    }
+
+    /// Return the numbers of the tracks that have no block in the cluster, listed by the legacy
+    /// `SilentTracks` element. Only known once it has been read by the block iterator, which
+    /// also gives access to them.
+    pub fn silent_tracks(&self) -> &[u64] {
+        self.silent_tracks.as_slice()
+    }
+
     /// Return an iterator over all the data blocks in the cluster.
     pub fn blocks(&'a mut self) -> Blocks<'a, R> {
         Blocks {
@@ -39,41 +127,180 @@ pub struct Blocks<'a, R: Read + 'a> {
     cluster: &'a mut Cluster<'a, R>,
 }
 
-impl<'a, R: Read + 'a> ::std::iter::Iterator for Blocks<'a, R> {
-    type Item = Result<Block>;
+impl<'a, R: Read + 'a> Blocks<'a, R> {
+    /// Return the numbers of the tracks that have no block in the cluster, once the
+    /// `SilentTracks` element, which comes before the blocks, has been read.
+    pub fn silent_tracks(&self) -> &[u64] {
+        self.cluster.silent_tracks()
+    }
+
+    /// Read elements of the cluster until a block is found.
+    fn next_block(&mut self) -> Result<Option<Block>> {
+        if self.cluster.verify {
+            self.cluster.verify_checksum()?;
+        }
 
-    fn next(&mut self) -> Option<Self::Item> {
         while self.cluster.pos < self.cluster.size {
-            let (elem, c) = match libebml::reader::read_element(&mut self.cluster.reader.r) {
-                Ok((elem, c)) => (elem, c),
-                Err(err) => return Some(Err(Error::from(err))),
+            let (id, size, position) = match self.cluster.queued_element.take() {
+                Some(element) => element,
+
+                // A cluster of unknown size ends with its segment, at the EBML header or the
+                // segment following it.
+                None if self.cluster.buffer.is_none() && self.cluster.reader.at_segment_start()? => {
+                    self.cluster.reader.segment_ended = true;
+                    self.cluster.pos = self.cluster.size;
+
+                    return Ok(None);
+                },
+
+                None => {
+                    let position = self.cluster.reader.segment_position;
+                    self.cluster.child = (None, position);
+
+                    let (id, size, c) = libebml::reader::read_element_info(&mut self.cluster.input())?;
+                    self.cluster.advance(c);
+
+                    (id, size, position)
+                },
             };
 
-            self.cluster.pos += c;
-            self.cluster.reader.segment_position += c;
+            self.cluster.child = (Some(id), position);
 
-            match elem.id() {
-                el::TIMECODE | el::SILENT_TRACKS | el::POSITION | el::PREV_SIZE | el::ENCRYPTED_BLOCK => continue,
+            // In lenient mode, a cluster of unknown or wrong size ends at the next top-level
+            // element, which is left to the reader.
+            let top_level = id == el::CLUSTER || (super::TOP_LEVEL_ELEMENTS.contains(&id) && id != el::VOID && id != el::CRC32);
+
+            if top_level && self.cluster.reader.lenient && self.cluster.buffer.is_none() {
+                self.cluster.reader.queued_element = Some((id, size, position));
+                self.cluster.pos = self.cluster.size;
+
+                return Ok(None);
+            }
+
+            let cluster_timecode = self.cluster.timecode;
+            let limits = self.cluster.reader.limits;
+
+            // Block groups are read in memory and verified, then their children are read one by one
+            // to locate the block data.
+            if id == el::BLOCK_GROUP {
+                limits.check("block size", size, limits.max_block_size)?;
+
+                let mut data = vec![0; size];
+                self.cluster.input().read_exact(&mut data)?;
+                limits.check_master(&data, 2)?;
 
-                el::SIMPLE_BLOCK => return Some(Block::from_binary(elem.content().into_binary())),
+                let position = self.cluster.reader.segment_position;
+                self.cluster.reader.verify_master(el::BLOCK_GROUP, position, &data)?;
 
-                el::BLOCK_GROUP => {
-                    let mut elems = match elem.content().children() {
-                        Ok(elems) => elems,
-                        Err(err) => return Some(Err(Error::from(err))),
-                    };
+                let mut group = Cursor::new(data);
+                let mut children = Vec::new();
+                let mut block_end = 0;
+
+                while (group.position() as usize) < size {
+                    let (elem, _) = libebml::reader::read_element(&mut group)?;
+
+                    if elem.id() == el::BLOCK || elem.id() == el::BLOCK_VIRTUAL {
+                        block_end = position + group.position() as usize;
+                    }
+
+                    children.push(elem);
+                }
+
+                self.cluster.advance(size);
+
+                let mut block = Block::from_group(children)?;
+                block.cluster_timecode = cluster_timecode;
+                block.position = block_end - block.data.len();
+
+                check_lace_count(&limits, &block)?;
+                return Ok(Some(block));
+            }
+
+            match id {
+                el::SIMPLE_BLOCK | el::ENCRYPTED_BLOCK => limits.check("block size", size, limits.max_block_size)?,
+                _ => limits.check("element size", size, limits.max_element_size)?,
+            };
 
-                    return Some(match elems.find(el::BLOCK) {
-                        Some(elem) => Block::from_binary(elem.content().into_binary()),
-                        None => Err(error::not_found(el::BLOCK)),
-                    });
+            let (content, c) = libebml::reader::read_element_content(&mut self.cluster.input(), size)?;
+            self.cluster.advance(c);
+
+            match id {
+                el::TIMECODE => self.cluster.timecode = content.into_uint(),
+
+                el::SILENT_TRACKS => {
+                    self.cluster.silent_tracks = content.children()?.vec().into_iter()
+                        .filter(|elem| elem.id() == el::SILENT_TRACK_NUMBER)
+                        .map(|elem| elem.content().into_uint())
+                        .collect();
                 },
 
-                wtf => return Some(Err(error::unexpected(el::SIMPLE_BLOCK, wtf))),
+                el::POSITION | el::PREV_SIZE | el::ENCRYPTED_BLOCK | el::CRC32 | el::VOID => continue,
+
+                el::SIMPLE_BLOCK => {
+                    let mut block = Block::from_binary(id, content.into_binary())?;
+                    block.cluster_timecode = cluster_timecode;
+                    block.position = self.cluster.reader.segment_position - block.data.len();
+
+                    check_lace_count(&limits, &block)?;
+                    return Ok(Some(block));
+                },
+
+                wtf => bail!(error::unexpected(el::SIMPLE_BLOCK, wtf)),
             };
         }
 
-        None
+        Ok(None)
+    }
+}
+
+impl<'a, R: Read + 'a> ::std::iter::Iterator for Blocks<'a, R> {
+    type Item = Result<Block>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_block() {
+            Ok(Some(block)) => Some(Ok(block)),
+
+            // Only the timecode of a cluster read without error bounds the resync points, as the
+            // timecode of a damaged cluster cannot be trusted.
+            Ok(None) => {
+                self.cluster.reader.last_timecode = self.cluster.timecode;
+                None
+            },
+
+            // In lenient mode, the rest of the cluster is skipped and the reader scans for the
+            // next one.
+            Err(err) => {
+                let (child, position) = self.cluster.child;
+                let offset = self.cluster.reader.segment_offset + position;
+
+                let err = match child {
+                    Some(id) => super::locate(err, &[el::CLUSTER, id], offset),
+                    None => super::locate(err, &[el::CLUSTER], offset),
+                };
+
+                if !self.cluster.reader.lenient {
+                    return Some(Err(err));
+                }
+
+                let position = match self.cluster.buffer {
+                    Some(_) => self.cluster.reader.segment_offset + self.cluster.reader.segment_position,
+                    None => self.cluster.reader.r.position(),
+                };
+
+                self.cluster.reader.failure = Some((position, err.to_string()));
+                self.cluster.pos = self.cluster.size;
+
+                None
+            },
+        }
+    }
+}
+
+/// Fail if `block` laces more frames than the limit, before they are parsed.
+fn check_lace_count(limits: &ReaderLimits, block: &Block) -> Result<()> {
+    match (&block.lacing, block.data.first()) {
+        (&Lacing::None, _) | (_, None) => Ok(()),
+        (_, Some(&count)) => limits.check("lace count", count as usize + 1, limits.max_lace_count),
     }
 }
 
@@ -81,6 +308,8 @@ impl<'a, R: Read + 'a> ::std::iter::Iterator for Blocks<'a, R> {
 pub type Frame = Vec<u8>;
 
 /// Different lacing types available.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Lacing {
     None,
     Xiph,
@@ -88,20 +317,134 @@ pub enum Lacing {
     FixedSize,
 }
 
+/// Timing of a frame of a block, from the legacy `TimeSlice` element.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeSlice {
+    /// Index of the frame in the lace of the block, starting at 0.
+    pub lace_number: UnsignedInt,
+
+    /// Number of the frame in the track, to resolve the frames shared by several blocks.
+    pub frame_number: UnsignedInt,
+
+    /// ID of the `BlockAdditional` element holding the frame, or 0 for the block data.
+    pub block_addition_id: UnsignedInt,
+
+    /// Delay and duration of the frame, in units of the timestamp scale, relative to the block.
+    pub delay: UnsignedInt,
+    pub duration: UnsignedInt,
+}
+
+impl TimeSlice {
+    fn read(elem: Element) -> Result<TimeSlice> {
+        let mut slice = TimeSlice::default();
+
+        for elem in elem.content().children()?.vec() {
+            match elem.id() {
+                el::LACE_NUMBER => slice.lace_number = elem.content().into_uint(),
+                el::FRAME_NUMBER => slice.frame_number = elem.content().into_uint(),
+                el::BLOCK_ADDITIONID => slice.block_addition_id = elem.content().into_uint(),
+                el::DELAY => slice.delay = elem.content().into_uint(),
+                el::SLICE_DURATION => slice.duration = elem.content().into_uint(),
+                _ => {},
+            };
+        }
+
+        Ok(slice)
+    }
+}
+
 /// Represents a matroska data block.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Block {
     track_number: UnsignedInt,
+    cluster_timecode: UnsignedInt,
     timecode: i16,
+    position: usize,
     keyframe: bool,
     invisible: bool,
     discardable: bool,
     lacing: Lacing,
+    duration: Option<UnsignedInt>,
+    references: Vec<SignedInt>,
+    additions: Vec<(UnsignedInt, Binary)>,
     data: Binary,
+
+    // Legacy features of block groups, written by early versions of mkvmerge.
+    is_virtual: bool,
+    reference_virtual: Option<SignedInt>,
+    slices: Vec<TimeSlice>,
 }
 
 impl Block {
-    /// Parse a matroska block from its binary representation.
-    fn from_binary(data: Vec<u8>) -> Result<Block> {
+    /// Parse a matroska block from the children of a `BlockGroup` element.
+    fn from_group(elems: Vec<Element>) -> Result<Block> {
+        let mut block = None;
+        let mut duration = None;
+        let mut references = Vec::new();
+        let mut additions = Vec::new();
+        let mut reference_virtual = None;
+        let mut slices = Vec::new();
+
+        for elem in elems {
+            match elem.id() {
+                el::BLOCK => block = Some(Block::from_binary(el::BLOCK, elem.content().into_binary())?),
+                el::BLOCK_DURATION => duration = Some(elem.content().into_uint()),
+                el::REFERENCE_BLOCK => references.push(elem.content().into_int()),
+                el::REFERENCE_VIRTUAL => reference_virtual = Some(elem.content().into_int()),
+
+                // A virtual block only has the header of a block, without any data.
+                el::BLOCK_VIRTUAL => if block.is_none() {
+                    let mut virtual_block = Block::from_binary(el::BLOCK_VIRTUAL, elem.content().into_binary())?;
+                    virtual_block.is_virtual = true;
+
+                    block = Some(virtual_block);
+                },
+
+                el::SLICES => {
+                    for slice in elem.content().children()?.vec() {
+                        if slice.id() == el::TIME_SLICE {
+                            slices.push(TimeSlice::read(slice)?);
+                        }
+                    }
+                },
+
+                el::BLOCK_ADDITIONS => {
+                    for more in elem.content().children()?.vec() {
+                        let mut more = more.content().children()?;
+
+                        let id = more.find(el::BLOCK_ADD_ID)
+                            .map_or(1, |elem| elem.content().into_uint());
+
+                        let data = more.find(el::BLOCK_ADDITIONAL)
+                            .ok_or(error::not_found(el::BLOCK_ADDITIONAL))?
+                            .content().into_binary();
+
+                        additions.push((id, data));
+                    }
+                },
+
+                _ => {},
+            };
+        }
+
+        let mut block = block.ok_or(error::not_found(el::BLOCK))?;
+        block.duration = duration;
+        block.references = references;
+        block.additions = additions;
+        block.reference_virtual = reference_virtual;
+        block.slices = slices;
+
+        // The keyframe flag is only stored in simple blocks. Inside a block group, a block is a
+        // keyframe if it does not reference any other block.
+        block.keyframe = block.references.is_empty();
+
+        Ok(block)
+    }
+
+    /// Parse a matroska block from the binary representation of the element `id`.
+    pub(crate) fn from_binary(id: ElementId, data: Vec<u8>) -> Result<Block> {
         let mut data_len = data.len();
         let mut cursor = Cursor::new(data);
 
@@ -140,7 +483,7 @@ impl Block {
             0b11 => Lacing::Ebml,
             0b10 => Lacing::FixedSize,
 
-            wtf => bail!(error::invalid_value(0, wtf)),
+            wtf => bail!(error::invalid_value(id, wtf)),
         };
 
         let mut data = vec![0u8; data_len];
@@ -148,12 +491,20 @@ impl Block {
 
         Ok(Block {
             track_number: track_number as UnsignedInt,
+            cluster_timecode: 0,
             timecode: timecode,
+            position: 0,
             keyframe: keyframe,
             invisible: invisible,
             discardable: discardable,
             lacing: lacing,
+            duration: None,
+            references: Vec::new(),
+            additions: Vec::new(),
             data: data,
+            is_virtual: false,
+            reference_virtual: None,
+            slices: Vec::new(),
         })
     }
 
@@ -167,6 +518,51 @@ impl Block {
         self.timecode
     }
 
+    /// Return the timecode of the block in segment ticks, that is the timecode of its cluster
+    /// added to its relative timecode.
+    pub fn absolute_timecode(&self) -> i64 {
+        self.cluster_timecode as i64 + self.timecode as i64
+    }
+
+    /// Return the position of the block data, including any lacing header, relative to the start
+    /// of the segment data.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Return the duration of the block in segment ticks, if it was stored in a block group.
+    pub fn duration(&self) -> Option<u64> {
+        self.duration
+    }
+
+    /// Return the timecodes of the blocks referenced by this block, relative to its own timecode.
+    pub fn references(&self) -> &[i64] {
+        self.references.as_slice()
+    }
+
+    /// Return the additional data of the block, as `BlockAddID` and content pairs, if it was stored
+    /// in a block group.
+    pub fn additions(&self) -> &[(u64, Vec<u8>)] {
+        self.additions.as_slice()
+    }
+
+    /// Return wether the block is a legacy `BlockVirtual`, which has no data and only marks the
+    /// place of a block in display order.
+    pub fn is_virtual(&self) -> bool {
+        self.is_virtual
+    }
+
+    /// Return the legacy `ReferenceVirtual` of the block: the timecode of the data that would be
+    /// at the place of a virtual block, relative to the block.
+    pub fn reference_virtual(&self) -> Option<i64> {
+        self.reference_virtual
+    }
+
+    /// Return the legacy time slices of the block, which describe its frames individually.
+    pub fn slices(&self) -> &[TimeSlice] {
+        self.slices.as_slice()
+    }
+
     /// Return wether the block contains only keyframe(s).
     pub fn keyframe(&self) -> bool {
         self.keyframe
@@ -192,6 +588,11 @@ impl Block {
         self.data.len()
     }
 
+    /// Return the raw data contained in the block, including any lacing header.
+    pub fn raw(&self) -> &[u8] {
+        self.data.as_slice()
+    }
+
     /// Return the data contained in the block. Consumes `self`.
     pub fn data(self) -> Vec<u8> {
         self.data
@@ -199,6 +600,10 @@ impl Block {
 
     /// Return the frames contained in the block. Consumes `self`.
     pub fn frames(self) -> Result<Vec<Frame>> {
+        if self.is_virtual {
+            return Ok(Vec::new());
+        }
+
         match self.lacing {
             Lacing::None => {
                 let mut frames = Vec::with_capacity(1);
@@ -212,9 +617,26 @@ impl Block {
             Lacing::FixedSize => parse_fixed_size_frames(self.data),
         }
     }
+
+    /// Return the frames contained in the block with their timestamp and duration in
+    /// nanoseconds, for a timestamp scale of `scale` and the default duration of the track.
+    /// Laced frames are spread over the block duration using the default duration. Consumes
+    /// `self`.
+    pub(crate) fn timed_frames(self, scale: u64, default_duration: Option<u64>) -> Result<Vec<(i64, Option<u64>, Frame)>> {
+        let timestamp = self.absolute_timecode() * scale as i64;
+        let duration = self.duration.map(|d| d * scale);
+
+        let frames = self.frames()?;
+        let count = frames.len() as u64;
+
+        Ok(frames.into_iter().enumerate().map(|(i, frame)| {
+            let offset = default_duration.map_or(0, |d| d * i as u64) as i64;
+            (timestamp + offset, duration.map(|d| d / count).or(default_duration), frame)
+        }).collect())
+    }
 }
 
-fn parse_xiph_frames(block: Vec<u8>) -> Result<Vec<Frame>> {
+pub(crate) fn parse_xiph_frames(block: Vec<u8>) -> Result<Vec<Frame>> {
     let mut frames = Vec::new();
     let mut remaining = block.len();
     let mut cursor = Cursor::new(block);
@@ -258,9 +680,8 @@ fn parse_xiph_frames(block: Vec<u8>) -> Result<Vec<Frame>> {
 
     // Read the actual frames in the lace based on the sizes that we read.
     for size in sizes {
-        let mut frame = vec![0u8; size];
-        let c = try_read(&mut cursor, &mut frame)?;
-        remaining -= c;
+        let frame = read_frame(&mut cursor, size, remaining)?;
+        remaining -= size;
 
         frames.push(frame);
     }
@@ -308,15 +729,18 @@ fn parse_ebml_frames(data: Vec<u8>) -> Result<Vec<Frame>> {
         // https://lists.matroska.org/pipermail/matroska-users/2011-January/006669.html)
         let diff = (diff as i64) - ((0b1 << (7 * c - 1)) - 1 as i64);
 
-        let prev_size = sizes[i - 1] as i64;
-        sizes.push((prev_size + diff) as usize);
+        let size = sizes[i - 1] as i64 + diff;
+        if size < 0 {
+            bail!(error::invalid_value(el::BLOCK, "negative laced frame size"));
+        }
+
+        sizes.push(size as usize);
     }
 
     // Read the actual frames in the lace based on the sizes that we read.
     for size in sizes {
-        let mut frame = vec![0u8; size];
-        let c = try_read(&mut cursor, &mut frame)?;
-        remaining -= c;
+        let frame = read_frame(&mut cursor, size, remaining)?;
+        remaining -= size;
 
         frames.push(frame);
     }
@@ -338,19 +762,30 @@ fn parse_fixed_size_frames(data: Vec<u8>) -> Result<Vec<Frame>> {
     let mut number = vec![0u8; 1];
     try_read(&mut cursor, &mut number)?;
 
-    let number = number[0] as usize;
-    let frame_size = len / number; // Is it really just a division ?!
+    // The stored number is the number of frames in the lace minus one, and the frames share the
+    // rest of the block.
+    let number = number[0] as usize + 1;
+    let frame_size = (len - 1) / number;
 
     for _ in 0..number {
-        let mut frame = vec![0u8; frame_size];
-        try_read(&mut cursor, &mut frame)?;
-
-        frames.push(frame);
+        frames.push(read_frame(&mut cursor, frame_size, frame_size)?);
     }
 
     Ok(frames)
 }
 
+/// Read a frame of `size` bytes, which must not exceed the `remaining` bytes of the block.
+fn read_frame<R: Read>(r: &mut R, size: usize, remaining: usize) -> Result<Frame> {
+    if size > remaining {
+        bail!(error::unexpected_eof());
+    }
+
+    let mut frame = vec![0u8; size];
+    r.read_exact(&mut frame)?;
+
+    Ok(frame)
+}
+
 fn try_read<R: Read>(r: &mut R, buf: &mut [u8]) -> Result<usize> {
     let c = r.read(buf)?;
 
@@ -0,0 +1,100 @@
+//! Read matroska cues, the index of the keyframes of a segment.
+
+use ebml::types::*;
+use ebml::{Element, ElementArray};
+
+use elements as el;
+use error::{self, Result};
+
+/// Location of a keyframe of a track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrackPosition {
+    pub track: UnsignedInt,
+
+    /// Position of the cluster containing the keyframe, relative to the segment data.
+    pub cluster_position: UnsignedInt,
+
+    /// Optional. Position of the block in the cluster, relative to the cluster data.
+    pub relative_position: Option<UnsignedInt>,
+
+    /// Optional. Duration of the block, in units of the timestamp scale.
+    pub duration: Option<UnsignedInt>,
+
+    /// Optional. Number of the block in the cluster, starting at 1.
+    pub block_number: Option<UnsignedInt>,
+}
+
+/// Keyframes of one or more tracks at a given time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CuePoint {
+    /// Absolute timestamp of the keyframes, in units of the timestamp scale.
+    pub time: UnsignedInt,
+
+    pub positions: Vec<TrackPosition>,
+}
+
+/// Read all the cue points of the segment. Expected input: children of the `Cues` master
+/// element.
+pub fn read(elems: ElementArray) -> Result<Vec<CuePoint>> {
+    let mut cues = Vec::new();
+
+    for entry in elems.vec() {
+        if entry.id() != el::CUE_POINT {
+            continue;
+        }
+
+        let cue = read_point(entry)
+            .map_err(|err| error::within(err, el::CUE_POINT, Some(cues.len() + 1)))?;
+
+        cues.push(cue);
+    }
+
+    Ok(cues)
+}
+
+fn read_point(entry: Element) -> Result<CuePoint> {
+    let mut time = None;
+    let mut positions = Vec::new();
+
+    for elem in entry.content().children()?.vec() {
+        match elem.id() {
+            el::CUE_TIME => time = Some(elem.content().into_uint()),
+
+            el::CUE_TRACK_POSITIONS => {
+                let position = read_position(elem)
+                    .map_err(|err| error::within(err, el::CUE_TRACK_POSITIONS, Some(positions.len() + 1)))?;
+
+                positions.push(position);
+            },
+
+            _ => {},
+        };
+    }
+
+    Ok(CuePoint {
+        time: time.ok_or(error::not_found(el::CUE_TIME))?,
+        positions: positions,
+    })
+}
+
+fn read_position(elem: Element) -> Result<TrackPosition> {
+    let mut elems = elem.content().children()?;
+
+    let track = elems.find(el::CUE_TRACK)
+        .ok_or(error::not_found(el::CUE_TRACK))?
+        .content().into_uint();
+
+    let cluster_position = elems.find(el::CUE_CLUSTER_POSITION)
+        .ok_or(error::not_found(el::CUE_CLUSTER_POSITION))?
+        .content().into_uint();
+
+    Ok(TrackPosition {
+        track: track,
+        cluster_position: cluster_position,
+        relative_position: elems.find(el::CUE_RELATIVE_POSITION).map(|elem| elem.content().into_uint()),
+        duration: elems.find(el::CUE_DURATION).map(|elem| elem.content().into_uint()),
+        block_number: elems.find(el::CUE_BLOCK_NUMBER).map(|elem| elem.content().into_uint()),
+    })
+}
@@ -0,0 +1,86 @@
+//! Bounds on the resources used to read untrusted input.
+
+use error::{self, Result};
+use schema::{self, Type};
+
+use super::tree;
+
+/// Limits enforced by the reader before allocating memory for the content of an element. Reading
+/// fails with a `LimitExceeded` error when one of them is exceeded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReaderLimits {
+    /// Maximum size in bytes of an element read in memory, such as the EBML header, a top-level
+    /// element other than a cluster, or a cluster whose checksum is verified.
+    pub max_element_size: usize,
+
+    /// Maximum size in bytes of a block or of a block group.
+    pub max_block_size: usize,
+
+    /// Maximum number of frames laced in a block.
+    pub max_lace_count: usize,
+
+    /// Maximum number of tracks in the segment.
+    pub max_tracks: usize,
+
+    /// Maximum depth of elements in the segment. Top-level elements such as `Tracks` have a depth
+    /// of 1.
+    pub max_depth: usize,
+}
+
+impl Default for ReaderLimits {
+    fn default() -> ReaderLimits {
+        ReaderLimits {
+            max_element_size: 256 << 20,
+            max_block_size: 64 << 20,
+            max_lace_count: 256,
+            max_tracks: 256,
+            max_depth: 32,
+        }
+    }
+}
+
+impl ReaderLimits {
+    /// Fail if `value` exceeds `max`, the limit called `limit`.
+    pub(crate) fn check(&self, limit: &str, value: usize, max: usize) -> Result<()> {
+        if value > max {
+            bail!(error::limit_exceeded(limit, value as u64, max as u64));
+        }
+
+        Ok(())
+    }
+
+    /// Verify `data`, the content of a master element at `depth`, before it is parsed: the
+    /// children must fit in their parents, and must not be nested deeper than the limit.
+    pub(crate) fn check_master(&self, data: &[u8], depth: usize) -> Result<()> {
+        let mut r = data;
+
+        while !r.is_empty() {
+            let (id, size, _) = match tree::read_header(&mut r)? {
+                Some(header) => header,
+                None => break,
+            };
+
+            if depth + 1 > self.max_depth {
+                bail!(error::limit_exceeded("element depth", (depth + 1) as u64, self.max_depth as u64));
+            }
+
+            // A child of unknown size extends to the end of its parent.
+            let size = match size {
+                Some(size) if size > r.len() as u64 => bail!(error::invalid_value(id, "element larger than its parent")),
+                Some(size) => size as usize,
+                None => r.len(),
+            };
+
+            let (content, rest) = r.split_at(size);
+
+            if schema::find(id).map_or(false, |definition| definition.kind == Type::Master) {
+                self.check_master(content, depth + 1)?;
+            }
+
+            r = rest;
+        }
+
+        Ok(())
+    }
+}
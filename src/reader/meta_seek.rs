@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 
 use ebml::types::*;
-use ebml::ElementArray;
+use ebml::{Element, ElementArray};
 
 use elements as el;
 use error::{self, Result};
@@ -15,23 +15,44 @@ pub type Info = HashMap<ElementId, u64>;
 /// Read Meta Seek entries. Expected input: array of `Seek` elements (the children of the
 /// `SeekHead` master element).
 pub fn read(elems: ElementArray) -> Result<Info> {
-    let mut entries = HashMap::new();
+    Ok(read_entries(elems)?.into_iter().collect())
+}
+
+/// Read Meta Seek entries in the order of the `Seek` elements, keeping the entries that
+/// reference the same element ID.
+pub fn read_entries(elems: ElementArray) -> Result<Vec<(ElementId, u64)>> {
+    let mut entries = Vec::new();
+    let mut index = 0;
 
     for entry in elems.vec() {
-        let mut data = entry.content().children()?;
+        if entry.id() != el::SEEK {
+            continue;
+        }
 
-        let id = data.find(el::SEEK_ID)
-            .ok_or(error::not_found(el::SEEK_ID))?
-            .content()
-            .into_uint();
+        index += 1;
 
-        let pos = data.find(el::SEEK_POSITION)
-            .ok_or(error::not_found(el::SEEK_POSITION))?
-            .content()
-            .into_uint();
+        let (id, pos) = read_seek(entry)
+            .map_err(|err| error::within(err, el::SEEK, Some(index)))?;
 
-        entries.insert(id, pos);
+        entries.push((id, pos));
     }
 
     Ok(entries)
 }
+
+/// Read the ID and the position of the element referenced by a `Seek` element.
+fn read_seek(entry: Element) -> Result<(ElementId, u64)> {
+    let mut data = entry.content().children()?;
+
+    let id = data.find(el::SEEK_ID)
+        .ok_or(error::not_found(el::SEEK_ID))?
+        .content()
+        .into_uint();
+
+    let pos = data.find(el::SEEK_POSITION)
+        .ok_or(error::not_found(el::SEEK_POSITION))?
+        .content()
+        .into_uint();
+
+    Ok((id, pos))
+}
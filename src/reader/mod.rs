@@ -4,59 +4,131 @@ pub mod meta_seek;
 pub mod segment;
 pub mod track;
 pub mod cluster;
-
-use std::io::Read;
+pub mod chapter;
+pub mod cues;
+pub mod tag;
+pub mod attachment;
+pub mod checksum;
+pub mod resync;
+pub mod tree;
+pub mod limits;
+
+use std::io::{self, Read};
+use std::mem;
 
 use ::ebml as libebml;
 use self::libebml::types::*;
+use self::libebml::ElementArray;
 
 use elements as el;
 use error::{self, Result};
+use schema;
 
 use self::cluster::Cluster;
+use self::limits::ReaderLimits;
+use self::resync::Input;
 
 /// Represents the different kinds of informative data that can be in a matroska file.
 /// Contrary to `Block` data, `Info` does not contain any media data, only metadata.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Info {
     MetaSeek(meta_seek::Info),
     Segment(segment::Info),
     Tracks(Vec<track::Info>),
+    Chapters(Vec<chapter::Edition>),
+    Tags(Vec<tag::Tag>),
+    Attachments(Vec<attachment::File>),
+    Cues(Vec<cues::CuePoint>),
 }
 
+/// Elements that may follow clusters at the top level of a segment.
+const TOP_LEVEL_ELEMENTS: &'static [ElementId] = &[
+    el::SEEK_HEAD, el::INFO, el::TRACKS, el::CUES, el::ATTACHEMENTS, el::CHAPTERS, el::TAGS,
+    el::VOID, el::CRC32,
+];
+
 /// High-level object that provides access to the different sections of the matroska file.
 pub struct Reader<R: Read> {
-    r: R,
+    r: Input<R>,
 
     // EBML header of this matroska file.
     header: libebml::header::Header,
 
-    // Current position in the segment and its size in bytes.
+    // Position of the segment data in the input, current position in the segment and size of the
+    // segment in bytes.
+    segment_offset: usize,
     segment_position: usize,
     segment_size: usize,
 
+    // Index of the segment in the input, starting at 0, and whether it ended before its declared
+    // size, at the EBML header or the segment following it.
+    segment_index: usize,
+    segment_ended: bool,
+
     // This is set to the next element that should be read when a reading process is nominally
     // stopped at some point. For example, metadata reading stops when a cluster is encountered.
-    // This variable is then set to the cluster element information for eventual later processing.
-    queued_element: Option<(ElementId, ElementSize)>,
+    // This variable is then set to the cluster element information for eventual later processing,
+    // with the position of the element in the segment.
+    queued_element: Option<(ElementId, ElementSize, usize)>,
+
+    // Handling of CRC-32 elements, and mismatches found so far in report mode.
+    checksums: checksum::Mode,
+    mismatches: Vec<checksum::Mismatch>,
+
+    // In lenient mode, errors in clusters are recovered from by scanning for the next cluster,
+    // which must not have a timecode lower than the one of the last cluster read without error.
+    lenient: bool,
+    failure: Option<(usize, String)>,
+    resyncs: Vec<resync::Event>,
+    dropped: Vec<resync::DroppedElement>,
+    last_timecode: UnsignedInt,
+
+    // Codec private data of the tracks read so far that could not be parsed.
+    codec_errors: Vec<track::CodecError>,
+
+    // Whether the content of the metadata elements parsed by `info` is kept, and the elements
+    // kept so far with the position of their content in the segment.
+    keep_contents: bool,
+    contents: Vec<(ElementId, usize, Vec<u8>)>,
+
+    limits: ReaderLimits,
 }
 
 impl<R: Read> Reader<R> {
     /// Initialize a new matroska reader. This function also parses the EBML header of the matroska
     /// file.
-    pub fn new(mut r: R) -> Result<Reader<R>> {
-        let (header, _) = libebml::reader::read_header(&mut r)?;
+    pub fn new(r: R) -> Result<Reader<R>> {
+        Reader::with_limits(r, ReaderLimits::default())
+    }
 
-        let (id, size, _) = libebml::reader::read_element_info(&mut r)?;
-        if id != el::SEGMENT {
-            bail!(error::unexpected(el::SEGMENT, id));
-        }
+    /// Initialize a new matroska reader enforcing `limits`, which already apply to the EBML header.
+    pub fn with_limits(r: R, limits: ReaderLimits) -> Result<Reader<R>> {
+        let mut r = Input::new(r);
+
+        let header = read_ebml_header(&mut r, &limits)?;
+        let size = read_segment_header(&mut r)?;
 
         Ok(Reader {
+            segment_offset: r.position(),
             r: r,
             header: header,
             segment_position: 0,
             segment_size: size,
-            queued_element: None
+            segment_index: 0,
+            segment_ended: false,
+            queued_element: None,
+            checksums: checksum::Mode::Ignore,
+            mismatches: Vec::new(),
+            lenient: false,
+            failure: None,
+            resyncs: Vec::new(),
+            dropped: Vec::new(),
+            last_timecode: 0,
+            codec_errors: Vec::new(),
+            keep_contents: false,
+            contents: Vec::new(),
+            limits: limits,
         })
     }
 
@@ -65,75 +137,104 @@ impl<R: Read> Reader<R> {
     pub fn info(&mut self) -> Result<Vec<Info>> {
         let mut info = Vec::new();
 
-        while self.segment_position < self.segment_size {
-            let (id, size) = match self.queued_element.take() {
-                Some((id, size)) => {
+        while self.segment_position < self.segment_size && !self.segment_ended {
+            let (id, size, position) = match self.queued_element.take() {
+                Some((id, size, position)) => {
                     match id {
                         // If a cluster element is queued, then we ignore it as this method is
                         // only supposed to retreive metadata.
 
                         el::CLUSTER => {
-                            let (_, c) = libebml::reader::read_element_content(&mut self.r, size)?;
-                            self.segment_position += c;
-
+                            self.skip(id, size, position)?;
                             return self.info();
                         },
 
-                        _ => (id, size),
+                        _ => (id, size, position),
                     }
                 },
 
-                None => {
-                    let (id, size, c) = libebml::reader::read_element_info(&mut self.r)?;
-                    self.segment_position += c;
+                None if self.lenient && self.r.at_eof()? => break,
 
-                    (id, size)
+                None if self.at_segment_start()? => {
+                    self.segment_ended = true;
+                    break;
                 },
-            };
 
-            match id {
-                el::SEEK_HEAD => {
-                    let (content, c) = libebml::reader::read_element_content(&mut self.r, size)?;
-                    self.segment_position += c;
+                None => {
+                    let position = self.segment_position;
+                    let offset = self.segment_offset + position;
 
-                    info.push(Info::MetaSeek(meta_seek::read(content.children()?)?));
-                },
+                    let (id, size, c) = libebml::reader::read_element_info(&mut self.r)
+                        .map_err(|err| locate(err.into(), &[], offset))?;
 
-                el::INFO => {
-                    let (content, c) = libebml::reader::read_element_content(&mut self.r, size)?;
                     self.segment_position += c;
 
-                    info.push(Info::Segment(segment::read(content.children()?)?));
+                    (id, size, position)
                 },
+            };
+
+            // End of the content of the element in the segment, up to which it is skipped if it
+            // cannot be read in lenient mode.
+            let end = self.segment_position.saturating_add(size);
+
+            match id {
+                el::INFO => info.push(Info::Segment(self.parse(id, size, position, segment::read)?)),
 
                 el::TRACKS => {
-                    let (content, c) = libebml::reader::read_element_content(&mut self.r, size)?;
-                    self.segment_position += c;
+                    let tracks = self.parse(id, size, position, track::read)?;
 
-                    info.push(Info::Tracks(track::read(content.children()?)?));
-                },
+                    if tracks.len() > self.limits.max_tracks {
+                        let err = error::limit_exceeded("number of tracks", tracks.len() as u64, self.limits.max_tracks as u64);
+                        bail!(locate(err, &[id], self.segment_offset + position));
+                    }
 
-                // Segment Top-Level-Element: read its child elements.
-                el::SEGMENT => continue,
+                    for track in &tracks {
+                        if let Err(err) = track.codec_config() {
+                            self.codec_errors.push(track::CodecError {
+                                track: track.index(),
+                                position: position,
+                                error: err.to_string(),
+                            });
+                        }
+                    }
 
-                // TODO: Process Chapters, Cues, Attachements and Tags.
-                el::CHAPTERS | el::CUES | el::ATTACHEMENTS | el::TAGS => {
-                    let (_, c) = libebml::reader::read_element_content(&mut self.r, size)?;
-                    self.segment_position += c;
+                    info.push(Info::Tracks(tracks));
+                },
+
+                el::SEEK_HEAD | el::CHAPTERS | el::TAGS | el::ATTACHEMENTS | el::CUES => {
+                    let parsed = match id {
+                        el::SEEK_HEAD => self.parse(id, size, position, meta_seek::read).map(Info::MetaSeek),
+                        el::CHAPTERS => self.parse(id, size, position, chapter::read).map(Info::Chapters),
+                        el::TAGS => self.parse(id, size, position, tag::read).map(Info::Tags),
+                        el::ATTACHEMENTS => self.parse(id, size, position, attachment::read).map(Info::Attachments),
+                        _ => self.parse(id, size, position, cues::read).map(Info::Cues),
+                    };
+
+                    match parsed {
+                        Ok(parsed) => info.push(parsed),
+                        Err(err) if self.lenient => self.drop_element(id, position, end, err)?,
+                        Err(err) => return Err(err),
+                    };
                 },
 
                 // Found the first cluster: information reading is done.
                 el::CLUSTER => {
-                    self.queued_element = Some((id, size));
+                    self.queued_element = Some((id, size, position));
                     break;
                 },
 
-                el::VOID | el::CRC32 => {
-                    let (_, c) = libebml::reader::read_element_content(&mut self.r, size)?;
-                    self.segment_position += c;
-                },
+                // A checksum of the whole segment cannot be verified while streaming it.
+                el::VOID | el::CRC32 => self.skip(id, size, position)?,
+
+                wtf => {
+                    let err = locate(error::unexpected(libebml::header::EBML, wtf), &[wtf], self.segment_offset + position);
+
+                    if !self.lenient {
+                        bail!(err);
+                    }
 
-                wtf => bail!(error::unexpected(libebml::header::EBML, wtf)),
+                    self.drop_element(wtf, position, end, err)?;
+                },
             };
         }
 
@@ -142,45 +243,411 @@ impl<R: Read> Reader<R> {
 
     /// Read the next matroska cluster. Returns `None` if there is no more to read. Borrows `self`.
     pub fn next_cluster<'a>(&'a mut self) -> Result<Option<Cluster<'a, R>>> {
-        if self.segment_position >= self.segment_size {
+        // In lenient mode, an error in the previous cluster is recovered from by scanning for the
+        // next one.
+        if let Some((position, error)) = self.failure.take() {
+            self.queued_element = None;
+
+            if !self.resync(position, error)? {
+                return Ok(None);
+            }
+        }
+
+        if self.segment_position >= self.segment_size || self.segment_ended {
             return Ok(None);
         }
 
         // If a cluster size has already been read & stored, use it. Otherwise, find the next
         // cluster's size by reading the next EBML element.
 
-        let size = match self.queued_element {
-            Some(_) => {
-                let (id, size) = self.queued_element.take().unwrap();
-
+        let (size, position) = match self.queued_element.take() {
+            Some((id, size, position)) => {
                 match id {
-                    el::CLUSTER => size,
-                    _ => return self.next_cluster(),
+                    el::CLUSTER => (size, position),
+
+                    // Leave other elements to the `info` method.
+                    _ => {
+                        self.queued_element = Some((id, size, position));
+                        return Ok(None);
+                    },
                 }
             },
 
+            // A segment of unknown size, which recordings from crashed encoders often have, simply
+            // ends with the input.
+            None if self.lenient && self.r.at_eof()? => return Ok(None),
+
+            // The segment is followed by another one before its declared size.
+            None if self.at_segment_start()? => {
+                self.segment_ended = true;
+                return Ok(None);
+            },
+
             None => {
                 // Read the next EBML element. If it is a cluster, we can go on reading cluster data.
                 // If not, then we return `None` to stop the iteration process.
 
-                let (id, s, c) = libebml::reader::read_element_info(&mut self.r)?;
+                let position = self.segment_position;
+                let offset = self.segment_offset + position;
+
+                let (id, s, c) = match libebml::reader::read_element_info(&mut self.r) {
+                    Ok(info) => info,
+                    Err(err) => return self.fail(locate(err.into(), &[], offset)),
+                };
+
                 self.segment_position += c;
 
                 match id {
                     // Found another cluster. Get its size in bytes.
-                    el::CLUSTER => s,
+                    el::CLUSTER => (s, position),
 
                     // Found some other element, queue it for eventual later processing by a user
-                    // call to the `info` method.
+                    // call to the `info` method. In lenient mode, it must be a top-level element.
                     _ => {
-                        self.queued_element = Some((id, s));
+                        if self.lenient && !TOP_LEVEL_ELEMENTS.contains(&id) {
+                            return self.fail(locate(error::unexpected(el::CLUSTER, id), &[id], offset));
+                        }
+
+                        self.queued_element = Some((id, s, position));
                         return Ok(None);
                     },
                 }
             },
         };
 
-        Ok(Some(Cluster::new(self, size)))
+        Ok(Some(Cluster::new(self, size, position)))
+    }
+
+    /// Read the metadata located before the first cluster, and return the segment information and
+    /// the tracks, which must both be present.
+    pub fn header_info(&mut self) -> Result<(segment::Info, Vec<track::Info>)> {
+        let mut segment = None;
+        let mut tracks = None;
+
+        for info in self.info()? {
+            match info {
+                Info::Segment(info) => segment = Some(info),
+                Info::Tracks(info) => tracks = Some(info),
+                _ => {},
+            };
+        }
+
+        let segment = segment.ok_or(error::not_found(el::INFO))?;
+        let tracks = tracks.ok_or(error::not_found(el::TRACKS))?;
+
+        Ok((segment, tracks))
+    }
+
+    /// Returns the position of the segment data in the input, which positions relative to the
+    /// segment are based on.
+    pub fn segment_offset(&self) -> usize {
+        self.segment_offset
+    }
+
+    /// Returns the size of the segment data, as declared in the input.
+    pub fn segment_size(&self) -> usize {
+        self.segment_size
+    }
+
+    /// Returns the index of the current segment in the input, starting at 0.
+    pub fn segment_index(&self) -> usize {
+        self.segment_index
+    }
+
+    /// Advance to the next segment of the input, which may be preceded by its own EBML header.
+    /// What remains of the current segment is skipped, and the metadata and clusters of the next
+    /// one are then read with `info` and `next_cluster`. Returns `false` at the end of the input.
+    pub fn next_segment(&mut self) -> Result<bool> {
+        self.failure = None;
+
+        match self.queued_element.take() {
+            // An element found after an unbuffered cluster of unknown size, whose header was read
+            // already. The EBML header of the next segment cannot be parsed anymore: keep the
+            // current one.
+            Some((libebml::header::EBML, size, position)) => {
+                self.skip(libebml::header::EBML, size, position)?;
+                self.segment_ended = true;
+            },
+
+            Some((el::SEGMENT, size, _)) => {
+                self.start_segment(size);
+                return Ok(true);
+            },
+
+            _ => {},
+        };
+
+        if !self.segment_ended {
+            let remaining = self.segment_size.saturating_sub(self.segment_position) as u64;
+            let c = io::copy(&mut (&mut self.r).take(remaining), &mut io::sink())?;
+
+            if c < remaining {
+                return Ok(false);
+            }
+        }
+
+        if self.r.at_eof()? {
+            return Ok(false);
+        }
+
+        if self.peek_id()? == libebml::header::EBML {
+            self.header = read_ebml_header(&mut self.r, &self.limits)?;
+        }
+
+        let offset = self.r.position();
+        let size = read_segment_header(&mut self.r)
+            .map_err(|err| error::at(err, offset as u64))?;
+
+        self.start_segment(size);
+        Ok(true)
+    }
+
+    /// Returns the current position in the input.
+    pub fn position(&self) -> usize {
+        self.r.position()
+    }
+
+    /// Set how `CRC-32` elements are handled. Checksums are ignored by default. When they are
+    /// verified, each master element that starts with a `CRC-32` is read fully in memory to be
+    /// hashed, including clusters, so a cluster larger than the `max_element_size` limit fails with
+    /// a `LimitExceeded` error.
+    pub fn set_checksum_mode(&mut self, mode: checksum::Mode) {
+        self.checksums = mode;
+    }
+
+    /// Returns the checksum mismatches found so far, in report mode.
+    pub fn checksum_mismatches(&self) -> &[checksum::Mismatch] {
+        self.mismatches.as_slice()
+    }
+
+    /// Set whether errors in clusters are recovered from, by scanning the input for the next
+    /// cluster, instead of being returned. A truncated input then simply ends the iteration.
+    /// Top-level elements other than `Info` and `Tracks` that cannot be read are skipped as well.
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Set the limits enforced while reading the rest of the input.
+    pub fn set_limits(&mut self, limits: ReaderLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the limits enforced while reading.
+    pub fn limits(&self) -> &ReaderLimits {
+        &self.limits
+    }
+
+    /// Returns the recoveries from errors so far, in lenient mode.
+    pub fn resyncs(&self) -> &[resync::Event] {
+        self.resyncs.as_slice()
+    }
+
+    /// Returns the top-level elements skipped so far because they could not be read, in lenient
+    /// mode.
+    pub fn dropped_elements(&self) -> &[resync::DroppedElement] {
+        self.dropped.as_slice()
+    }
+
+    /// Returns the codec private data of the tracks read so far that could not be parsed.
+    pub fn codec_errors(&self) -> &[track::CodecError] {
+        self.codec_errors.as_slice()
+    }
+
+    /// Set whether the encoded content of the metadata elements parsed by `info` is kept, so that
+    /// elements can be copied as they are. It is not kept by default.
+    pub fn set_keep_contents(&mut self, keep: bool) {
+        self.keep_contents = keep;
+    }
+
+    /// Take the metadata elements parsed by `info` since the last call whose content is kept, as
+    /// their ID, the position of their content in the segment and their content, without their
+    /// ID and size.
+    pub fn take_contents(&mut self) -> Vec<(ElementId, usize, Vec<u8>)> {
+        mem::replace(&mut self.contents, Vec::new())
+    }
+
+    /// Reset the reading state for a segment of `size` bytes starting at the current position.
+    fn start_segment(&mut self, size: ElementSize) {
+        self.segment_offset = self.r.position();
+        self.segment_position = 0;
+        self.segment_size = size;
+        self.segment_index += 1;
+        self.segment_ended = false;
+        self.queued_element = None;
+        self.last_timecode = 0;
+    }
+
+    /// Check whether the next element in the input is an EBML header or a segment, which end the
+    /// current segment.
+    fn at_segment_start(&mut self) -> Result<bool> {
+        let id = self.peek_id()?;
+        Ok(id == libebml::header::EBML || id == el::SEGMENT)
+    }
+
+    /// Returns the next 4 bytes of the input as an ID, without consuming them. This is only
+    /// meaningful to look for IDs of that length, such as those of top-level elements.
+    fn peek_id(&mut self) -> Result<ElementId> {
+        let head = self.r.peek(4)?;
+        Ok(if head.len() == 4 { tree::uint(&head) } else { 0 })
+    }
+
+    /// Handle an error while reading clusters: return it, or in lenient mode, record it and go on
+    /// with the next cluster.
+    fn fail<'a>(&'a mut self, err: error::Error) -> Result<Option<Cluster<'a, R>>> {
+        if !self.lenient {
+            return Err(err);
+        }
+
+        self.failure = Some((self.r.position(), err.to_string()));
+        self.next_cluster()
+    }
+
+    /// Skip the rest of the top-level element `id` located at `position` in the segment, whose
+    /// content ends at `end`, after the error `err` in lenient mode, and record it. A truncated
+    /// input ends the skipping.
+    fn drop_element(&mut self, id: ElementId, position: usize, end: usize, err: error::Error) -> Result<()> {
+        let rest = end.saturating_sub(self.segment_position);
+        self.segment_position += io::copy(&mut (&mut self.r).take(rest as u64), &mut io::sink())? as usize;
+
+        self.dropped.push(resync::DroppedElement {
+            id: id,
+            position: position,
+            error: err.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Scan the input for the next cluster after an error at `position`. Returns `false` if the
+    /// end of the input was reached first.
+    fn resync(&mut self, position: usize, error: String) -> Result<bool> {
+        let max_size = (self.segment_offset + self.segment_size).saturating_sub(self.r.position());
+        let resumed = resync::find_cluster(&mut self.r, self.last_timecode, max_size)?;
+
+        self.segment_position = self.r.position() - self.segment_offset;
+        self.resyncs.push(resync::Event {
+            position: position,
+            error: error,
+            resumed: resumed,
+        });
+
+        Ok(resumed.is_some())
+    }
+
+    /// Read the master element `id` located at `position` in the segment, and parse its children
+    /// with `read`. Errors are located at the deepest element of their path found in the content.
+    fn parse<T, F>(&mut self, id: ElementId, size: ElementSize, position: usize, read: F) -> Result<T>
+        where F: FnOnce(ElementArray) -> Result<T>
+    {
+        let offset = self.segment_offset + position;
+        let content_position = self.segment_position;
+        let content_offset = self.segment_offset + content_position;
+
+        let data = self.read_master(size).map_err(|err| locate(err, &[id], offset))?;
+        self.limits.check_master(&data, 1).map_err(|err| locate(err, &[id], offset))?;
+        self.verify_master(id, content_position, &data).map_err(|err| locate(err, &[id], offset))?;
+
+        let children = libebml::reader::read_element_content(&mut &data[..], size)
+            .and_then(|(content, _)| content.children())
+            .map_err(|err| locate(err.into(), &[id], offset))?;
+
+        let parsed = read(children).map_err(|err| {
+            let err = match err.path().and_then(|path| tree::find(&data, path)) {
+                Some(child_offset) => error::at(err, (content_offset + child_offset) as u64),
+                None => err,
+            };
+
+            locate(err, &[id], offset)
+        })?;
+
+        if self.keep_contents {
+            self.contents.push((id, content_position, data));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Read the content of a master element.
+    fn read_master(&mut self, size: ElementSize) -> Result<Vec<u8>> {
+        self.limits.check("element size", size, self.limits.max_element_size)?;
+
+        let mut data = vec![0; size];
+        self.r.read_exact(&mut data)?;
+        self.segment_position += size;
+
+        Ok(data)
+    }
+
+    /// Verify the checksums of `data`, the content of the master element `id` located at
+    /// `position` in the segment, and of the master elements nested in it. Malformed content is
+    /// left to the parsing of the element, and must have been checked against the depth limit.
+    pub(crate) fn verify_master(&mut self, id: ElementId, position: usize, data: &[u8]) -> Result<()> {
+        if self.checksums == checksum::Mode::Ignore {
+            return Ok(());
+        }
+
+        let mut r = data;
+
+        while !r.is_empty() {
+            let offset = data.len() - r.len();
+
+            let (child, size, len) = match tree::read_header(&mut r) {
+                Ok(Some((child, Some(size), len))) if size <= r.len() as u64 => (child, size as usize, len),
+                _ => break,
+            };
+
+            let (content, rest) = r.split_at(size);
+
+            // Only a first child can be the checksum of its siblings.
+            if child == el::CRC32 && offset == 0 {
+                self.verify_checksum(id, position, content, rest)?;
+            } else if schema::find(child).map_or(false, |definition| definition.kind == schema::Type::Master) {
+                self.verify_master(child, position + offset + len, content)?;
+            }
+
+            r = rest;
+        }
+
+        Ok(())
+    }
+
+    /// Skip the content of the element `id` located at `position` in the segment, without reading
+    /// it in memory.
+    fn skip(&mut self, id: ElementId, size: ElementSize, position: usize) -> Result<()> {
+        let c = io::copy(&mut (&mut self.r).take(size as u64), &mut io::sink())
+            .map_err(|err| locate(err.into(), &[id], self.segment_offset + position))? as usize;
+
+        self.segment_position += c;
+
+        if c < size {
+            bail!(locate(error::unexpected_eof(), &[id], self.segment_offset + position));
+        }
+
+        Ok(())
+    }
+
+    /// Verify that the content of a `CRC-32` element matches `data`, the content of the following
+    /// siblings, and handle a mismatch according to the checksum mode.
+    pub(crate) fn verify_checksum(&mut self, parent: ElementId, position: usize, crc: &[u8], data: &[u8]) -> Result<()> {
+        let expected = checksum::decode(crc);
+        let actual = checksum::crc32(data);
+
+        if expected == actual {
+            return Ok(());
+        }
+
+        match self.checksums {
+            checksum::Mode::Strict => bail!(error::checksum_mismatch(parent, expected, actual)),
+            checksum::Mode::Report => self.mismatches.push(checksum::Mismatch {
+                parent: parent,
+                position: position,
+                expected: expected,
+                actual: actual,
+            }),
+            checksum::Mode::Ignore => {},
+        };
+
+        Ok(())
     }
 
     /// Returns a reference to the EBML header of this matroska file.
@@ -188,3 +655,43 @@ impl<R: Read> Reader<R> {
         &self.header
     }
 }
+
+/// Read the EBML header at the current position of `r`, after checking its size.
+fn read_ebml_header<R: Read>(r: &mut Input<R>, limits: &ReaderLimits) -> Result<libebml::header::Header> {
+    // Check the size of the EBML header before it is read in memory.
+    let head = r.peek(12)?;
+
+    if let Ok(Some((_, Some(size), _))) = tree::read_header(&mut &head[..]) {
+        limits.check("element size", size as usize, limits.max_element_size)?;
+    }
+
+    let (header, _) = libebml::reader::read_header(r)?;
+    Ok(header)
+}
+
+/// Read the ID and size of the segment at the current position of `r`, and return the size.
+fn read_segment_header<R: Read>(r: &mut Input<R>) -> Result<ElementSize> {
+    let (id, size, _) = libebml::reader::read_element_info(r)?;
+    if id != el::SEGMENT {
+        bail!(error::unexpected(el::SEGMENT, id));
+    }
+
+    Ok(size)
+}
+
+/// Locate `err` in the element at the absolute position `offset`, whose path from the segment is
+/// `path`, unless a deeper position is already known.
+fn locate(err: error::Error, path: &[ElementId], offset: usize) -> error::Error {
+    let err = path.iter().rev().fold(err, |err, id| error::within(err, *id, None));
+    error::at(error::within(err, el::SEGMENT, None), offset as u64)
+}
+
+/// Return the elements of `elems` whose IDs are not in `known`, as ID and encoded content pairs.
+/// This is used to keep the elements that are not parsed, so that they can be written back.
+/// Checksums and padding are dropped, as they are only valid in their original place.
+pub(crate) fn unparsed(elems: ElementArray, known: &[ElementId]) -> Vec<(ElementId, Binary)> {
+    elems.vec().into_iter()
+        .filter(|elem| !known.contains(&elem.id()) && elem.id() != el::CRC32 && elem.id() != el::VOID)
+        .map(|elem| (elem.id(), elem.content().into_binary()))
+        .collect()
+}
@@ -0,0 +1,192 @@
+//! Recovery from corrupted or truncated clusters, by scanning the input for the next cluster, and
+//! from damaged metadata elements, by skipping them.
+
+use std::io::{self, Read};
+
+use ebml::types::*;
+
+use elements as el;
+
+use super::tree::read_vint;
+
+/// Number of bytes following a cluster ID that are checked before resuming: the size of the
+/// cluster, then its `Timecode` element.
+const LOOKAHEAD: usize = 4 + 8 + 1 + 1 + 8;
+
+/// Recovery from an error while reading clusters, in lenient mode.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Event {
+    /// Position in the input where the error was detected.
+    pub position: usize,
+
+    /// Description of the error.
+    pub error: String,
+
+    /// Position in the input of the cluster where reading resumed, or `None` if the end of the
+    /// input was reached first.
+    pub resumed: Option<usize>,
+}
+
+/// Top-level element other than a cluster that was skipped because it could not be read, in
+/// lenient mode.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DroppedElement {
+    pub id: ElementId,
+
+    /// Position of the element in the segment.
+    pub position: usize,
+
+    /// Description of the error.
+    pub error: String,
+}
+
+/// Input source that keeps track of its position, and to which data that was read ahead can be
+/// given back.
+pub(crate) struct Input<R: Read> {
+    inner: R,
+    position: usize,
+    pushback: Vec<u8>,
+}
+
+impl<R: Read> Input<R> {
+    pub fn new(inner: R) -> Input<R> {
+        Input {
+            inner: inner,
+            position: 0,
+            pushback: Vec::new(),
+        }
+    }
+
+    /// Position of the next byte to read in the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Check wether the end of the input has been reached.
+    pub fn at_eof(&mut self) -> io::Result<bool> {
+        let mut byte = [0u8; 1];
+
+        if self.read(&mut byte)? == 0 {
+            return Ok(true);
+        }
+
+        self.unread(&byte);
+        Ok(false)
+    }
+
+    /// Returns the next `len` bytes of the input, or less at its end, without consuming them.
+    pub fn peek(&mut self, len: usize) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.take(len as u64).read_to_end(&mut data)?;
+
+        self.unread(&data);
+        Ok(data)
+    }
+
+    /// Give back `data`, which was just read, so that it is read again.
+    pub fn unread(&mut self, data: &[u8]) {
+        let mut pushback = data.to_vec();
+        pushback.extend_from_slice(&self.pushback);
+
+        self.pushback = pushback;
+        self.position -= data.len();
+    }
+}
+
+impl<R: Read> Read for Input<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = if self.pushback.is_empty() {
+            self.inner.read(buf)?
+        } else {
+            let n = buf.len().min(self.pushback.len());
+            buf[..n].copy_from_slice(&self.pushback[..n]);
+            self.pushback.drain(..n);
+            n
+        };
+
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// Scan `input` byte by byte for a cluster whose size is at most `max_size` and whose timecode is
+/// at least `min_timecode`. Returns the position of the cluster, from which the input is read
+/// again, or `None` if the end of the input is reached.
+pub(crate) fn find_cluster<R: Read>(input: &mut Input<R>, min_timecode: u64, max_size: usize) -> io::Result<Option<usize>> {
+    let id = [(el::CLUSTER >> 24) as u8, (el::CLUSTER >> 16) as u8, (el::CLUSTER >> 8) as u8, el::CLUSTER as u8];
+
+    let mut window = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let mut eof = false;
+
+    loop {
+        while !eof && window.len() < LOOKAHEAD {
+            let n = input.read(&mut chunk)?;
+            window.extend_from_slice(&chunk[..n]);
+            eof = n == 0;
+        }
+
+        let found = window.windows(4).position(|bytes| bytes == id);
+
+        match found {
+            Some(start) if window.len() - start >= LOOKAHEAD || eof => {
+                if plausible(&window[start + 4..], min_timecode, max_size) {
+                    let position = input.position() - (window.len() - start);
+                    input.unread(&window[start..]);
+
+                    return Ok(Some(position));
+                }
+
+                window.drain(..start + 1);
+            },
+
+            // Not enough data after the ID to check it.
+            Some(start) => {
+                window.drain(..start);
+            },
+
+            None if eof => return Ok(None),
+
+            // Keep the last bytes, which may be the start of an ID.
+            None => {
+                let keep = window.len().min(3);
+                let len = window.len();
+                window.drain(..len - keep);
+            },
+        };
+
+        if eof && window.len() < 4 {
+            return Ok(None);
+        }
+    }
+}
+
+/// Check that `data`, following a cluster ID, starts with a plausible size and `Timecode`.
+fn plausible(data: &[u8], min_timecode: u64, max_size: usize) -> bool {
+    let (size, len) = match read_vint(data) {
+        Some(vint) => vint,
+        None => return false,
+    };
+
+    if size > max_size as u64 {
+        return false;
+    }
+
+    let data = &data[len..];
+    if data.first() != Some(&(el::TIMECODE as u8)) {
+        return false;
+    }
+
+    match read_vint(&data[1..]) {
+        Some((timecode_len, len)) if timecode_len >= 1 && timecode_len <= 8 => {
+            let value = &data[1 + len..];
+
+            value.len() >= timecode_len as usize && value[..timecode_len as usize].iter()
+                .fold(0, |timecode, byte| (timecode << 8) | *byte as u64) >= min_timecode
+        },
+
+        _ => false,
+    }
+}
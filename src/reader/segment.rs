@@ -6,12 +6,29 @@ use ebml::ElementArray;
 use elements as el;
 use error::Result;
 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Info {
     timecode_scale: UnsignedInt,
     uid: Option<Binary>,
     filename: Option<Utf8>,
+    prev_uid: Option<Binary>,
+    next_uid: Option<Binary>,
+    prev_filename: Option<Utf8>,
+    next_filename: Option<Utf8>,
+    duration: Option<Float>,
+    title: Option<Utf8>,
+    muxing_app: Option<Utf8>,
+    writing_app: Option<Utf8>,
+    extra: Vec<(ElementId, Binary)>,
 }
 
+/// Children of the `Info` element that are parsed into `Info`.
+const INFO_ELEMENTS: &'static [ElementId] = &[
+    el::SEGMENT_UID, el::SEGMENT_FILENAME, el::PREV_UID, el::NEXT_UID, el::PREV_FILENAME,
+    el::NEXT_FILENAME, el::TIMECODE_SCALE, el::DURATION, el::TITLE, el::MUXING_APP, el::WRITING_APP,
+];
+
 impl Info {
     /// Timestamp scale of the segment in nanoseconds.
     pub fn timecode_scale(&self) -> u64 {
@@ -35,6 +52,143 @@ impl Info {
             None
         }
     }
+
+    /// Optional. Unique identifier of the previous segment of a linked chain.
+    pub fn prev_uid(&self) -> Option<&[u8]> {
+        if let Some(ref uid) = self.prev_uid {
+            Some(uid.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Optional. Unique identifier of the next segment of a linked chain.
+    pub fn next_uid(&self) -> Option<&[u8]> {
+        if let Some(ref uid) = self.next_uid {
+            Some(uid.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Optional. The file name of the previous segment of a linked chain.
+    pub fn prev_filename(&self) -> Option<&str> {
+        if let Some(ref filename) = self.prev_filename {
+            Some(filename.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Optional. The file name of the next segment of a linked chain.
+    pub fn next_filename(&self) -> Option<&str> {
+        if let Some(ref filename) = self.next_filename {
+            Some(filename.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Unique identifiers of the families of segments that the segment belongs to. They are kept
+    /// with the unparsed children, as there may be several of them.
+    pub fn families(&self) -> Vec<&[u8]> {
+        self.extra.iter()
+            .filter(|&&(id, _)| id == el::SEGMENT_FAMILY)
+            .map(|&(_, ref family)| family.as_slice())
+            .collect()
+    }
+
+    /// Optional. Duration of the segment, in units of the timestamp scale.
+    pub fn duration(&self) -> Option<f64> {
+        self.duration
+    }
+
+    /// Optional. General name of the segment.
+    pub fn title(&self) -> Option<&str> {
+        if let Some(ref title) = self.title {
+            Some(title.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Optional. Name of the library that was used to create the file.
+    pub fn muxing_app(&self) -> Option<&str> {
+        if let Some(ref app) = self.muxing_app {
+            Some(app.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Optional. Name of the application that was used to create the file.
+    pub fn writing_app(&self) -> Option<&str> {
+        if let Some(ref app) = self.writing_app {
+            Some(app.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Children of the `Info` element that are not parsed, as ID and encoded content pairs.
+    pub fn extra(&self) -> &[(u64, Vec<u8>)] {
+        self.extra.as_slice()
+    }
+
+    /// Set the name of the library that was used to create the file.
+    pub fn set_muxing_app(&mut self, app: Option<String>) {
+        self.muxing_app = app;
+    }
+
+    /// Set the unique identifier of the segment.
+    pub fn set_uid(&mut self, uid: Option<Vec<u8>>) {
+        self.uid = uid;
+    }
+
+    /// Set the unique identifier of the previous segment of a linked chain.
+    pub fn set_prev_uid(&mut self, uid: Option<Vec<u8>>) {
+        self.prev_uid = uid;
+    }
+
+    /// Set the unique identifier of the next segment of a linked chain.
+    pub fn set_next_uid(&mut self, uid: Option<Vec<u8>>) {
+        self.next_uid = uid;
+    }
+
+    /// Set the file names of the previous and next segments of a linked chain.
+    pub fn set_filenames(&mut self, prev: Option<String>, next: Option<String>) {
+        self.prev_filename = prev;
+        self.next_filename = next;
+    }
+
+    /// Set the duration of the segment, in units of the timestamp scale.
+    pub fn set_duration(&mut self, duration: Option<f64>) {
+        self.duration = duration;
+    }
+
+    /// Set the general name of the segment.
+    pub fn set_title(&mut self, title: Option<String>) {
+        self.title = title;
+    }
+}
+
+impl Default for Info {
+    fn default() -> Info {
+        Info {
+            timecode_scale: 1000000,
+            uid: None,
+            filename: None,
+            prev_uid: None,
+            next_uid: None,
+            prev_filename: None,
+            next_filename: None,
+            duration: None,
+            title: None,
+            muxing_app: None,
+            writing_app: None,
+            extra: Vec::new(),
+        }
+    }
 }
 
 /// Read matroska segment information. Expected input: children of the `Info` master element.
@@ -45,12 +199,45 @@ pub fn read(mut elems: ElementArray) -> Result<Info> {
     let filename = elems.find(el::SEGMENT_FILENAME)
         .map_or_else(|| Ok(None), |elem| elem.content().into_utf8().map(|s| Some(s)))?;
 
+    let prev_uid = elems.find(el::PREV_UID)
+        .map(|elem| elem.content().into_binary());
+
+    let next_uid = elems.find(el::NEXT_UID)
+        .map(|elem| elem.content().into_binary());
+
+    let prev_filename = elems.find(el::PREV_FILENAME)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_utf8().map(|s| Some(s)))?;
+
+    let next_filename = elems.find(el::NEXT_FILENAME)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_utf8().map(|s| Some(s)))?;
+
     let timecode_scale = elems.find(el::TIMECODE_SCALE)
         .map_or(1000000, |elem| elem.content().into_uint());
 
+    let duration = elems.find(el::DURATION)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_float().map(|f| Some(f)))?;
+
+    let title = elems.find(el::TITLE)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_utf8().map(|s| Some(s)))?;
+
+    let muxing_app = elems.find(el::MUXING_APP)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_utf8().map(|s| Some(s)))?;
+
+    let writing_app = elems.find(el::WRITING_APP)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_utf8().map(|s| Some(s)))?;
+
     Ok(Info {
         uid: uid,
         filename: filename,
-        timecode_scale: timecode_scale
+        prev_uid: prev_uid,
+        next_uid: next_uid,
+        prev_filename: prev_filename,
+        next_filename: next_filename,
+        timecode_scale: timecode_scale,
+        duration: duration,
+        title: title,
+        muxing_app: muxing_app,
+        writing_app: writing_app,
+        extra: super::unparsed(elems, INFO_ELEMENTS),
     })
 }
@@ -0,0 +1,140 @@
+//! Read matroska tags.
+
+use ebml::types::*;
+use ebml::{Element, ElementArray};
+
+use elements as el;
+use error::{self, Result};
+
+/// Elements that a tag applies to. A tag without any UID applies to the whole segment.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Targets {
+    pub type_value: UnsignedInt,
+    pub target_type: Option<Utf8>,
+    pub track_uids: Vec<UnsignedInt>,
+    pub edition_uids: Vec<UnsignedInt>,
+    pub chapter_uids: Vec<UnsignedInt>,
+    pub attachment_uids: Vec<UnsignedInt>,
+}
+
+/// A named value, which can contain nested values.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SimpleTag {
+    pub name: Utf8,
+    pub language: Utf8,
+    pub default: bool,
+    pub string: Option<Utf8>,
+    pub binary: Option<Binary>,
+    pub children: Vec<SimpleTag>,
+}
+
+/// Represents a set of values applying to the same targets.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Tag {
+    pub targets: Targets,
+    pub simple_tags: Vec<SimpleTag>,
+}
+
+/// Read all the tags of the matroska file. Expected input: children of the `Tags` master element.
+pub fn read(elems: ElementArray) -> Result<Vec<Tag>> {
+    let mut tags = Vec::new();
+
+    for entry in elems.vec() {
+        if entry.id() != el::TAG {
+            continue;
+        }
+
+        let tag = read_tag(entry)
+            .map_err(|err| error::within(err, el::TAG, Some(tags.len() + 1)))?;
+
+        tags.push(tag);
+    }
+
+    Ok(tags)
+}
+
+fn read_tag(entry: Element) -> Result<Tag> {
+    let mut tag = Tag {
+        targets: Targets {
+            type_value: 50,
+            ..Targets::default()
+        },
+        simple_tags: Vec::new(),
+    };
+
+    for elem in entry.content().children()?.vec() {
+        match elem.id() {
+            el::TARGETS => {
+                tag.targets = read_targets(elem)
+                    .map_err(|err| error::within(err, el::TARGETS, None))?;
+            },
+
+            el::SIMPLE_TAG => {
+                let simple = read_simple_tag(elem)
+                    .map_err(|err| error::within(err, el::SIMPLE_TAG, Some(tag.simple_tags.len() + 1)))?;
+
+                tag.simple_tags.push(simple);
+            },
+
+            _ => {},
+        };
+    }
+
+    Ok(tag)
+}
+
+fn read_targets(elem: Element) -> Result<Targets> {
+    let mut targets = Targets {
+        type_value: 50,
+        ..Targets::default()
+    };
+
+    for elem in elem.content().children()?.vec() {
+        match elem.id() {
+            el::TARGET_TYPE_VALUE => targets.type_value = elem.content().into_uint(),
+            el::TARGET_TYPE => targets.target_type = Some(elem.content().into_utf8()?),
+            el::TAG_TRACK_UID => targets.track_uids.push(elem.content().into_uint()),
+            el::TAG_EDITION_UID => targets.edition_uids.push(elem.content().into_uint()),
+            el::TAG_CHAPTER_UID => targets.chapter_uids.push(elem.content().into_uint()),
+            el::TAG_ATTACHMENT_UID => targets.attachment_uids.push(elem.content().into_uint()),
+            _ => {},
+        };
+    }
+
+    Ok(targets)
+}
+
+fn read_simple_tag(elem: Element) -> Result<SimpleTag> {
+    let mut tag = SimpleTag {
+        name: String::new(),
+        language: String::from("und"),
+        default: true,
+        string: None,
+        binary: None,
+        children: Vec::new(),
+    };
+
+    for elem in elem.content().children()?.vec() {
+        match elem.id() {
+            el::TAG_NAME => tag.name = elem.content().into_utf8()?,
+            el::TAG_LANGUAGE => tag.language = elem.content().into_utf8()?,
+            el::TAG_DEFAULT => tag.default = elem.content().into_uint() != 0,
+            el::TAG_STRING => tag.string = Some(elem.content().into_utf8()?),
+            el::TAG_BINARY => tag.binary = Some(elem.content().into_binary()),
+
+            el::SIMPLE_TAG => {
+                let child = read_simple_tag(elem)
+                    .map_err(|err| error::within(err, el::SIMPLE_TAG, Some(tag.children.len() + 1)))?;
+
+                tag.children.push(child);
+            },
+
+            _ => {},
+        };
+    }
+
+    Ok(tag)
+}
@@ -1,12 +1,35 @@
 //! Read matroska track information.
 
+use ebml as libebml;
 use ebml::types::*;
-use ebml::ElementArray;
+use ebml::{Element, ElementArray};
 
+use codec::{aac, CodecConfig};
 use elements as el;
 use error::{self, Result};
 
+/// Children of the `TrackEntry` element that are parsed into `Info`.
+const TRACK_ENTRY_ELEMENTS: &'static [ElementId] = &[
+    el::TRACK_NUMBER, el::TRACK_UID, el::TRACK_TYPE, el::FLAG_ENABLED, el::FLAG_DEFAULT,
+    el::FLAG_FORCED, el::FLAG_LACING, el::DEFAULT_DURATION, el::NAME, el::LANGUAGE, el::CODEC_ID,
+    el::CODEC_PRIVATE, el::CODEC_DELAY, el::SEEK_PRE_ROLL, el::VIDEO, el::AUDIO, el::TRACK_OPERATION,
+    el::TRICK_TRACK_UID, el::TRICK_TRACK_SEGMENT_UID, el::TRICK_TRACK_FLAG, el::TRICK_MASTER_TRACK_UID,
+    el::TRICK_MASTER_TRACK_SEGMENT_UID,
+];
+
+/// Children of the `Video` element that are parsed into `Video`.
+const VIDEO_ELEMENTS: &'static [ElementId] = &[
+    el::PIXEL_WIDTH, el::PIXEL_HEIGHT, el::DISPLAY_WIDTH, el::DISPLAY_HEIGHT,
+];
+
+/// Children of the `Audio` element that are parsed into `Audio`.
+const AUDIO_ELEMENTS: &'static [ElementId] = &[
+    el::SAMPLING_FREQUENCY, el::OUTPUT_SAMPLING_FREQUENCY, el::CHANNELS, el::BIT_DEPTH,
+];
+
 /// Possible MKV track types.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Kind {
     Video(Video),
     Audio(Audio),
@@ -17,10 +40,30 @@ pub enum Kind {
     Control,
 }
 
+impl Kind {
+    /// Numerical `TrackType` value corresponding to this kind of track.
+    pub fn track_type(&self) -> u64 {
+        match *self {
+            Kind::Video(_) => 0x01,
+            Kind::Audio(_) => 0x02,
+            Kind::Complex => 0x03,
+            Kind::Logo => 0x10,
+            Kind::Subtitle => 0x11,
+            Kind::Buttons => 0x12,
+            Kind::Control => 0x20,
+        }
+    }
+}
+
 /// Information about a video track.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Video {
     pixel_width: UnsignedInt,
     pixel_height: UnsignedInt,
+    display_width: Option<UnsignedInt>,
+    display_height: Option<UnsignedInt>,
+    extra: Vec<(ElementId, Binary)>,
 }
 
 impl Video {
@@ -33,13 +76,32 @@ impl Video {
     pub fn height(&self) -> u64 {
         self.pixel_height
     }
+
+    /// Optional. Width of the video frames to display.
+    pub fn display_width(&self) -> Option<u64> {
+        self.display_width
+    }
+
+    /// Optional. Height of the video frames to display.
+    pub fn display_height(&self) -> Option<u64> {
+        self.display_height
+    }
+
+    /// Children of the `Video` element that are not parsed, as ID and encoded content pairs.
+    pub fn extra(&self) -> &[(u64, Vec<u8>)] {
+        self.extra.as_slice()
+    }
 }
 
 /// Information about an audio track.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Audio {
     channels: UnsignedInt,
     sampling_freq: Float,
-    out_sampling_freq: Float
+    out_sampling_freq: Float,
+    bit_depth: Option<UnsignedInt>,
+    extra: Vec<(ElementId, Binary)>,
 }
 
 impl Audio {
@@ -57,14 +119,161 @@ impl Audio {
     pub fn output_sampling_frequenct(&self) -> f64 {
         self.out_sampling_freq
     }
+
+    /// Optional. Number of bits per audio sample.
+    pub fn bit_depth(&self) -> Option<u64> {
+        self.bit_depth
+    }
+
+    /// Children of the `Audio` element that are not parsed, as ID and encoded content pairs.
+    pub fn extra(&self) -> &[(u64, Vec<u8>)] {
+        self.extra.as_slice()
+    }
+}
+
+/// Role of a track in a combined stereoscopic track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlaneType {
+    Left,
+    Right,
+    Background,
+}
+
+impl PlaneType {
+    /// Numerical `TrackPlaneType` value corresponding to this plane type.
+    pub fn value(&self) -> u64 {
+        match *self {
+            PlaneType::Left => 0,
+            PlaneType::Right => 1,
+            PlaneType::Background => 2,
+        }
+    }
+}
+
+/// A track used as a plane of a combined track.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Plane {
+    pub uid: UnsignedInt,
+    pub kind: PlaneType,
+}
+
+/// Operation creating a virtual track from other tracks, referenced by their UIDs.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Operation {
+    /// Tracks whose frames are combined into a single picture, such as the views of each eye.
+    pub planes: Vec<Plane>,
+
+    /// Tracks whose blocks are joined in a single track, in the order of their timestamps.
+    pub join_uids: Vec<UnsignedInt>,
+}
+
+/// Links between a track and the track used for its smooth fast-forward and rewind, or trick
+/// track. A segment UID of `None` refers to the segment of the track.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Trick {
+    /// UID of the trick track of this track, and of its segment.
+    pub track_uid: Option<UnsignedInt>,
+    pub track_segment_uid: Option<Binary>,
+
+    /// Wether this track is a trick track.
+    pub trick_track: bool,
+
+    /// UID of the track that this trick track is for, and of its segment.
+    pub master_track_uid: Option<UnsignedInt>,
+    pub master_track_segment_uid: Option<Binary>,
+}
+
+/// Codec private data that could not be parsed. The audio parameters missing from the track then
+/// take their default values.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CodecError {
+    /// Number of the track, and position of the `Tracks` element in the segment data.
+    pub track: UnsignedInt,
+    pub position: usize,
+
+    /// Description of the error.
+    pub error: String,
+}
+
+/// A transformation applied to the frames of a track or to its codec private data, which must be
+/// reverted before they can be decoded.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ContentEncoding {
+    /// Order in which the encodings were applied. They are reverted from the highest order.
+    pub order: UnsignedInt,
+
+    /// Bit field of what is encoded: 1 for the frames, 2 for the codec private data.
+    pub scope: UnsignedInt,
+
+    /// 0 for compression, 1 for encryption.
+    pub kind: UnsignedInt,
+
+    /// Compression algorithm and its settings. The algorithm is 0 for zlib, 1 for bzlib, 2 for
+    /// lzo1x and 3 for header stripping, whose settings are the bytes removed from each frame.
+    pub compression: Option<(UnsignedInt, Option<Binary>)>,
+}
+
+impl ContentEncoding {
+    /// Whether the frames of the track are encoded.
+    pub fn frames(&self) -> bool {
+        self.scope & 0x01 != 0
+    }
+
+    /// Whether the codec private data of the track is encoded.
+    pub fn codec_private(&self) -> bool {
+        self.scope & 0x02 != 0
+    }
+
+    /// Revert this encoding on `data`. Only header stripping is supported: other compression
+    /// algorithms and encryption return an error.
+    pub fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self.kind {
+            0 => {},
+            1 => bail!(error::invalid_value(el::CONTENT_ENCODING_TYPE, "encryption is not supported")),
+            wtf => bail!(error::invalid_value(el::CONTENT_ENCODING_TYPE, wtf)),
+        };
+
+        match self.compression {
+            Some((3, ref settings)) => {
+                let mut decoded = settings.clone().unwrap_or_default();
+                decoded.extend_from_slice(data);
+
+                Ok(decoded)
+            },
+
+            Some((algorithm, _)) => bail!(error::invalid_value(el::CONTENT_COMP_ALGO, algorithm)),
+            None => bail!(error::not_found(el::CONTENT_COMPRESSION)),
+        }
+    }
 }
 
 /// Contains parsed information about a matroka track.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Info {
     number: UnsignedInt,
     uid: UnsignedInt,
     kind: Kind,
     codec_id: Utf8,
+    codec_private: Option<Binary>,
+    name: Option<Utf8>,
+    language: Utf8,
+    enabled: bool,
+    default: bool,
+    forced: bool,
+    lacing: bool,
+    default_duration: Option<UnsignedInt>,
+    codec_delay: UnsignedInt,
+    seek_pre_roll: UnsignedInt,
+    operation: Option<Operation>,
+    trick: Trick,
+    extra: Vec<(ElementId, Binary)>,
 }
 
 impl Info {
@@ -87,6 +296,149 @@ impl Info {
     pub fn codec(&self) -> &str {
         self.codec_id.as_str()
     }
+
+    /// Optional. Codec-specific initialization data.
+    pub fn codec_private(&self) -> Option<&[u8]> {
+        if let Some(ref data) = self.codec_private {
+            Some(data.as_slice())
+        } else {
+            None
+        }
+    }
+
+    /// Parse the codec private data according to the codec of the track. AAC tracks without
+    /// private data are described by their legacy codec ID and audio parameters. Returns `None`
+    /// if the codec has no known configuration or if there is no private data.
+    pub fn codec_config(&self) -> Result<Option<CodecConfig>> {
+        let config = CodecConfig::parse(self.codec(), self.codec_private())?;
+
+        if let (None, None, &Kind::Audio(ref audio)) = (&config, self.codec_private(), &self.kind) {
+            let config = aac::legacy_config(self.codec(), audio.sampling_frequency() as u32, audio.channels() as u8);
+            return Ok(config.map(CodecConfig::Aac));
+        }
+
+        Ok(config)
+    }
+
+    /// Optional. Human-readable name of the track.
+    pub fn name(&self) -> Option<&str> {
+        if let Some(ref name) = self.name {
+            Some(name.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Language of the track in the ISO-639-2 form.
+    pub fn language(&self) -> &str {
+        self.language.as_str()
+    }
+
+    /// Wether the track is usable.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Wether the track should be selected by a player if no user preference is set.
+    pub fn default(&self) -> bool {
+        self.default
+    }
+
+    /// Wether the track must be played regardless of the user preferences.
+    pub fn forced(&self) -> bool {
+        self.forced
+    }
+
+    /// Wether the track may contain blocks using lacing.
+    pub fn lacing(&self) -> bool {
+        self.lacing
+    }
+
+    /// Optional. Number of nanoseconds a frame lasts.
+    pub fn default_duration(&self) -> Option<u64> {
+        self.default_duration
+    }
+
+    /// Built-in delay of the codec in nanoseconds.
+    pub fn codec_delay(&self) -> u64 {
+        self.codec_delay
+    }
+
+    /// Amount of nanoseconds that must be decoded before the actual playback position after a seek.
+    pub fn seek_pre_roll(&self) -> u64 {
+        self.seek_pre_roll
+    }
+
+    /// Optional. Operation creating this virtual track from other tracks.
+    pub fn operation(&self) -> Option<&Operation> {
+        self.operation.as_ref()
+    }
+
+    /// Links to the trick track of this track, or to the master track of this trick track.
+    pub fn trick(&self) -> &Trick {
+        &self.trick
+    }
+
+    /// Transformations applied to the frames of the track or to its codec private data, in the
+    /// order in which they must be reverted. They are read from the `ContentEncodings` element,
+    /// which is kept with the unparsed children.
+    pub fn content_encodings(&self) -> Result<Vec<ContentEncoding>> {
+        let mut encodings = Vec::new();
+
+        for &(id, ref content) in &self.extra {
+            if id != el::CONTENT_ENCODINGS {
+                continue;
+            }
+
+            let elems = libebml::reader::read_element_content(&mut &content[..], content.len())
+                .and_then(|(content, _)| content.children())?;
+
+            for elem in elems.vec() {
+                if elem.id() != el::CONTENT_ENCODING {
+                    continue;
+                }
+
+                let index = encodings.len() + 1;
+                let encoding = read_encoding(elem)
+                    .map_err(|err| error::within(error::within(err, el::CONTENT_ENCODING, Some(index)), el::CONTENT_ENCODINGS, None))?;
+
+                encodings.push(encoding);
+            }
+        }
+
+        encodings.sort_by(|a, b| b.order.cmp(&a.order));
+        Ok(encodings)
+    }
+
+    /// Children of the `TrackEntry` element that are not parsed, as ID and encoded content pairs.
+    pub fn extra(&self) -> &[(u64, Vec<u8>)] {
+        self.extra.as_slice()
+    }
+
+    /// Set the human-readable name of the track.
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Set the language of the track.
+    pub fn set_language(&mut self, language: String) {
+        self.language = language;
+    }
+
+    /// Set the default flag of the track.
+    pub fn set_default(&mut self, default: bool) {
+        self.default = default;
+    }
+
+    /// Set the codec private data of the track.
+    pub fn set_codec_private(&mut self, codec_private: Option<Vec<u8>>) {
+        self.codec_private = codec_private;
+    }
+
+    /// Set the forced flag of the track.
+    pub fn set_forced(&mut self, forced: bool) {
+        self.forced = forced;
+    }
 }
 
 /// Read information about all tracks in the matroska file. Expected input: children of the
@@ -95,81 +447,277 @@ pub fn read(elems: ElementArray) -> Result<Vec<Info>> {
     let mut tracks = Vec::new();
 
     for track_entry in elems.vec() {
-        let mut data = track_entry.content().children()?;
-
-        let number = data.find(el::TRACK_NUMBER)
-            .ok_or(error::not_found(el::TRACK_NUMBER))?
-            .content().into_uint();
-
-        let uid = data.find(el::TRACK_UID)
-            .ok_or(error::not_found(el::TRACK_UID))?
-            .content().into_uint();
-
-        let codec_id = data.find(el::CODEC_ID)
-            .ok_or(error::not_found(el::CODEC_ID))?
-            .content().into_utf8()?;
-
-        let track_type = data.find(el::TRACK_TYPE)
-            .ok_or(error::not_found(el::TRACK_TYPE))?
-            .content().into_uint();
-
-        let kind = match track_type {
-            0x01 => {
-                let mut video = data.find(el::VIDEO)
-                    .ok_or(error::not_found(el::VIDEO))?
-                    .content().children()?;
-
-                let pw = video.find(el::PIXEL_WIDTH)
-                    .ok_or(error::not_found(el::PIXEL_WIDTH))?
-                    .content().into_uint();
-
-                let ph = video.find(el::PIXEL_HEIGHT)
-                    .ok_or(error::not_found(el::PIXEL_HEIGHT))?
-                    .content().into_uint();
-
-                Kind::Video(Video {
-                    pixel_width: pw,
-                    pixel_height: ph,
-                })
-            },
+        if track_entry.id() != el::TRACK_ENTRY {
+            continue;
+        }
+
+        let track = read_entry(track_entry)
+            .map_err(|err| error::within(err, el::TRACK_ENTRY, Some(tracks.len() + 1)))?;
+
+        tracks.push(track);
+    }
 
-            0x02 => {
-                let mut audio = data.find(el::AUDIO)
-                    .ok_or(error::not_found(el::AUDIO))?
-                    .content().children()?;
+    Ok(tracks)
+}
+
+/// Read a track from a `TrackEntry` element.
+fn read_entry(entry: Element) -> Result<Info> {
+    let mut data = entry.content().children()?;
+
+    let number = data.find(el::TRACK_NUMBER)
+        .ok_or(error::not_found(el::TRACK_NUMBER))?
+        .content().into_uint();
+
+    let uid = data.find(el::TRACK_UID)
+        .ok_or(error::not_found(el::TRACK_UID))?
+        .content().into_uint();
+
+    let codec_id = data.find(el::CODEC_ID)
+        .ok_or(error::not_found(el::CODEC_ID))?
+        .content().into_utf8()?;
+
+    let codec_private = data.find(el::CODEC_PRIVATE)
+        .map(|elem| elem.content().into_binary());
+
+    let name = data.find(el::NAME)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_utf8().map(|s| Some(s)))?;
+
+    let language = data.find(el::LANGUAGE)
+        .map_or_else(|| Ok(String::from("eng")), |elem| elem.content().into_utf8())?;
+
+    let enabled = data.find(el::FLAG_ENABLED)
+        .map_or(1, |elem| elem.content().into_uint());
+
+    let default = data.find(el::FLAG_DEFAULT)
+        .map_or(1, |elem| elem.content().into_uint());
+
+    let forced = data.find(el::FLAG_FORCED)
+        .map_or(0, |elem| elem.content().into_uint());
+
+    let lacing = data.find(el::FLAG_LACING)
+        .map_or(1, |elem| elem.content().into_uint());
+
+    let default_duration = data.find(el::DEFAULT_DURATION)
+        .map(|elem| elem.content().into_uint());
+
+    let codec_delay = data.find(el::CODEC_DELAY)
+        .map_or(0, |elem| elem.content().into_uint());
+
+    let seek_pre_roll = data.find(el::SEEK_PRE_ROLL)
+        .map_or(0, |elem| elem.content().into_uint());
+
+    let operation = match data.find(el::TRACK_OPERATION) {
+        Some(elem) => Some(read_operation(elem).map_err(|err| error::within(err, el::TRACK_OPERATION, None))?),
+        None => None,
+    };
+
+    let trick = Trick {
+        track_uid: data.find(el::TRICK_TRACK_UID).map(|elem| elem.content().into_uint()),
+        track_segment_uid: data.find(el::TRICK_TRACK_SEGMENT_UID).map(|elem| elem.content().into_binary()),
+        trick_track: data.find(el::TRICK_TRACK_FLAG).map_or(0, |elem| elem.content().into_uint()) != 0,
+        master_track_uid: data.find(el::TRICK_MASTER_TRACK_UID).map(|elem| elem.content().into_uint()),
+        master_track_segment_uid: data.find(el::TRICK_MASTER_TRACK_SEGMENT_UID).map(|elem| elem.content().into_binary()),
+    };
+
+    let track_type = data.find(el::TRACK_TYPE)
+        .ok_or(error::not_found(el::TRACK_TYPE))?
+        .content().into_uint();
+
+    let kind = match track_type {
+        0x01 => {
+            let video = data.find(el::VIDEO).ok_or(error::not_found(el::VIDEO))?;
+            Kind::Video(read_video(video).map_err(|err| error::within(err, el::VIDEO, None))?)
+        },
+
+        0x02 => {
+            let audio = data.find(el::AUDIO).ok_or(error::not_found(el::AUDIO))?;
+            let audio = read_audio(audio, &codec_id, codec_private.as_ref().map(|data| data.as_slice()));
+
+            Kind::Audio(audio.map_err(|err| error::within(err, el::AUDIO, None))?)
+        },
+
+        0x03 => Kind::Complex,
+        0x10 => Kind::Logo,
+        0x11 => Kind::Subtitle,
+        0x12 => Kind::Buttons,
+        0x20 => Kind::Control,
+
+        wtf => bail!(error::invalid_value(el::TRACK_TYPE, wtf)),
+    };
+
+    Ok(Info {
+        number: number,
+        uid: uid,
+        codec_id: codec_id,
+        codec_private: codec_private,
+        name: name,
+        language: language,
+        enabled: enabled != 0,
+        default: default != 0,
+        forced: forced != 0,
+        lacing: lacing != 0,
+        default_duration: default_duration,
+        codec_delay: codec_delay,
+        seek_pre_roll: seek_pre_roll,
+        operation: operation,
+        trick: trick,
+        kind: kind,
+        extra: super::unparsed(data, TRACK_ENTRY_ELEMENTS),
+    })
+}
 
-                let channels = audio.find(el::CHANNELS)
-                    .map_or(1, |elem| elem.content().into_uint());
+/// Read the `TrackOperation` element of a track.
+fn read_operation(elem: Element) -> Result<Operation> {
+    let mut operation = Operation::default();
 
-                let sampling_freq = audio.find(el::SAMPLING_FREQUENCY)
-                    .map_or_else(|| Ok(8000.0), |elem| elem.content().into_float())?;
+    for elem in elem.content().children()?.vec() {
+        match elem.id() {
+            el::TRACK_COMBINE_PLANES => {
+                for plane in elem.content().children()?.vec() {
+                    if plane.id() != el::TRACK_PLANE {
+                        continue;
+                    }
 
-                let out_sampling_freq = audio.find(el::OUTPUT_SAMPLING_FREQUENCY)
-                    .map_or_else(|| Ok(sampling_freq), |elem| elem.content().into_float())?;
+                    let index = operation.planes.len() + 1;
+                    let plane = read_plane(plane)
+                        .map_err(|err| error::within(error::within(err, el::TRACK_PLANE, Some(index)), el::TRACK_COMBINE_PLANES, None))?;
 
-                Kind::Audio(Audio {
-                    channels: channels,
-                    sampling_freq: sampling_freq,
-                    out_sampling_freq: out_sampling_freq,
-                })
+                    operation.planes.push(plane);
+                }
             },
 
-            0x03 => Kind::Complex,
-            0x10 => Kind::Logo,
-            0x11 => Kind::Subtitle,
-            0x12 => Kind::Buttons,
-            0x20 => Kind::Control,
+            el::TRACK_JOIN_BLOCKS => {
+                for uid in elem.content().children()?.vec() {
+                    if uid.id() == el::TRACK_JOIN_UID {
+                        operation.join_uids.push(uid.content().into_uint());
+                    }
+                }
+            },
 
-            wtf => bail!(error::invalid_value(el::TRACK_TYPE, wtf)),
+            _ => {},
         };
-
-        tracks.push(Info {
-            number: number,
-            uid: uid,
-            codec_id: codec_id,
-            kind: kind,
-        });
     }
 
-    Ok(tracks)
+    Ok(operation)
+}
+
+/// Read a `ContentEncoding` element.
+fn read_encoding(encoding: Element) -> Result<ContentEncoding> {
+    let mut encoding = encoding.content().children()?;
+
+    let compression = match encoding.find(el::CONTENT_COMPRESSION) {
+        Some(elem) => {
+            let mut compression = elem.content().children()?;
+
+            let algorithm = compression.find(el::CONTENT_COMP_ALGO)
+                .map_or(0, |elem| elem.content().into_uint());
+
+            let settings = compression.find(el::CONTENT_COMP_SETTINGS)
+                .map(|elem| elem.content().into_binary());
+
+            Some((algorithm, settings))
+        },
+
+        None => None,
+    };
+
+    Ok(ContentEncoding {
+        order: encoding.find(el::CONTENT_ENCODING_ORDER).map_or(0, |elem| elem.content().into_uint()),
+        scope: encoding.find(el::CONTENT_ENCODING_SCOPE).map_or(1, |elem| elem.content().into_uint()),
+        kind: encoding.find(el::CONTENT_ENCODING_TYPE).map_or(0, |elem| elem.content().into_uint()),
+        compression: compression,
+    })
+}
+
+/// Read a `TrackPlane` element.
+fn read_plane(plane: Element) -> Result<Plane> {
+    let mut plane = plane.content().children()?;
+
+    let uid = plane.find(el::TRACK_PLANE_UID)
+        .ok_or(error::not_found(el::TRACK_PLANE_UID))?
+        .content().into_uint();
+
+    let kind = match plane.find(el::TRACK_PLANE_TYPE).ok_or(error::not_found(el::TRACK_PLANE_TYPE))?.content().into_uint() {
+        0 => PlaneType::Left,
+        1 => PlaneType::Right,
+        2 => PlaneType::Background,
+        wtf => bail!(error::invalid_value(el::TRACK_PLANE_TYPE, wtf)),
+    };
+
+    Ok(Plane {
+        uid: uid,
+        kind: kind,
+    })
+}
+
+/// Read the `Video` element of a track.
+fn read_video(video: Element) -> Result<Video> {
+    let mut video = video.content().children()?;
+
+    let pw = video.find(el::PIXEL_WIDTH)
+        .ok_or(error::not_found(el::PIXEL_WIDTH))?
+        .content().into_uint();
+
+    let ph = video.find(el::PIXEL_HEIGHT)
+        .ok_or(error::not_found(el::PIXEL_HEIGHT))?
+        .content().into_uint();
+
+    let dw = video.find(el::DISPLAY_WIDTH)
+        .map(|elem| elem.content().into_uint());
+
+    let dh = video.find(el::DISPLAY_HEIGHT)
+        .map(|elem| elem.content().into_uint());
+
+    Ok(Video {
+        pixel_width: pw,
+        pixel_height: ph,
+        display_width: dw,
+        display_height: dh,
+        extra: super::unparsed(video, VIDEO_ELEMENTS),
+    })
+}
+
+/// Read the `Audio` element of a track, whose codec is used to find missing values.
+fn read_audio(audio: Element, codec_id: &str, codec_private: Option<&[u8]>) -> Result<Audio> {
+    let mut audio = audio.content().children()?;
+
+    let channels = audio.find(el::CHANNELS)
+        .map(|elem| elem.content().into_uint());
+
+    let sampling_freq = audio.find(el::SAMPLING_FREQUENCY)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_float().map(|f| Some(f)))?;
+
+    let out_sampling_freq = audio.find(el::OUTPUT_SAMPLING_FREQUENCY)
+        .map_or_else(|| Ok(None), |elem| elem.content().into_float().map(|f| Some(f)))?;
+
+    // Missing values are derived from the codec private data when it can be parsed, and otherwise
+    // take their default values. Parsing errors are reported by the reader.
+    let config = match (channels, sampling_freq, out_sampling_freq) {
+        (Some(_), Some(_), Some(_)) => None,
+        _ => CodecConfig::parse(codec_id, codec_private).unwrap_or(None),
+    };
+
+    let channels = channels
+        .or(config.as_ref().and_then(|c| c.channels()).map(|c| c as u64))
+        .unwrap_or(1);
+
+    let sampling_freq = sampling_freq
+        .or(config.as_ref().and_then(|c| c.sample_rate()).map(|f| f as f64))
+        .unwrap_or(8000.0);
+
+    // Legacy AAC codec IDs signal spectral band replication, which doubles the sampling frequency.
+    let out_sampling_freq = out_sampling_freq
+        .or(config.as_ref().and_then(|c| c.output_sample_rate()).map(|f| f as f64))
+        .unwrap_or(if codec_id.ends_with("/SBR") { sampling_freq * 2.0 } else { sampling_freq });
+
+    let bit_depth = audio.find(el::BIT_DEPTH)
+        .map(|elem| elem.content().into_uint());
+
+    Ok(Audio {
+        channels: channels,
+        sampling_freq: sampling_freq,
+        out_sampling_freq: out_sampling_freq,
+        bit_depth: bit_depth,
+        extra: super::unparsed(audio, AUDIO_ELEMENTS),
+    })
 }
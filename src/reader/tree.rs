@@ -0,0 +1,349 @@
+//! Walk a whole matroska file as a tree of EBML elements, for inspection and debugging.
+
+use std::io::{self, Read};
+
+use ebml::types::*;
+
+use schema::{self, Type};
+
+use super::limits::ReaderLimits;
+use super::resync::Input;
+
+/// Maximum number of bytes of binary content kept in the tree.
+const PREVIEW_LEN: usize = 16;
+
+/// Decoded content of an element.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Value {
+    Master(Vec<Node>),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    String(String),
+
+    /// Signed number of nanoseconds since 2001-01-01T00:00:00 UTC.
+    Date(i64),
+
+    /// First bytes of the content of binary elements, and of elements that are not in the schema.
+    Binary(Vec<u8>),
+}
+
+/// Element of the file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Node {
+    pub id: ElementId,
+
+    /// Position of the element in the file, and length of its ID and size.
+    pub offset: u64,
+    pub header_len: usize,
+
+    /// Size of the content in bytes, or `None` if it is unknown.
+    pub size: Option<u64>,
+
+    pub value: Value,
+}
+
+impl Node {
+    /// Return the name of the element, or its ID in hexadecimal if it is not in the schema.
+    pub fn name(&self) -> String {
+        schema::name(self.id)
+    }
+
+    /// Return the children of the element, if it is a master element.
+    pub fn children(&self) -> &[Node] {
+        match self.value {
+            Value::Master(ref children) => children.as_slice(),
+            _ => &[],
+        }
+    }
+}
+
+/// Elements of a file, as far as they could be read.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Tree {
+    pub elements: Vec<Node>,
+
+    /// Position and description of the error that stopped the reading, if the file is damaged.
+    pub error: Option<(u64, String)>,
+}
+
+/// Header of an element, read ahead.
+struct Header {
+    id: ElementId,
+    size: Option<u64>,
+    len: usize,
+    offset: u64,
+}
+
+/// Read all the elements of the file read from `r`, starting with the EBML header. Reading stops
+/// at the first malformed element, whose position is reported in the tree, and at the first
+/// element nested deeper than the default `ReaderLimits::max_depth`.
+pub fn read<R: Read>(r: R) -> Tree {
+    let mut walker = Walker {
+        input: Input::new(r),
+        queued: None,
+        max_depth: ReaderLimits::default().max_depth,
+    };
+
+    let mut elements = Vec::new();
+
+    let error = match walker.children("", None, 0, &mut elements) {
+        Ok(()) => None,
+        Err(err) => Some((walker.input.position() as u64, err.to_string())),
+    };
+
+    Tree {
+        elements: elements,
+        error: error,
+    }
+}
+
+struct Walker<R: Read> {
+    input: Input<R>,
+
+    // Header of an element that ended a master element of unknown size.
+    queued: Option<Header>,
+
+    max_depth: usize,
+}
+
+impl<R: Read> Walker<R> {
+    /// Read the children of the element located at `path` into `nodes`, up to the absolute
+    /// position `end`. If `end` is unknown, the children end with the input or with an element
+    /// that belongs to an ancestor. The children are at `depth`, top-level elements such as
+    /// `Tracks` being at depth 1.
+    fn children(&mut self, path: &str, end: Option<u64>, depth: usize, nodes: &mut Vec<Node>) -> io::Result<()> {
+        loop {
+            if end.map_or(false, |end| self.input.position() as u64 >= end) && self.queued.is_none() {
+                return Ok(());
+            }
+
+            let header = match self.queued.take() {
+                Some(header) => header,
+
+                None => {
+                    let offset = self.input.position() as u64;
+
+                    match read_header(&mut self.input)? {
+                        Some((id, size, len)) => Header { id: id, size: size, len: len, offset: offset },
+                        None if end.is_none() => return Ok(()),
+                        None => return Err(eof()),
+                    }
+                },
+            };
+
+            let definition = schema::find(header.id);
+
+            if end.is_none() {
+                if let Some(definition) = definition {
+                    let global = definition.parent == "*";
+
+                    if !global && definition.parent != path && path.starts_with(definition.parent) {
+                        self.queued = Some(header);
+                        return Ok(());
+                    }
+                }
+            }
+
+            if depth > self.max_depth {
+                return Err(invalid(&format!("element nested deeper than {} levels", self.max_depth)));
+            }
+
+            let kind = definition.map_or(Type::Binary, |definition| definition.kind);
+
+            if kind == Type::Master {
+                let child_path = format!("{}\\{}", path, definition.unwrap().name);
+                let child_end = header.size.map(|size| self.input.position() as u64 + size);
+
+                nodes.push(Node {
+                    id: header.id,
+                    offset: header.offset,
+                    header_len: header.len,
+                    size: header.size,
+                    value: Value::Master(Vec::new()),
+                });
+
+                if let Value::Master(ref mut children) = nodes.last_mut().unwrap().value {
+                    self.children(&child_path, child_end, depth + 1, children)?;
+                }
+
+                continue;
+            }
+
+            let size = header.size.ok_or(invalid("only master elements may have an unknown size"))?;
+
+            nodes.push(Node {
+                id: header.id,
+                offset: header.offset,
+                header_len: header.len,
+                size: header.size,
+                value: self.value(kind, size)?,
+            });
+        }
+    }
+
+    /// Read and decode the content of a non-master element.
+    fn value(&mut self, kind: Type, size: u64) -> io::Result<Value> {
+        if kind == Type::Binary {
+            let mut preview = vec![0; size.min(PREVIEW_LEN as u64) as usize];
+            self.input.read_exact(&mut preview)?;
+
+            let rest = size - preview.len() as u64;
+            if io::copy(&mut (&mut self.input).take(rest), &mut io::sink())? < rest {
+                return Err(eof());
+            }
+
+            return Ok(Value::Binary(preview));
+        }
+
+        let data = read_data(&mut self.input, size)?;
+
+        Ok(match kind {
+            Type::Unsigned => Value::Unsigned(uint(&data)),
+            Type::Signed => Value::Signed(int(&data)),
+            Type::Date => Value::Date(int(&data)),
+
+            Type::Float => match data.len() {
+                0 => Value::Float(0.0),
+                4 => Value::Float(f32::from_bits(uint(&data) as u32) as f64),
+                8 => Value::Float(f64::from_bits(uint(&data))),
+                _ => return Err(invalid("floats must be 4 or 8 bytes long")),
+            },
+
+            _ => Value::String(String::from_utf8_lossy(&data).trim_end_matches('\0').to_string()),
+        })
+    }
+}
+
+/// Find the element at `path`, such as `TrackEntry[2]/Video`, in `data`, the content of a master
+/// element. An index selects among the elements with the same name, and defaults to the first one.
+/// Returns the position of the deepest element of the path that could be found.
+pub(crate) fn find(data: &[u8], path: &str) -> Option<usize> {
+    let mut found = None;
+    let (mut start, mut end) = (0, data.len());
+
+    for component in path.split('/') {
+        let (name, index) = match component.find('[') {
+            Some(i) => (&component[..i], component[i + 1..].trim_end_matches(']').parse().unwrap_or(1)),
+            None => (component, 1),
+        };
+
+        let mut position = start;
+        let mut count = 0;
+
+        loop {
+            let mut r = &data[position..end];
+
+            let (id, size, len) = match read_header(&mut r) {
+                Ok(Some((id, Some(size), len))) => (id, size as usize, len),
+                _ => return found,
+            };
+
+            if schema::name(id) == name {
+                count += 1;
+
+                if count == index {
+                    found = Some(position);
+                    start = position + len;
+                    end = (start + size).min(end);
+                    break;
+                }
+            }
+
+            position += len + size;
+            if position >= end {
+                return found;
+            }
+        }
+    }
+
+    found
+}
+
+/// Read the ID and the size of an element, which is `None` if unknown, with the length of the
+/// header. Returns `None` at the end of the input.
+pub(crate) fn read_header<R: Read>(r: &mut R) -> io::Result<Option<(ElementId, Option<u64>, usize)>> {
+    let mut first = [0u8; 1];
+    if r.read(&mut first)? == 0 {
+        return Ok(None);
+    }
+
+    let id_len = first[0].leading_zeros() as usize + 1;
+    if id_len > 4 {
+        return Err(invalid("invalid element ID"));
+    }
+
+    let mut id = vec![first[0]; id_len];
+    r.read_exact(&mut id[1..])?;
+
+    let id = id.iter().fold(0, |id, byte| (id << 8) | *byte as u64);
+
+    r.read_exact(&mut first)?;
+
+    let size_len = first[0].leading_zeros() as usize + 1;
+    if size_len > 8 {
+        return Err(invalid("invalid element size"));
+    }
+
+    let mut size = vec![first[0]; size_len];
+    r.read_exact(&mut size[1..])?;
+
+    let (size, _) = read_vint(&size).unwrap();
+    let unknown = size == (1 << (7 * size_len)) - 1;
+
+    Ok(Some((id, if unknown { None } else { Some(size) }, id_len + size_len)))
+}
+
+/// Read the content of an element of `size` bytes.
+pub(crate) fn read_data<R: Read>(r: &mut R, size: u64) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    r.take(size).read_to_end(&mut data)?;
+
+    if (data.len() as u64) < size {
+        return Err(eof());
+    }
+
+    Ok(data)
+}
+
+/// Decode an EBML variable size integer, and return it with its length.
+pub(crate) fn read_vint(data: &[u8]) -> Option<(u64, usize)> {
+    let first = *data.first()?;
+    if first == 0 {
+        return None;
+    }
+
+    let len = first.leading_zeros() as usize + 1;
+    if data.len() < len {
+        return None;
+    }
+
+    let mask = (0xFFu32 >> len) as u8;
+    Some((data[1..len].iter().fold((first & mask) as u64, |value, byte| (value << 8) | *byte as u64), len))
+}
+
+/// Decode a big-endian unsigned integer.
+pub(crate) fn uint(data: &[u8]) -> u64 {
+    data.iter().fold(0, |value, byte| (value << 8) | *byte as u64)
+}
+
+/// Decode a big-endian signed integer.
+fn int(data: &[u8]) -> i64 {
+    if data.is_empty() {
+        return 0;
+    }
+
+    let shift = 64 - 8 * data.len().min(8) as u32;
+    ((uint(data) << shift) as i64) >> shift
+}
+
+pub(crate) fn eof() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "unexpected end of file")
+}
+
+pub(crate) fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,153 @@
+//! Copy selected tracks of a matroska file into a new file, without re-encoding.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, Write};
+
+use ebml::types::*;
+
+use elements as el;
+use error::{self, Result};
+use reader::{Info, Reader};
+use reader::{tag, track};
+use writer::{self, element, Writer};
+
+/// Modifications to apply to the information of a copied track. Fields left to `None` are kept
+/// as-is.
+#[derive(Default)]
+pub struct TrackEdit {
+    pub language: Option<String>,
+    pub name: Option<String>,
+    pub default: Option<bool>,
+    pub forced: Option<bool>,
+}
+
+impl TrackEdit {
+    /// Apply the modifications to the specified track information.
+    pub fn apply(&self, track: &mut track::Info) {
+        if let Some(ref language) = self.language {
+            track.set_language(language.clone());
+        }
+
+        if let Some(ref name) = self.name {
+            track.set_name(Some(name.clone()));
+        }
+
+        if let Some(default) = self.default {
+            track.set_default(default);
+        }
+
+        if let Some(forced) = self.forced {
+            track.set_forced(forced);
+        }
+    }
+}
+
+/// Copy the tracks whose numbers are listed in `tracks` from `input` into a new matroska file
+/// written to `output`, applying the specified edits. Packets are copied as-is and the cues are
+/// rebuilt. The chapters and the attachments are copied, and so are the tags, except those that
+/// only target tracks which are not copied. Returns the output once the file is complete.
+pub fn remux<R, W>(input: &mut Reader<R>, output: W, tracks: &[UnsignedInt], edits: &HashMap<UnsignedInt, TrackEdit>) -> Result<W>
+    where R: Read, W: Write + Seek
+{
+    let mut segment = None;
+    let mut all_tracks = None;
+    let mut metadata = Vec::new();
+
+    for info in input.info()? {
+        match info {
+            Info::Segment(info) => segment = Some(info),
+            Info::Tracks(info) => all_tracks = Some(info),
+            info => metadata.push(info),
+        };
+    }
+
+    let segment = segment.ok_or(error::not_found(el::INFO))?;
+    let all_tracks = all_tracks.ok_or(error::not_found(el::TRACKS))?;
+
+    for number in tracks {
+        if !all_tracks.iter().any(|track| track.index() == *number) {
+            bail!(error::invalid_value(el::TRACK_NUMBER, number));
+        }
+    }
+
+    let mut selected = Vec::new();
+
+    for mut track in all_tracks {
+        if !tracks.contains(&track.index()) {
+            continue;
+        }
+
+        if let Some(edit) = edits.get(&track.index()) {
+            edit.apply(&mut track);
+        }
+
+        selected.push(track);
+    }
+
+    let doc_type = input.header().doc_type().to_string();
+    let mut writer = Writer::new(output, &doc_type, &segment, &selected)?;
+
+    // Metadata may also be found between or after the clusters.
+    loop {
+        let mut clusters = 0;
+
+        while let Some(mut cluster) = input.next_cluster()? {
+            clusters += 1;
+
+            for block in cluster.blocks() {
+                let block = block?;
+
+                if tracks.contains(&block.track()) {
+                    let timecode = block.absolute_timecode();
+                    writer.write_block(block, timecode)?;
+                }
+            }
+        }
+
+        let infos = input.info()?;
+
+        if infos.is_empty() && clusters == 0 {
+            break;
+        }
+
+        metadata.extend(infos);
+    }
+
+    let uids: Vec<UnsignedInt> = selected.iter().map(|track| track.uid()).collect();
+
+    for info in metadata {
+        match info {
+            Info::Chapters(editions) => {
+                writer.add_element(el::CHAPTERS, element::content(&writer::encode_chapters(&editions)).to_vec());
+            },
+
+            Info::Tags(tags) => {
+                let tags: Vec<tag::Tag> = tags.into_iter().filter_map(|mut tag| {
+                    let track_uids = &mut tag.targets.track_uids;
+
+                    if !track_uids.is_empty() {
+                        track_uids.retain(|uid| uids.contains(uid));
+
+                        if track_uids.is_empty() {
+                            return None;
+                        }
+                    }
+
+                    Some(tag)
+                }).collect();
+
+                if !tags.is_empty() {
+                    writer.add_element(el::TAGS, element::content(&writer::encode_tags(&tags)).to_vec());
+                }
+            },
+
+            Info::Attachments(files) => {
+                writer.add_element(el::ATTACHEMENTS, element::content(&writer::encode_attachments(&files)).to_vec());
+            },
+
+            _ => {},
+        };
+    }
+
+    writer.finish()
+}
@@ -0,0 +1,277 @@
+//! Rewrite a damaged matroska file, such as a recording from a crashed encoder, into a clean one.
+
+use std::io::{Read, Seek, Write};
+
+use ebml::types::*;
+
+use elements as el;
+use error::{self, Result};
+use reader::{Info, Reader};
+use reader::{attachment, chapter, checksum, resync, tag, tree};
+use writer::{self, element, Writer};
+
+/// Changes made to the file by `repair`. The cues and the `SeekHead` are always regenerated.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Report {
+    /// Size of the segment data declared in the input, and size actually found, in bytes.
+    pub declared_size: u64,
+    pub actual_size: u64,
+
+    /// Duration declared in the input, and duration computed from the end of the last block, in
+    /// segment ticks.
+    pub declared_duration: Option<f64>,
+    pub duration: f64,
+
+    /// Damaged data skipped while reading the clusters.
+    pub resyncs: Vec<resync::Event>,
+
+    /// Number of blocks copied, and of blocks dropped because they belong to no track.
+    pub blocks: u64,
+    pub orphan_blocks: u64,
+
+    /// Top-level elements other than `Info` and `Tracks` that could not be read, with the error.
+    pub dropped_elements: Vec<String>,
+
+    /// Number of editions and attached files rebuilt from their parsed values because their
+    /// checksums did not match. The others are copied as they are.
+    pub rebuilt_elements: u64,
+}
+
+impl Report {
+    /// Wether the input was already sound, apart from its cues and `SeekHead`. The declared
+    /// duration may differ from the computed one by less than a segment tick.
+    pub fn clean(&self) -> bool {
+        self.declared_size == self.actual_size
+            && self.declared_duration.map_or(false, |duration| (duration - self.duration).abs() < 1.0)
+            && self.resyncs.is_empty()
+            && self.orphan_blocks == 0
+            && self.dropped_elements.is_empty()
+            && self.rebuilt_elements == 0
+    }
+}
+
+/// Copy everything that can be read from `input` into a new matroska file written to `output`.
+/// The input is read in lenient mode: damaged clusters are skipped up to the next readable one,
+/// and damaged metadata elements are dropped, so that only the segment information and the tracks
+/// must be readable. The segment size and duration are recomputed, and the cues and the
+/// `SeekHead` are rebuilt. All the tracks, chapters, tags and attachments that can be read are
+/// kept. Checksums are verified in report mode, and editions and attached files are copied byte
+/// for byte, unless a checksum mismatch was found in them. Returns the output once the file is
+/// complete, with the report of the changes.
+pub fn repair<R, W>(input: &mut Reader<R>, output: W) -> Result<(W, Report)>
+    where R: Read, W: Write + Seek
+{
+    input.set_lenient(true);
+    input.set_keep_contents(true);
+    input.set_checksum_mode(checksum::Mode::Report);
+
+    let mut segment = None;
+    let mut tracks = None;
+    let mut metadata = Metadata::default();
+
+    for info in input.info()? {
+        match info {
+            Info::Segment(info) => segment = Some(info),
+            Info::Tracks(info) => tracks = Some(info),
+            info => metadata.add(info),
+        };
+    }
+
+    metadata.copy(input);
+
+    let mut segment = segment.ok_or(error::not_found(el::INFO))?;
+    let tracks = tracks.ok_or(error::not_found(el::TRACKS))?;
+
+    let mut report = Report {
+        declared_size: input.segment_size() as u64,
+        actual_size: 0,
+        declared_duration: segment.duration(),
+        duration: 0.0,
+        resyncs: Vec::new(),
+        blocks: 0,
+        orphan_blocks: 0,
+        dropped_elements: Vec::new(),
+        rebuilt_elements: 0,
+    };
+
+    segment.set_duration(None);
+
+    let doc_type = input.header().doc_type().to_string();
+    let mut writer = Writer::new(output, &doc_type, &segment, &tracks)?;
+
+    // Metadata may also be found between or after the clusters. An error that stops the reading
+    // of the metadata, in `Info` or `Tracks`, ends the copy.
+    let mut failure = None;
+
+    loop {
+        let mut clusters = 0;
+
+        while let Some(mut cluster) = input.next_cluster()? {
+            clusters += 1;
+
+            for block in cluster.blocks() {
+                let block = block?;
+
+                if !tracks.iter().any(|track| track.index() == block.track()) {
+                    report.orphan_blocks += 1;
+                    continue;
+                }
+
+                let timecode = block.absolute_timecode().max(0);
+                writer.write_block(block, timecode)?;
+
+                report.blocks += 1;
+            }
+        }
+
+        match input.info() {
+            Ok(ref infos) if infos.is_empty() && clusters == 0 => break,
+            Ok(infos) => {
+                for info in infos {
+                    metadata.add(info);
+                }
+
+                metadata.copy(input);
+            },
+
+            Err(err) => {
+                failure = Some(err.to_string());
+                break;
+            },
+        };
+    }
+
+    report.actual_size = (input.position() - input.segment_offset()) as u64;
+    report.duration = writer.end_timecode() as f64;
+    report.resyncs = input.resyncs().to_vec();
+
+    report.dropped_elements = input.dropped_elements().iter()
+        .map(|dropped| dropped.error.clone())
+        .chain(failure)
+        .collect();
+
+    report.rebuilt_elements = metadata.rebuilt;
+
+    if let Some(editions) = metadata.editions {
+        writer.add_element(el::CHAPTERS, editions.concat());
+    }
+
+    if !metadata.tags.is_empty() {
+        writer.add_element(el::TAGS, element::content(&writer::encode_tags(&metadata.tags)).to_vec());
+    }
+
+    if !metadata.files.is_empty() {
+        writer.add_element(el::ATTACHEMENTS, metadata.files.concat());
+    }
+
+    Ok((writer.finish()?, report))
+}
+
+/// Metadata to copy after the clusters. Only the first set of chapters is kept, as several
+/// editions cannot be merged.
+#[derive(Default)]
+struct Metadata {
+    // Parsed chapters and attachments, waiting for their encoded content to be taken from the
+    // reader.
+    chapters: Vec<Vec<chapter::Edition>>,
+    attachments: Vec<Vec<attachment::File>>,
+
+    // Encoded editions and attached files, with the number of them that were rebuilt.
+    editions: Option<Vec<Vec<u8>>>,
+    files: Vec<Vec<u8>>,
+    rebuilt: u64,
+
+    tags: Vec<tag::Tag>,
+}
+
+impl Metadata {
+    fn add(&mut self, info: Info) {
+        match info {
+            Info::Chapters(editions) => self.chapters.push(editions),
+            Info::Attachments(files) => self.attachments.push(files),
+            Info::Tags(tags) => self.tags.extend(tags),
+            _ => {},
+        };
+    }
+
+    /// Encode the chapters and attachments added since the last call, using the content kept by
+    /// `input`, which was read in the same order.
+    fn copy<R: Read>(&mut self, input: &mut Reader<R>) {
+        let mut chapters = self.chapters.drain(..);
+        let mut attachments = self.attachments.drain(..);
+
+        for (id, position, content) in input.take_contents() {
+            let mismatches = input.checksum_mismatches();
+
+            match id {
+                el::CHAPTERS => {
+                    let editions = chapters.next().unwrap_or_default();
+
+                    if self.editions.is_some() {
+                        continue;
+                    }
+
+                    let mut encoded = Vec::new();
+
+                    for ((data, damaged), edition) in children(&content, position, el::EDITION_ENTRY, mismatches).into_iter().zip(editions) {
+                        if damaged {
+                            self.rebuilt += 1;
+                            encoded.push(element::content(&writer::encode_chapters(&[edition])).to_vec());
+                        } else {
+                            encoded.push(data.to_vec());
+                        }
+                    }
+
+                    self.editions = Some(encoded);
+                },
+
+                el::ATTACHEMENTS => {
+                    let files = attachments.next().unwrap_or_default();
+
+                    for ((data, damaged), file) in children(&content, position, el::ATTACHED_FILE, mismatches).into_iter().zip(files) {
+                        if damaged {
+                            self.rebuilt += 1;
+                            self.files.push(element::content(&writer::encode_attachments(&[file])).to_vec());
+                        } else {
+                            self.files.push(data.to_vec());
+                        }
+                    }
+                },
+
+                _ => {},
+            };
+        }
+    }
+}
+
+/// Split `data`, the content of a master element located at `position` in the segment, into its
+/// children with the ID `id`, including their ID and size. Each of them is flagged as damaged if
+/// a checksum mismatch was found in it, or in the checksum of the whole element.
+fn children<'a>(data: &'a [u8], position: usize, id: ElementId, mismatches: &[checksum::Mismatch]) -> Vec<(&'a [u8], bool)> {
+    let whole = mismatches.iter().any(|mismatch| mismatch.position == position);
+
+    let mut children = Vec::new();
+    let mut r = data;
+
+    while !r.is_empty() {
+        let start = data.len() - r.len();
+
+        let (child, size) = match tree::read_header(&mut r) {
+            Ok(Some((child, Some(size), _))) if size <= r.len() as u64 => (child, size as usize),
+            _ => break,
+        };
+
+        r = &r[size..];
+        let end = data.len() - r.len();
+
+        if child == id {
+            let damaged = whole || mismatches.iter()
+                .any(|mismatch| mismatch.position >= position + start && mismatch.position < position + end);
+
+            children.push((&data[start..end], damaged));
+        }
+    }
+
+    children
+}
@@ -0,0 +1,336 @@
+//! Schema of the matroska elements: names, types, locations and default values.
+
+use ebml::types::*;
+
+use elements as el;
+
+/// Type of the content of an element.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Type {
+    Master,
+    Unsigned,
+    Signed,
+    Float,
+
+    /// ASCII string.
+    String,
+    Utf8,
+
+    /// Signed number of nanoseconds since 2001-01-01T00:00:00 UTC.
+    Date,
+    Binary,
+}
+
+/// Default value of an element.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Value {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    String(&'static str),
+}
+
+/// Definition of an element.
+pub struct Definition {
+    pub id: ElementId,
+    pub name: &'static str,
+    pub kind: Type,
+
+    /// Path of the parent element, such as `\Segment\Info`. It is empty for root elements, and
+    /// `*` for global elements, which may appear in any master element. Chapter atoms and simple
+    /// tags may also be nested in elements of their own kind.
+    pub parent: &'static str,
+
+    /// Wether the element must be present in its parent. Mandatory elements with a default value
+    /// may be left out, the default value then applies.
+    pub mandatory: bool,
+    pub default: Option<Value>,
+}
+
+impl Definition {
+    /// Return the path of the element, such as `\Segment\Info\Duration`.
+    pub fn path(&self) -> String {
+        format!("{}\\{}", self.parent, self.name)
+    }
+}
+
+/// Definitions of all the elements, in the order of `elements`.
+pub const SCHEMA: &'static [Definition] = &[
+    // EBML header.
+
+    Definition { id: el::EBML, name: "EBML", kind: Type::Master, parent: "", mandatory: true, default: None },
+    Definition { id: el::EBML_VERSION, name: "EBMLVersion", kind: Type::Unsigned, parent: "\\EBML", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::EBML_READ_VERSION, name: "EBMLReadVersion", kind: Type::Unsigned, parent: "\\EBML", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::EBML_MAX_ID_LENGTH, name: "EBMLMaxIDLength", kind: Type::Unsigned, parent: "\\EBML", mandatory: true, default: Some(Value::Unsigned(4)) },
+    Definition { id: el::EBML_MAX_SIZE_LENGTH, name: "EBMLMaxSizeLength", kind: Type::Unsigned, parent: "\\EBML", mandatory: true, default: Some(Value::Unsigned(8)) },
+    Definition { id: el::DOC_TYPE, name: "DocType", kind: Type::String, parent: "\\EBML", mandatory: true, default: None },
+    Definition { id: el::DOC_TYPE_VERSION, name: "DocTypeVersion", kind: Type::Unsigned, parent: "\\EBML", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::DOC_TYPE_READ_VERSION, name: "DocTypeReadVersion", kind: Type::Unsigned, parent: "\\EBML", mandatory: true, default: Some(Value::Unsigned(1)) },
+
+    // Global elements.
+
+    Definition { id: el::VOID, name: "Void", kind: Type::Binary, parent: "*", mandatory: false, default: None },
+    Definition { id: el::CRC32, name: "CRC-32", kind: Type::Binary, parent: "*", mandatory: false, default: None },
+
+    // Root element.
+
+    Definition { id: el::SEGMENT, name: "Segment", kind: Type::Master, parent: "", mandatory: true, default: None },
+
+    // Meta Seek Information.
+
+    Definition { id: el::SEEK_HEAD, name: "SeekHead", kind: Type::Master, parent: "\\Segment", mandatory: false, default: None },
+    Definition { id: el::SEEK, name: "Seek", kind: Type::Master, parent: "\\Segment\\SeekHead", mandatory: true, default: None },
+    Definition { id: el::SEEK_ID, name: "SeekID", kind: Type::Binary, parent: "\\Segment\\SeekHead\\Seek", mandatory: true, default: None },
+    Definition { id: el::SEEK_POSITION, name: "SeekPosition", kind: Type::Unsigned, parent: "\\Segment\\SeekHead\\Seek", mandatory: true, default: None },
+
+    // Segment Information.
+
+    Definition { id: el::INFO, name: "Info", kind: Type::Master, parent: "\\Segment", mandatory: true, default: None },
+    Definition { id: el::SEGMENT_UID, name: "SegmentUID", kind: Type::Binary, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::SEGMENT_FILENAME, name: "SegmentFilename", kind: Type::Utf8, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::PREV_UID, name: "PrevUID", kind: Type::Binary, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::PREV_FILENAME, name: "PrevFilename", kind: Type::Utf8, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::NEXT_UID, name: "NextUID", kind: Type::Binary, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::NEXT_FILENAME, name: "NextFilename", kind: Type::Utf8, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::SEGMENT_FAMILY, name: "SegmentFamily", kind: Type::Binary, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_TRANSLATE, name: "ChapterTranslate", kind: Type::Master, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_TRANSLATE_EDITION_UID, name: "ChapterTranslateEditionUID", kind: Type::Unsigned, parent: "\\Segment\\Info\\ChapterTranslate", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_TRANSLATE_CODEC, name: "ChapterTranslateCodec", kind: Type::Unsigned, parent: "\\Segment\\Info\\ChapterTranslate", mandatory: true, default: None },
+    Definition { id: el::CHAPTER_TRANSLATE_ID, name: "ChapterTranslateID", kind: Type::Binary, parent: "\\Segment\\Info\\ChapterTranslate", mandatory: true, default: None },
+    Definition { id: el::TIMECODE_SCALE, name: "TimecodeScale", kind: Type::Unsigned, parent: "\\Segment\\Info", mandatory: true, default: Some(Value::Unsigned(1000000)) },
+    Definition { id: el::DURATION, name: "Duration", kind: Type::Float, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::DATE_UTC, name: "DateUTC", kind: Type::Date, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::TITLE, name: "Title", kind: Type::Utf8, parent: "\\Segment\\Info", mandatory: false, default: None },
+    Definition { id: el::MUXING_APP, name: "MuxingApp", kind: Type::Utf8, parent: "\\Segment\\Info", mandatory: true, default: None },
+    Definition { id: el::WRITING_APP, name: "WritingApp", kind: Type::Utf8, parent: "\\Segment\\Info", mandatory: true, default: None },
+
+    // Track information.
+
+    Definition { id: el::TRACKS, name: "Tracks", kind: Type::Master, parent: "\\Segment", mandatory: false, default: None },
+    Definition { id: el::TRACK_ENTRY, name: "TrackEntry", kind: Type::Master, parent: "\\Segment\\Tracks", mandatory: true, default: None },
+    Definition { id: el::TRACK_NUMBER, name: "TrackNumber", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: None },
+    Definition { id: el::TRACK_UID, name: "TrackUID", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: None },
+    Definition { id: el::TRACK_TYPE, name: "TrackType", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: None },
+    Definition { id: el::FLAG_ENABLED, name: "FlagEnabled", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::FLAG_DEFAULT, name: "FlagDefault", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::FLAG_FORCED, name: "FlagForced", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::FLAG_LACING, name: "FlagLacing", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::MIN_CACHE, name: "MinCache", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::MAX_CACHE, name: "MaxCache", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::DEFAULT_DURATION, name: "DefaultDuration", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::DEFAULT_DECODED_FIELD_DURATION, name: "DefaultDecodedFieldDuration", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::TRACK_TIMECODE_SCALE, name: "TrackTimecodeScale", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Float(1.0)) },
+    Definition { id: el::TRACK_OFFSET, name: "TrackOffset", kind: Type::Signed, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: Some(Value::Signed(0)) },
+    Definition { id: el::MAX_BLOCK_ADDITION_ID, name: "MaxBlockAdditionID", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::NAME, name: "Name", kind: Type::Utf8, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::LANGUAGE, name: "Language", kind: Type::String, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::String("eng")) },
+    Definition { id: el::CODEC_ID, name: "CodecID", kind: Type::String, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: None },
+    Definition { id: el::CODEC_PRIVATE, name: "CodecPrivate", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CODEC_NAME, name: "CodecName", kind: Type::Utf8, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::ATTACHMENT_LINK, name: "AttachmentLink", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CODEC_SETTINGS, name: "CodecSettings", kind: Type::Utf8, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CODEC_INFO_URL, name: "CodecInfoURL", kind: Type::String, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CODEC_DOWNLOAD_URL, name: "CodecDownloadURL", kind: Type::String, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CODEC_DECODE_ALL, name: "CodecDecodeAll", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::TRACK_OVERLAY, name: "TrackOverlay", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CODEC_DELAY, name: "CodecDelay", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::SEEK_PRE_ROLL, name: "SeekPreRoll", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::TRACK_TRANSLATE, name: "TrackTranslate", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::TRACK_TRANSLATE_EDITION_UID, name: "TrackTranslateEditionUID", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\TrackTranslate", mandatory: false, default: None },
+    Definition { id: el::TRACK_TRANSLATE_CODEC, name: "TrackTranslateCodec", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\TrackTranslate", mandatory: true, default: None },
+    Definition { id: el::TRACK_TRANSLATE_TRACK_ID, name: "TrackTranslateTrackID", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry\\TrackTranslate", mandatory: true, default: None },
+    Definition { id: el::VIDEO, name: "Video", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::FLAG_INTERLACED, name: "FlagInterlaced", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::FIELD_ORDER, name: "FieldOrder", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(2)) },
+    Definition { id: el::STEREO_MODE, name: "StereoMode", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::ALPHA_MODE, name: "AlphaMode", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::OLD_STEREO_MODE, name: "OldStereoMode", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: None },
+    Definition { id: el::PIXEL_WIDTH, name: "PixelWidth", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: None },
+    Definition { id: el::PIXEL_HEIGHT, name: "PixelHeight", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: None },
+    Definition { id: el::PIXEL_CROP_BOTTOM, name: "PixelCropBottom", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::PIXEL_CROP_TOP, name: "PixelCropTop", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::PIXEL_CROP_LEFT, name: "PixelCropLeft", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::PIXEL_CROP_RIGHT, name: "PixelCropRight", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::DISPLAY_WIDTH, name: "DisplayWidth", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: None },
+    Definition { id: el::DISPLAY_HEIGHT, name: "DisplayHeight", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: None },
+    Definition { id: el::DISPLAY_UNIT, name: "DisplayUnit", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::ASPECT_RATIO_TYPE, name: "AspectRatioType", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::COLOUR_SPACE, name: "ColourSpace", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: None },
+    Definition { id: el::GAMMA_VALUE, name: "GammaValue", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: None },
+    Definition { id: el::FRAME_RATE, name: "FrameRate", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: None },
+    Definition { id: el::COLOUR, name: "Colour", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\Video", mandatory: false, default: None },
+    Definition { id: el::MATRIX_COEFFICIENTS, name: "MatrixCoefficients", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: true, default: Some(Value::Unsigned(2)) },
+    Definition { id: el::BITS_PER_CHANNEL, name: "BitsPerChannel", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CHROMA_SUBSAMPLING_HORZ, name: "ChromaSubsamplingHorz", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: false, default: None },
+    Definition { id: el::CHROMA_SUBSAMPLING_VERT, name: "ChromaSubsamplingVert", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: false, default: None },
+    Definition { id: el::CB_SUBSAMPLING_HORZ, name: "CbSubsamplingHorz", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: false, default: None },
+    Definition { id: el::CB_SUBSAMPLING_VERT, name: "CbSubsamplingVert", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: false, default: None },
+    Definition { id: el::CHROMA_SITING_HORZ, name: "ChromaSitingHorz", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CHROMA_SITING_VERT, name: "ChromaSitingVert", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::RANGE, name: "Range", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::TRANSFER_CHARACTERISTICS, name: "TransferCharacteristics", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: true, default: Some(Value::Unsigned(2)) },
+    Definition { id: el::PRIMARIES, name: "Primaries", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: true, default: Some(Value::Unsigned(2)) },
+    Definition { id: el::MAX_CLL, name: "MaxCLL", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: false, default: None },
+    Definition { id: el::MAX_FALL, name: "MaxFALL", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: false, default: None },
+    Definition { id: el::MASTERING_METADATA, name: "MasteringMetadata", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour", mandatory: false, default: None },
+    Definition { id: el::PRIMARY_RCHROMATICITY_X, name: "PrimaryRChromaticityX", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::PRIMARY_RCHROMATICITY_Y, name: "PrimaryRChromaticityY", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::PRIMARY_GCHROMATICITY_X, name: "PrimaryGChromaticityX", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::PRIMARY_GCHROMATICITY_Y, name: "PrimaryGChromaticityY", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::PRIMARY_BCHROMATICITY_X, name: "PrimaryBChromaticityX", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::PRIMARY_BCHROMATICITY_Y, name: "PrimaryBChromaticityY", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::WHITE_POINT_CHROMATICITY_X, name: "WhitePointChromaticityX", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::WHITE_POINT_CHROMATICITY_Y, name: "WhitePointChromaticityY", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::LUMINANCE_MAX, name: "LuminanceMax", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::LUMINANCE_MIN, name: "LuminanceMin", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Video\\Colour\\MasteringMetadata", mandatory: false, default: None },
+    Definition { id: el::AUDIO, name: "Audio", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::SAMPLING_FREQUENCY, name: "SamplingFrequency", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Audio", mandatory: true, default: Some(Value::Float(8000.0)) },
+    Definition { id: el::OUTPUT_SAMPLING_FREQUENCY, name: "OutputSamplingFrequency", kind: Type::Float, parent: "\\Segment\\Tracks\\TrackEntry\\Audio", mandatory: false, default: None },
+    Definition { id: el::CHANNELS, name: "Channels", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Audio", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::CHANNEL_POSITIONS, name: "ChannelPositions", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry\\Audio", mandatory: false, default: None },
+    Definition { id: el::BIT_DEPTH, name: "BitDepth", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\Audio", mandatory: false, default: None },
+    Definition { id: el::TRACK_OPERATION, name: "TrackOperation", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::TRACK_COMBINE_PLANES, name: "TrackCombinePlanes", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\TrackOperation", mandatory: false, default: None },
+    Definition { id: el::TRACK_PLANE, name: "TrackPlane", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\TrackOperation\\TrackCombinePlanes", mandatory: true, default: None },
+    Definition { id: el::TRACK_PLANE_UID, name: "TrackPlaneUID", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\TrackOperation\\TrackCombinePlanes\\TrackPlane", mandatory: true, default: None },
+    Definition { id: el::TRACK_PLANE_TYPE, name: "TrackPlaneType", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\TrackOperation\\TrackCombinePlanes\\TrackPlane", mandatory: true, default: None },
+    Definition { id: el::TRACK_JOIN_BLOCKS, name: "TrackJoinBlocks", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\TrackOperation", mandatory: false, default: None },
+    Definition { id: el::TRACK_JOIN_UID, name: "TrackJoinUID", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\TrackOperation\\TrackJoinBlocks", mandatory: true, default: None },
+    Definition { id: el::TRICK_TRACK_UID, name: "TrickTrackUID", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::TRICK_TRACK_SEGMENT_UID, name: "TrickTrackSegmentUID", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::TRICK_TRACK_FLAG, name: "TrickTrackFlag", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::TRICK_MASTER_TRACK_UID, name: "TrickMasterTrackUID", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::TRICK_MASTER_TRACK_SEGMENT_UID, name: "TrickMasterTrackSegmentUID", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CONTENT_ENCODINGS, name: "ContentEncodings", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry", mandatory: false, default: None },
+    Definition { id: el::CONTENT_ENCODING, name: "ContentEncoding", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings", mandatory: true, default: None },
+    Definition { id: el::CONTENT_ENCODING_ORDER, name: "ContentEncodingOrder", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CONTENT_ENCODING_SCOPE, name: "ContentEncodingScope", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::CONTENT_ENCODING_TYPE, name: "ContentEncodingType", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CONTENT_COMPRESSION, name: "ContentCompression", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding", mandatory: false, default: None },
+    Definition { id: el::CONTENT_COMP_ALGO, name: "ContentCompAlgo", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentCompression", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CONTENT_COMP_SETTINGS, name: "ContentCompSettings", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentCompression", mandatory: false, default: None },
+    Definition { id: el::CONTENT_ENCRYPTION, name: "ContentEncryption", kind: Type::Master, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding", mandatory: false, default: None },
+    Definition { id: el::CONTENT_ENC_ALGO, name: "ContentEncAlgo", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentEncryption", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CONTENT_ENC_KEY_ID, name: "ContentEncKeyID", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentEncryption", mandatory: false, default: None },
+    Definition { id: el::CONTENT_SIGNATURE, name: "ContentSignature", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentEncryption", mandatory: false, default: None },
+    Definition { id: el::CONTENT_SIG_KEY_ID, name: "ContentSigKeyID", kind: Type::Binary, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentEncryption", mandatory: false, default: None },
+    Definition { id: el::CONTENT_SIG_ALGO, name: "ContentSigAlgo", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentEncryption", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CONTENT_SIG_HASH_ALGO, name: "ContentSigHashAlgo", kind: Type::Unsigned, parent: "\\Segment\\Tracks\\TrackEntry\\ContentEncodings\\ContentEncoding\\ContentEncryption", mandatory: false, default: Some(Value::Unsigned(0)) },
+    // Cueing data.
+
+    Definition { id: el::CUES, name: "Cues", kind: Type::Master, parent: "\\Segment", mandatory: false, default: None },
+    Definition { id: el::CUE_POINT, name: "CuePoint", kind: Type::Master, parent: "\\Segment\\Cues", mandatory: true, default: None },
+    Definition { id: el::CUE_TIME, name: "CueTime", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint", mandatory: true, default: None },
+    Definition { id: el::CUE_TRACK_POSITIONS, name: "CueTrackPositions", kind: Type::Master, parent: "\\Segment\\Cues\\CuePoint", mandatory: true, default: None },
+    Definition { id: el::CUE_TRACK, name: "CueTrack", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions", mandatory: true, default: None },
+    Definition { id: el::CUE_CLUSTER_POSITION, name: "CueClusterPosition", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions", mandatory: true, default: None },
+    Definition { id: el::CUE_RELATIVE_POSITION, name: "CueRelativePosition", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions", mandatory: false, default: None },
+    Definition { id: el::CUE_DURATION, name: "CueDuration", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions", mandatory: false, default: None },
+    Definition { id: el::CUE_BLOCK_NUMBER, name: "CueBlockNumber", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions", mandatory: false, default: None },
+    Definition { id: el::CUE_CODEC_STATE, name: "CueCodecState", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CUE_REFERENCE, name: "CueReference", kind: Type::Master, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions", mandatory: false, default: None },
+    Definition { id: el::CUE_REF_TIME, name: "CueRefTime", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions\\CueReference", mandatory: true, default: None },
+    Definition { id: el::CUE_REF_CLUSTER, name: "CueRefCluster", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions\\CueReference", mandatory: false, default: None },
+    Definition { id: el::CUE_REF_NUMBER, name: "CueRefNumber", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions\\CueReference", mandatory: false, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::CUE_REF_CODEC_STATE, name: "CueRefCodecState", kind: Type::Unsigned, parent: "\\Segment\\Cues\\CuePoint\\CueTrackPositions\\CueReference", mandatory: false, default: Some(Value::Unsigned(0)) },
+
+    // Cluster data.
+
+    Definition { id: el::CLUSTER, name: "Cluster", kind: Type::Master, parent: "\\Segment", mandatory: false, default: None },
+    Definition { id: el::TIMECODE, name: "Timecode", kind: Type::Unsigned, parent: "\\Segment\\Cluster", mandatory: true, default: None },
+    Definition { id: el::SILENT_TRACKS, name: "SilentTracks", kind: Type::Master, parent: "\\Segment\\Cluster", mandatory: false, default: None },
+    Definition { id: el::SILENT_TRACK_NUMBER, name: "SilentTrackNumber", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\SilentTracks", mandatory: false, default: None },
+    Definition { id: el::POSITION, name: "Position", kind: Type::Unsigned, parent: "\\Segment\\Cluster", mandatory: false, default: None },
+    Definition { id: el::PREV_SIZE, name: "PrevSize", kind: Type::Unsigned, parent: "\\Segment\\Cluster", mandatory: false, default: None },
+    Definition { id: el::SIMPLE_BLOCK, name: "SimpleBlock", kind: Type::Binary, parent: "\\Segment\\Cluster", mandatory: false, default: None },
+    Definition { id: el::BLOCK_GROUP, name: "BlockGroup", kind: Type::Master, parent: "\\Segment\\Cluster", mandatory: false, default: None },
+    Definition { id: el::BLOCK, name: "Block", kind: Type::Binary, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: true, default: None },
+    Definition { id: el::BLOCK_VIRTUAL, name: "BlockVirtual", kind: Type::Binary, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::BLOCK_ADDITIONS, name: "BlockAdditions", kind: Type::Master, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::BLOCK_MORE, name: "BlockMore", kind: Type::Master, parent: "\\Segment\\Cluster\\BlockGroup\\BlockAdditions", mandatory: true, default: None },
+    Definition { id: el::BLOCK_ADD_ID, name: "BlockAddID", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\BlockAdditions\\BlockMore", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::BLOCK_ADDITIONAL, name: "BlockAdditional", kind: Type::Binary, parent: "\\Segment\\Cluster\\BlockGroup\\BlockAdditions\\BlockMore", mandatory: true, default: None },
+    Definition { id: el::BLOCK_DURATION, name: "BlockDuration", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::REFERENCE_PRIORITY, name: "ReferencePriority", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::REFERENCE_BLOCK, name: "ReferenceBlock", kind: Type::Signed, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::REFERENCE_VIRTUAL, name: "ReferenceVirtual", kind: Type::Signed, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::CODEC_STATE, name: "CodecState", kind: Type::Binary, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::DISCARD_PADDING, name: "DiscardPadding", kind: Type::Signed, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::SLICES, name: "Slices", kind: Type::Master, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::TIME_SLICE, name: "TimeSlice", kind: Type::Master, parent: "\\Segment\\Cluster\\BlockGroup\\Slices", mandatory: false, default: None },
+    Definition { id: el::LACE_NUMBER, name: "LaceNumber", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\Slices\\TimeSlice", mandatory: false, default: None },
+    Definition { id: el::FRAME_NUMBER, name: "FrameNumber", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\Slices\\TimeSlice", mandatory: false, default: None },
+    Definition { id: el::BLOCK_ADDITIONID, name: "BlockAdditionID", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\Slices\\TimeSlice", mandatory: false, default: None },
+    Definition { id: el::DELAY, name: "Delay", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\Slices\\TimeSlice", mandatory: false, default: None },
+    Definition { id: el::SLICE_DURATION, name: "SliceDuration", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\Slices\\TimeSlice", mandatory: false, default: None },
+    Definition { id: el::REFERENCE_FRAME, name: "ReferenceFrame", kind: Type::Master, parent: "\\Segment\\Cluster\\BlockGroup", mandatory: false, default: None },
+    Definition { id: el::REFERENCE_OFFSET, name: "ReferenceOffset", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\ReferenceFrame", mandatory: true, default: None },
+    Definition { id: el::REFERENCE_TIMECODE, name: "ReferenceTimeCode", kind: Type::Unsigned, parent: "\\Segment\\Cluster\\BlockGroup\\ReferenceFrame", mandatory: true, default: None },
+    Definition { id: el::ENCRYPTED_BLOCK, name: "EncryptedBlock", kind: Type::Binary, parent: "\\Segment\\Cluster", mandatory: false, default: None },
+
+    // Attachments.
+
+    Definition { id: el::ATTACHEMENTS, name: "Attachments", kind: Type::Master, parent: "\\Segment", mandatory: false, default: None },
+    Definition { id: el::ATTACHED_FILE, name: "AttachedFile", kind: Type::Master, parent: "\\Segment\\Attachments", mandatory: true, default: None },
+    Definition { id: el::FILE_DESCRIPTION, name: "FileDescription", kind: Type::Utf8, parent: "\\Segment\\Attachments\\AttachedFile", mandatory: false, default: None },
+    Definition { id: el::FILE_NAME, name: "FileName", kind: Type::Utf8, parent: "\\Segment\\Attachments\\AttachedFile", mandatory: true, default: None },
+    Definition { id: el::FILE_MIME_TYPE, name: "FileMimeType", kind: Type::String, parent: "\\Segment\\Attachments\\AttachedFile", mandatory: true, default: None },
+    Definition { id: el::FILE_DATA, name: "FileData", kind: Type::Binary, parent: "\\Segment\\Attachments\\AttachedFile", mandatory: true, default: None },
+    Definition { id: el::FILE_UID, name: "FileUID", kind: Type::Unsigned, parent: "\\Segment\\Attachments\\AttachedFile", mandatory: true, default: None },
+
+    // Chapters.
+
+    Definition { id: el::CHAPTERS, name: "Chapters", kind: Type::Master, parent: "\\Segment", mandatory: false, default: None },
+    Definition { id: el::EDITION_ENTRY, name: "EditionEntry", kind: Type::Master, parent: "\\Segment\\Chapters", mandatory: true, default: None },
+    Definition { id: el::EDITION_UID, name: "EditionUID", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry", mandatory: false, default: None },
+    Definition { id: el::EDITION_FLAG_HIDDEN, name: "EditionFlagHidden", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::EDITION_FLAG_DEFAULT, name: "EditionFlagDefault", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::EDITION_FLAG_ORDERED, name: "EditionFlagOrdered", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CHAPTER_ATOM, name: "ChapterAtom", kind: Type::Master, parent: "\\Segment\\Chapters\\EditionEntry", mandatory: true, default: None },
+    Definition { id: el::CHAPTER_UID, name: "ChapterUID", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: true, default: None },
+    Definition { id: el::CHAPTER_STRING_UID, name: "ChapterStringUID", kind: Type::Utf8, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_TIME_START, name: "ChapterTimeStart", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: true, default: None },
+    Definition { id: el::CHAPTER_TIME_END, name: "ChapterTimeEnd", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_FLAG_HIDDEN, name: "ChapterFlagHidden", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: true, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::CHAPTER_FLAG_ENABLED, name: "ChapterFlagEnabled", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::CHAPTER_SEGMENT_UID, name: "ChapterSegmentUID", kind: Type::Binary, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_SEGMENT_EDITION_UID, name: "ChapterSegmentEditionUID", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_PHYSICAL_EQUIV, name: "ChapterPhysicalEquiv", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_TRACK, name: "ChapterTrack", kind: Type::Master, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: false, default: None },
+    Definition { id: el::CHAPTER_TRACK_NUMBER, name: "ChapterTrackNumber", kind: Type::Unsigned, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom\\ChapterTrack", mandatory: true, default: None },
+    Definition { id: el::CHAPTER_DISPLAY, name: "ChapterDisplay", kind: Type::Master, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom", mandatory: false, default: None },
+    Definition { id: el::CHAP_STRING, name: "ChapString", kind: Type::Utf8, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom\\ChapterDisplay", mandatory: true, default: None },
+    Definition { id: el::CHAP_LANGUAGE, name: "ChapLanguage", kind: Type::String, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom\\ChapterDisplay", mandatory: true, default: Some(Value::String("eng")) },
+    Definition { id: el::CHAP_COUNTRY, name: "ChapCountry", kind: Type::String, parent: "\\Segment\\Chapters\\EditionEntry\\ChapterAtom\\ChapterDisplay", mandatory: false, default: None },
+
+    // Tagging.
+
+    Definition { id: el::TAGS, name: "Tags", kind: Type::Master, parent: "\\Segment", mandatory: false, default: None },
+    Definition { id: el::TAG, name: "Tag", kind: Type::Master, parent: "\\Segment\\Tags", mandatory: true, default: None },
+    Definition { id: el::TARGETS, name: "Targets", kind: Type::Master, parent: "\\Segment\\Tags\\Tag", mandatory: true, default: None },
+    Definition { id: el::TARGET_TYPE_VALUE, name: "TargetTypeValue", kind: Type::Unsigned, parent: "\\Segment\\Tags\\Tag\\Targets", mandatory: false, default: Some(Value::Unsigned(50)) },
+    Definition { id: el::TARGET_TYPE, name: "TargetType", kind: Type::String, parent: "\\Segment\\Tags\\Tag\\Targets", mandatory: false, default: None },
+    Definition { id: el::TAG_TRACK_UID, name: "TagTrackUID", kind: Type::Unsigned, parent: "\\Segment\\Tags\\Tag\\Targets", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::TAG_EDITION_UID, name: "TagEditionUID", kind: Type::Unsigned, parent: "\\Segment\\Tags\\Tag\\Targets", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::TAG_CHAPTER_UID, name: "TagChapterUID", kind: Type::Unsigned, parent: "\\Segment\\Tags\\Tag\\Targets", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::TAG_ATTACHMENT_UID, name: "TagAttachmentUID", kind: Type::Unsigned, parent: "\\Segment\\Tags\\Tag\\Targets", mandatory: false, default: Some(Value::Unsigned(0)) },
+    Definition { id: el::SIMPLE_TAG, name: "SimpleTag", kind: Type::Master, parent: "\\Segment\\Tags\\Tag", mandatory: true, default: None },
+    Definition { id: el::TAG_NAME, name: "TagName", kind: Type::Utf8, parent: "\\Segment\\Tags\\Tag\\SimpleTag", mandatory: true, default: None },
+    Definition { id: el::TAG_LANGUAGE, name: "TagLanguage", kind: Type::String, parent: "\\Segment\\Tags\\Tag\\SimpleTag", mandatory: true, default: Some(Value::String("und")) },
+    Definition { id: el::TAG_DEFAULT, name: "TagDefault", kind: Type::Unsigned, parent: "\\Segment\\Tags\\Tag\\SimpleTag", mandatory: true, default: Some(Value::Unsigned(1)) },
+    Definition { id: el::TAG_STRING, name: "TagString", kind: Type::Utf8, parent: "\\Segment\\Tags\\Tag\\SimpleTag", mandatory: false, default: None },
+    Definition { id: el::TAG_BINARY, name: "TagBinary", kind: Type::Binary, parent: "\\Segment\\Tags\\Tag\\SimpleTag", mandatory: false, default: None },
+];
+
+/// Return the definition of the element `id`.
+pub fn find(id: ElementId) -> Option<&'static Definition> {
+    SCHEMA.iter().find(|definition| definition.id == id)
+}
+
+/// Return the name of the element `id`, or its ID in hexadecimal if it is unknown.
+pub fn name(id: ElementId) -> String {
+    match find(id) {
+        Some(definition) => definition.name.to_string(),
+        None => format!("0x{:X}", id),
+    }
+}
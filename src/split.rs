@@ -0,0 +1,164 @@
+//! Split a matroska file into several complete matroska files.
+
+use std::io::{Read, Seek, Write};
+
+use elements as el;
+use error::{self, Result};
+use reader::{Info, Reader};
+use reader::track;
+use writer::{self, Writer};
+
+/// Criteria used to choose where the input is split. Parts always start at a keyframe.
+pub enum Mode {
+    /// Start a new part every time the specified duration, in nanoseconds, is reached.
+    Duration(u64),
+
+    /// Start a new part every time the specified size, in bytes, is reached.
+    Size(u64),
+
+    /// Start a new part at each of the specified timestamps, in nanoseconds.
+    Timestamps(Vec<u64>),
+
+    /// Start a new part at the beginning of each top-level chapter of the default edition.
+    Chapters,
+}
+
+/// Split `input` according to `mode`. The `output` function is called with the index of each part
+/// to obtain the destination it should be written to. The timestamps, the duration and the cues of
+/// each part are rebuilt: timestamps are rebased on the keyframe starting the part, and blocks
+/// following that keyframe in the input but preceding it go to the previous part. If `link` is
+/// set, the parts are linked together through their `PrevUID` and `NextUID` elements. Returns the
+/// outputs once all the parts are complete.
+pub fn split<R, W, F>(input: &mut Reader<R>, mode: &Mode, link: bool, mut output: F) -> Result<Vec<W>>
+    where R: Read, W: Write + Seek, F: FnMut(usize) -> Result<W>
+{
+    let mut segment = None;
+    let mut tracks = None;
+    let mut editions = Vec::new();
+
+    for info in input.info()? {
+        match info {
+            Info::Segment(info) => segment = Some(info),
+            Info::Tracks(info) => tracks = Some(info),
+            Info::Chapters(info) => editions = info,
+            _ => {},
+        };
+    }
+
+    let segment = segment.ok_or(error::not_found(el::INFO))?;
+    let tracks = tracks.ok_or(error::not_found(el::TRACKS))?;
+    let scale = segment.timecode_scale();
+
+    // Split points in segment ticks, for the modes that define them in advance.
+    let mut boundaries: Vec<i64> = match *mode {
+        Mode::Timestamps(ref timestamps) => timestamps.iter().map(|t| (t / scale) as i64).collect(),
+
+        Mode::Chapters => {
+            let edition = editions.iter().find(|edition| edition.default())
+                .or(editions.first())
+                .ok_or(error::not_found(el::CHAPTERS))?;
+
+            edition.atoms().iter().map(|atom| (atom.time_start() / scale) as i64).collect()
+        },
+
+        _ => Vec::new(),
+    };
+
+    boundaries.sort();
+
+    let video_tracks: Vec<u64> = tracks.iter()
+        .filter(|track| match *track.kind() { track::Kind::Video(_) => true, _ => false })
+        .map(|track| track.index())
+        .collect();
+
+    let doc_type = input.header().doc_type().to_string();
+
+    let mut outputs = Vec::new();
+    let mut part: Option<(Writer<W>, i64)> = None;
+    let mut next_boundary = 0;
+
+    // The previous part is kept open for the blocks that follow the keyframe starting the current
+    // part in the input, but precede it, such as audio interleaved late.
+    let mut previous: Option<(Writer<W>, i64)> = None;
+    let mut parts = 0;
+
+    let mut prev_uid = None;
+    let mut uid = writer::generate_uid();
+
+    while let Some(mut cluster) = input.next_cluster()? {
+        for block in cluster.blocks() {
+            let block = block?;
+            let timecode = block.absolute_timecode();
+
+            // Only split at keyframes of the video tracks, or of any track if there is no video.
+            let split_point = block.keyframe()
+                && (video_tracks.is_empty() || video_tracks.contains(&block.track()));
+
+            let new_part = match part {
+                Some((ref writer, start)) => split_point && timecode >= start && match *mode {
+                    Mode::Duration(duration) => timecode - start >= (duration / scale) as i64,
+                    Mode::Size(size) => writer.position() >= size,
+                    _ => next_boundary < boundaries.len() && timecode >= boundaries[next_boundary],
+                },
+
+                None => true,
+            };
+
+            if new_part {
+                while next_boundary < boundaries.len() && boundaries[next_boundary] <= timecode {
+                    next_boundary += 1;
+                }
+
+                if let Some((writer, _)) = previous.take() {
+                    outputs.push(writer.finish()?);
+                }
+
+                previous = part.take();
+
+                let next_uid = writer::generate_uid();
+
+                let mut info = segment.clone();
+                info.set_duration(None);
+                info.set_filenames(None, None);
+                info.set_uid(Some(uid.clone()));
+
+                if link {
+                    info.set_prev_uid(prev_uid.take());
+                    info.set_next_uid(Some(next_uid.clone()));
+                } else {
+                    info.set_prev_uid(None);
+                    info.set_next_uid(None);
+                }
+
+                prev_uid = Some(uid);
+                uid = next_uid;
+
+                let writer = Writer::new(output(parts)?, &doc_type, &info, &tracks)?;
+                part = Some((writer, timecode));
+                parts += 1;
+            }
+
+            // Each part is rebased on its first keyframe. Blocks preceding it belong to the
+            // previous part, and are dropped before the first part.
+            match (&mut part, &mut previous) {
+                (&mut Some((ref mut writer, start)), _) if timecode >= start => writer.write_block(block, timecode - start)?,
+                (_, &mut Some((ref mut writer, start))) if timecode >= start => writer.write_block(block, timecode - start)?,
+                _ => {},
+            };
+        }
+    }
+
+    if let Some((writer, _)) = previous {
+        outputs.push(writer.finish()?);
+    }
+
+    if let Some((mut writer, _)) = part {
+        if link {
+            writer.unlink_next();
+        }
+
+        outputs.push(writer.finish()?);
+    }
+
+    Ok(outputs)
+}
@@ -0,0 +1,203 @@
+//! Combine the frames of the plane tracks of a stereoscopic virtual track, such as the views of
+//! each eye, into sets of frames sharing the same timestamp.
+
+use std::collections::VecDeque;
+use std::io::Read;
+
+use elements as el;
+use error::{self, Result};
+use link;
+use reader::Reader;
+use reader::track::{self, PlaneType};
+
+/// Maximum number of incomplete sets kept while waiting for their other planes. Older sets are
+/// returned as they are beyond this number.
+const MAX_INCOMPLETE: usize = 16;
+
+/// Frames of the planes of a virtual track at a given timestamp. A plane is `None` if its track
+/// has no frame at this timestamp.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FrameSet {
+    /// Presentation timestamp in nanoseconds.
+    pub timestamp: i64,
+
+    pub left: Option<Vec<u8>>,
+    pub right: Option<Vec<u8>>,
+    pub background: Option<Vec<u8>>,
+}
+
+impl FrameSet {
+    fn plane_mut(&mut self, kind: PlaneType) -> &mut Option<Vec<u8>> {
+        match kind {
+            PlaneType::Left => &mut self.left,
+            PlaneType::Right => &mut self.right,
+            PlaneType::Background => &mut self.background,
+        }
+    }
+}
+
+/// Groups the frames of plane tracks by timestamp. Frames are pushed in the order of the file,
+/// and a set is ready once all its planes are present, or once a later set is.
+pub struct Combiner {
+    planes: Vec<(u64, PlaneType)>,
+    incomplete: Vec<FrameSet>,
+    ready: VecDeque<FrameSet>,
+}
+
+impl Combiner {
+    /// Create a combiner for `planes`, the track numbers of the planes and their types.
+    pub fn new(planes: Vec<(u64, PlaneType)>) -> Combiner {
+        Combiner {
+            planes: planes,
+            incomplete: Vec::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Create a combiner for the virtual track `track`, whose planes are found in `tracks` by
+    /// their UIDs.
+    pub fn for_track(track: &track::Info, tracks: &[track::Info]) -> Result<Combiner> {
+        let operation = match track.operation() {
+            Some(operation) if !operation.planes.is_empty() => operation,
+            _ => bail!(error::not_found(el::TRACK_COMBINE_PLANES)),
+        };
+
+        let mut planes = Vec::with_capacity(operation.planes.len());
+
+        for plane in &operation.planes {
+            match tracks.iter().find(|track| track.uid() == plane.uid) {
+                Some(track) => planes.push((track.index(), plane.kind)),
+                None => bail!(error::invalid_value(el::TRACK_PLANE_UID, plane.uid)),
+            };
+        }
+
+        Ok(Combiner::new(planes))
+    }
+
+    /// Add a frame of the track number `track`. Frames of other tracks are ignored.
+    pub fn push(&mut self, track: u64, timestamp: i64, frame: Vec<u8>) {
+        let kind = match self.planes.iter().find(|&&(number, _)| number == track) {
+            Some(&(_, kind)) => kind,
+            None => return,
+        };
+
+        // Sets are kept in the order of their timestamps.
+        let index = match self.incomplete.iter().position(|set| set.timestamp >= timestamp) {
+            Some(index) if self.incomplete[index].timestamp == timestamp => index,
+
+            position => {
+                let index = position.unwrap_or(self.incomplete.len());
+
+                self.incomplete.insert(index, FrameSet {
+                    timestamp: timestamp,
+                    ..FrameSet::default()
+                });
+
+                index
+            },
+        };
+
+        *self.incomplete[index].plane_mut(kind) = Some(frame);
+
+        // A complete set makes the previous ones ready, as their missing planes will not come.
+        let complete = {
+            let set = &mut self.incomplete[index];
+            self.planes.iter().all(|&(_, kind)| set.plane_mut(kind).is_some())
+        };
+
+        if complete {
+            self.ready.extend(self.incomplete.drain(..index + 1));
+        } else if self.incomplete.len() > MAX_INCOMPLETE {
+            self.ready.push_back(self.incomplete.remove(0));
+        }
+    }
+
+    /// Make all the remaining sets ready, once there is no more frame to add.
+    pub fn finish(&mut self) {
+        self.ready.extend(self.incomplete.drain(..));
+    }
+
+    /// Return the next set that is ready.
+    pub fn pop(&mut self) -> Option<FrameSet> {
+        self.ready.pop_front()
+    }
+}
+
+/// Iterator over the frame sets of a virtual track, reading the clusters of a segment.
+pub struct FrameSets<R: Read> {
+    reader: Reader<R>,
+    scale: u64,
+    tracks: Vec<track::Info>,
+    combiner: Combiner,
+    done: bool,
+}
+
+impl<R: Read> FrameSets<R> {
+    /// Read the metadata of `reader` before the first cluster, and return the frame sets of the
+    /// virtual track number `track`.
+    pub fn new(mut reader: Reader<R>, track: u64) -> Result<FrameSets<R>> {
+        let (info, tracks) = reader.header_info()?;
+
+        let combiner = match tracks.iter().find(|info| info.index() == track) {
+            Some(info) => Combiner::for_track(info, &tracks)?,
+            None => bail!(error::invalid_value(el::TRACK_NUMBER, track)),
+        };
+
+        Ok(FrameSets {
+            reader: reader,
+            scale: info.timecode_scale(),
+            tracks: tracks,
+            combiner: combiner,
+            done: false,
+        })
+    }
+
+    /// Add the frames of the next cluster to the combiner. Returns `false` at the end of the
+    /// segment.
+    fn fill(&mut self) -> Result<bool> {
+        let mut cluster = match self.reader.next_cluster()? {
+            Some(cluster) => cluster,
+            None => return Ok(false),
+        };
+
+        for block in cluster.blocks() {
+            for packet in link::block_packets(block?, 0, &self.tracks, self.scale)? {
+                self.combiner.push(packet.track, packet.timestamp, packet.data);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for FrameSets<R> {
+    type Item = Result<FrameSet>;
+
+    fn next(&mut self) -> Option<Result<FrameSet>> {
+        loop {
+            if let Some(set) = self.combiner.pop() {
+                return Some(Ok(set));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            match self.fill() {
+                Ok(true) => {},
+
+                Ok(false) => {
+                    self.combiner.finish();
+                    self.done = true;
+                },
+
+                // Stop the iteration after an error.
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                },
+            };
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! ASS and SSA events, for the `S_TEXT/ASS` and `S_TEXT/SSA` codecs.
+
+/// Fields of event lines used when the script header does not define them.
+const DEFAULT_FORMAT: &'static [&'static str] = &[
+    "Layer", "Start", "End", "Style", "Name", "MarginL", "MarginR", "MarginV", "Effect", "Text",
+];
+
+/// Script header, stored in the codec private data.
+pub struct Header {
+    script: String,
+    format: Vec<String>,
+}
+
+impl Header {
+    /// Parse the script header. The event fields are read from the `Format` line of the
+    /// `[Events]` section.
+    pub fn parse(script: &[u8]) -> Header {
+        let script = String::from_utf8_lossy(script).into_owned();
+        let mut format = None;
+        let mut events = false;
+
+        for line in script.lines() {
+            let line = line.trim();
+
+            if line.starts_with('[') {
+                events = line.eq_ignore_ascii_case("[Events]");
+            } else if events && line.starts_with("Format:") {
+                format = Some(line["Format:".len()..].split(',').map(|f| f.trim().to_string()).collect());
+            }
+        }
+
+        Header {
+            format: format.unwrap_or_else(|| DEFAULT_FORMAT.iter().map(|f| f.to_string()).collect()),
+            script: script,
+        }
+    }
+
+    /// Text of the script header.
+    pub fn script(&self) -> &str {
+        self.script.as_str()
+    }
+
+    /// Names of the fields of event lines, including `Start` and `End`.
+    pub fn format(&self) -> &[String] {
+        self.format.as_slice()
+    }
+
+    /// Parse the content of a block. Blocks store the read order of the event followed by the
+    /// fields of the event line, without `Start` and `End`.
+    pub fn parse_event(&self, data: &[u8]) -> Event {
+        let line = String::from_utf8_lossy(data);
+
+        let names: Vec<&String> = self.format.iter()
+            .filter(|name| name.as_str() != "Start" && name.as_str() != "End")
+            .collect();
+
+        // The text is the last field and may contain commas.
+        let mut values = line.splitn(names.len() + 1, ',');
+        let read_order = values.next().and_then(|v| v.trim().parse().ok()).unwrap_or(0);
+
+        let mut fields = Vec::with_capacity(names.len());
+        for (name, value) in names.into_iter().zip(values) {
+            fields.push((name.clone(), value.to_string()));
+        }
+
+        Event {
+            read_order: read_order,
+            fields: fields,
+        }
+    }
+}
+
+/// Dialogue event of an ASS or SSA script.
+pub struct Event {
+    /// Position of the event in the original script.
+    pub read_order: u64,
+
+    /// Fields of the event line, as name and value pairs, in the order of the script format.
+    pub fields: Vec<(String, String)>,
+}
+
+impl Event {
+    /// Return the value of the field `name`.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.iter()
+            .find(|&&(ref n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, ref value)| value.as_str())
+    }
+
+    /// Return the text of the event, including override tags.
+    pub fn text(&self) -> &str {
+        self.field("Text").unwrap_or("")
+    }
+
+    /// Return the text of the event without override tags, with line breaks and hard spaces
+    /// converted.
+    pub fn plain_text(&self) -> String {
+        let mut text = String::new();
+        let mut depth = 0;
+
+        for c in self.text().chars() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                _ if depth == 0 => text.push(c),
+                _ => {},
+            };
+        }
+
+        text.replace("\\N", "\n").replace("\\n", "\n").replace("\\h", " ")
+    }
+}
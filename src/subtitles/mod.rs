@@ -0,0 +1,166 @@
+//! Decode the blocks of subtitle tracks into timed cues.
+
+pub mod ass;
+pub mod pgs;
+pub mod vobsub;
+pub mod webvtt;
+
+use std::io::Read;
+
+use elements as el;
+use error::{self, Result};
+use reader::cluster::Block;
+use reader::track;
+use reader::Reader;
+
+/// Subtitle displayed for some time.
+pub struct Cue {
+    /// Presentation timestamp in nanoseconds.
+    pub start: u64,
+
+    /// Optional. Display duration in nanoseconds.
+    pub duration: Option<u64>,
+
+    pub content: Content,
+}
+
+impl Cue {
+    /// Return the text of the cue without formatting, if it is a text subtitle.
+    pub fn text(&self) -> Option<String> {
+        match self.content {
+            Content::Text(ref text) => Some(text.clone()),
+            Content::Ass(ref event) => Some(event.plain_text()),
+            Content::WebVtt(ref cue) => Some(cue.plain_text()),
+            Content::VobSub(_) | Content::Pgs(_) => None,
+        }
+    }
+}
+
+/// Decoded content of a subtitle block, depending on the codec of the track.
+pub enum Content {
+    /// `S_TEXT/UTF8` plain text.
+    Text(String),
+
+    /// `S_TEXT/ASS` and `S_TEXT/SSA` dialogue event.
+    Ass(ass::Event),
+
+    /// `S_TEXT/WEBVTT` cue.
+    WebVtt(webvtt::Cue),
+
+    /// `S_VOBSUB` bitmap.
+    VobSub(vobsub::Subpicture),
+
+    /// `S_HDMV/PGS` display set.
+    Pgs(pgs::DisplaySet),
+}
+
+enum Codec {
+    Text,
+    Ass(ass::Header),
+    WebVtt,
+    VobSub(vobsub::Index),
+    Pgs,
+}
+
+/// Decodes the blocks of a subtitle track.
+pub struct Decoder {
+    codec: Codec,
+    timecode_scale: u64,
+    default_duration: Option<u64>,
+}
+
+impl Decoder {
+    /// Create a decoder for `track`, in a segment whose timecode scale is `timecode_scale`.
+    pub fn new(track: &track::Info, timecode_scale: u64) -> Result<Decoder> {
+        let private = track.codec_private().unwrap_or(&[]);
+
+        let codec = match track.codec() {
+            "S_TEXT/UTF8" => Codec::Text,
+            "S_TEXT/ASS" | "S_TEXT/SSA" => Codec::Ass(ass::Header::parse(private)),
+            "S_TEXT/WEBVTT" => Codec::WebVtt,
+            "S_VOBSUB" => Codec::VobSub(vobsub::Index::parse(private)),
+            "S_HDMV/PGS" => Codec::Pgs,
+            codec => bail!(error::invalid_value(el::CODEC_ID, codec)),
+        };
+
+        Ok(Decoder {
+            codec: codec,
+            timecode_scale: timecode_scale,
+            default_duration: track.default_duration(),
+        })
+    }
+
+    /// Return the script header, for ASS and SSA tracks.
+    pub fn ass_header(&self) -> Option<&ass::Header> {
+        match self.codec {
+            Codec::Ass(ref header) => Some(header),
+            _ => None,
+        }
+    }
+
+    /// Return the subpicture index, for VobSub tracks.
+    pub fn vobsub_index(&self) -> Option<&vobsub::Index> {
+        match self.codec {
+            Codec::VobSub(ref index) => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Decode a block of the track. The duration of the cue is the one of the block, or the
+    /// default duration of the track.
+    pub fn decode(&self, block: Block) -> Result<Cue> {
+        let start = block.absolute_timecode().max(0) as u64 * self.timecode_scale;
+        let mut duration = block.duration().map(|d| d * self.timecode_scale).or(self.default_duration);
+
+        let additional = block.additions().first().map(|&(_, ref data)| data.clone());
+        let data = block.data();
+
+        let content = match self.codec {
+            Codec::Text => Content::Text(String::from_utf8_lossy(&data).into_owned()),
+            Codec::Ass(ref header) => Content::Ass(header.parse_event(&data)),
+            Codec::WebVtt => Content::WebVtt(webvtt::Cue::parse(&data, additional.as_ref().map(|a| a.as_slice()))),
+
+            Codec::VobSub(ref index) => {
+                let sub = vobsub::decode(&data, index)?;
+
+                // Subpictures may carry their own display duration.
+                if duration.is_none() {
+                    duration = sub.stop.map(|stop| stop.saturating_sub(sub.start));
+                }
+
+                Content::VobSub(sub)
+            },
+
+            Codec::Pgs => Content::Pgs(pgs::decode(&data)?),
+        };
+
+        Ok(Cue {
+            start: start,
+            duration: duration,
+            content: content,
+        })
+    }
+}
+
+/// Decode all the blocks of the subtitle track number `track` of `input`.
+pub fn read<R: Read>(input: &mut Reader<R>, track: u64) -> Result<Vec<Cue>> {
+    let (segment, tracks) = input.header_info()?;
+
+    let info = tracks.iter().find(|info| info.index() == track)
+        .ok_or(error::invalid_value(el::TRACK_NUMBER, track))?;
+
+    let decoder = Decoder::new(info, segment.timecode_scale())?;
+    let mut cues = Vec::new();
+
+    while let Some(mut cluster) = input.next_cluster()? {
+        for block in cluster.blocks() {
+            let block = block?;
+
+            if block.track() == track {
+                cues.push(decoder.decode(block)?);
+            }
+        }
+    }
+
+    Ok(cues)
+}
@@ -0,0 +1,250 @@
+//! Presentation graphic streams, for the `S_HDMV/PGS` codec.
+
+use elements as el;
+use error::{self, Result};
+
+const SEGMENT_PALETTE: u8 = 0x14;
+const SEGMENT_OBJECT: u8 = 0x15;
+const SEGMENT_COMPOSITION: u8 = 0x16;
+const SEGMENT_WINDOW: u8 = 0x17;
+const SEGMENT_END: u8 = 0x80;
+
+/// Set of segments stored in a block, describing what is displayed from its timestamp on.
+pub struct DisplaySet {
+    /// Dimensions of the video the objects are displayed over.
+    pub width: u64,
+    pub height: u64,
+
+    /// Composition state: 0x00 for a normal update, 0x40 for an acquisition point and 0x80 for
+    /// an epoch start.
+    pub state: u8,
+
+    /// Palette used by the displayed objects.
+    pub palette_id: u8,
+
+    /// Objects displayed by this set, which is empty to clear the screen.
+    pub compositions: Vec<Composition>,
+
+    pub palettes: Vec<Palette>,
+    pub objects: Vec<Object>,
+}
+
+/// Placement of an object on the screen.
+pub struct Composition {
+    pub object_id: u16,
+    pub window_id: u8,
+    pub forced: bool,
+    pub x: u64,
+    pub y: u64,
+
+    /// Optional. Part of the object to display, as x, y, width and height.
+    pub crop: Option<(u64, u64, u64, u64)>,
+}
+
+/// Palette definition, with YCrCb colours and opacities.
+pub struct Palette {
+    pub id: u8,
+    pub version: u8,
+
+    /// Entries as index, Y, Cr, Cb and alpha values.
+    pub entries: Vec<(u8, u8, u8, u8, u8)>,
+}
+
+/// Decoded bitmap object.
+pub struct Object {
+    pub id: u16,
+    pub version: u8,
+    pub width: u64,
+    pub height: u64,
+
+    /// One byte per pixel, row by row, indexing the palette entries.
+    pub pixels: Vec<u8>,
+}
+
+/// Parse the segments of a display set.
+pub fn decode(data: &[u8]) -> Result<DisplaySet> {
+    let mut set = DisplaySet {
+        width: 0,
+        height: 0,
+        state: 0,
+        palette_id: 0,
+        compositions: Vec::new(),
+        palettes: Vec::new(),
+        objects: Vec::new(),
+    };
+
+    // Objects may be split in several segments: their run-length encoded data is accumulated
+    // until the last one.
+    let mut pending: Option<(u16, u8, u64, u64, Vec<u8>)> = None;
+    let mut pos = 0;
+
+    while pos + 3 <= data.len() {
+        let kind = data[pos];
+        let len = read_u16(data, pos + 1)? as usize;
+        pos += 3;
+
+        if pos + len > data.len() {
+            bail!(error::unexpected_eof());
+        }
+
+        let segment = &data[pos..pos + len];
+        pos += len;
+
+        match kind {
+            SEGMENT_COMPOSITION => {
+                if segment.len() < 11 {
+                    bail!(error::unexpected_eof());
+                }
+
+                set.width = read_u16(segment, 0)?;
+                set.height = read_u16(segment, 2)?;
+                set.state = segment[7];
+                set.palette_id = segment[9];
+
+                let mut p = 11;
+                for _ in 0..segment[10] {
+                    if p + 8 > segment.len() {
+                        bail!(error::unexpected_eof());
+                    }
+
+                    let cropped = segment[p + 3] & 0x80 != 0;
+
+                    let mut composition = Composition {
+                        object_id: read_u16(segment, p)? as u16,
+                        window_id: segment[p + 2],
+                        forced: segment[p + 3] & 0x40 != 0,
+                        x: read_u16(segment, p + 4)?,
+                        y: read_u16(segment, p + 6)?,
+                        crop: None,
+                    };
+
+                    p += 8;
+
+                    if cropped {
+                        composition.crop = Some((
+                            read_u16(segment, p)?,
+                            read_u16(segment, p + 2)?,
+                            read_u16(segment, p + 4)?,
+                            read_u16(segment, p + 6)?,
+                        ));
+
+                        p += 8;
+                    }
+
+                    set.compositions.push(composition);
+                }
+            },
+
+            SEGMENT_PALETTE => {
+                if segment.len() < 2 {
+                    bail!(error::unexpected_eof());
+                }
+
+                set.palettes.push(Palette {
+                    id: segment[0],
+                    version: segment[1],
+                    entries: segment[2..].chunks(5)
+                        .filter(|e| e.len() == 5)
+                        .map(|e| (e[0], e[1], e[2], e[3], e[4]))
+                        .collect(),
+                });
+            },
+
+            SEGMENT_OBJECT => {
+                if segment.len() < 4 {
+                    bail!(error::unexpected_eof());
+                }
+
+                let id = read_u16(segment, 0)? as u16;
+                let flags = segment[3];
+
+                if flags & 0x80 != 0 {
+                    // First segment: data length, then dimensions.
+                    if segment.len() < 11 {
+                        bail!(error::unexpected_eof());
+                    }
+
+                    let width = read_u16(segment, 7)?;
+                    let height = read_u16(segment, 9)?;
+
+                    pending = Some((id, segment[2], width, height, segment[11..].to_vec()));
+                } else if let Some((pending_id, _, _, _, ref mut rle)) = pending {
+                    if pending_id == id {
+                        rle.extend_from_slice(&segment[4..]);
+                    }
+                }
+
+                if flags & 0x40 != 0 {
+                    if let Some((id, version, width, height, rle)) = pending.take() {
+                        set.objects.push(Object {
+                            id: id,
+                            version: version,
+                            width: width,
+                            height: height,
+                            pixels: decode_rle(&rle, width as usize, height as usize)?,
+                        });
+                    }
+                }
+            },
+
+            SEGMENT_WINDOW | SEGMENT_END => {},
+
+            wtf => bail!(error::invalid_value(el::BLOCK, format!("PGS segment {:#x}", wtf))),
+        };
+    }
+
+    Ok(set)
+}
+
+/// Decode a run-length encoded bitmap, whose lines are terminated by two zero bytes.
+fn decode_rle(data: &[u8], width: usize, height: usize) -> Result<Vec<u8>> {
+    let mut pixels = vec![0u8; width * height];
+    let (mut x, mut y) = (0, 0);
+    let mut pos = 0;
+
+    let mut next = || -> Result<u8> {
+        let byte = *data.get(pos).ok_or(error::unexpected_eof())?;
+        pos += 1;
+        Ok(byte)
+    };
+
+    while y < height {
+        let (run, colour) = match next()? {
+            0 => match next()? {
+                0 => {
+                    x = 0;
+                    y += 1;
+                    continue;
+                },
+
+                flags => {
+                    let mut run = (flags & 0x3F) as usize;
+                    if flags & 0x40 != 0 {
+                        run = (run << 8) | next()? as usize;
+                    }
+
+                    (run, if flags & 0x80 != 0 { next()? } else { 0 })
+                },
+            },
+
+            colour => (1, colour),
+        };
+
+        let run = run.min(width.saturating_sub(x));
+        for pixel in &mut pixels[y * width + x..y * width + x + run] {
+            *pixel = colour;
+        }
+
+        x += run;
+    }
+
+    Ok(pixels)
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<u64> {
+    if pos + 2 > data.len() {
+        bail!(error::unexpected_eof());
+    }
+
+    Ok((data[pos] as u64) << 8 | data[pos + 1] as u64)
+}
@@ -0,0 +1,229 @@
+//! DVD subpictures, for the `S_VOBSUB` codec.
+
+use elements as el;
+use error::{self, Result};
+
+/// Parsed `.idx` file, stored in the codec private data.
+pub struct Index {
+    /// Dimensions of the video the subpictures are displayed over.
+    pub width: u64,
+    pub height: u64,
+
+    /// The 16 RGB colours that subpictures pick their four colours from.
+    pub palette: Vec<u32>,
+}
+
+impl Index {
+    /// Parse the `size` and `palette` lines of an `.idx` file.
+    pub fn parse(data: &[u8]) -> Index {
+        let mut index = Index {
+            width: 720,
+            height: 480,
+            palette: vec![0; 16],
+        };
+
+        for line in String::from_utf8_lossy(data).lines() {
+            let mut parts = line.splitn(2, ':');
+
+            match (parts.next().map(|key| key.trim()), parts.next()) {
+                (Some("size"), Some(size)) => {
+                    let mut dims = size.trim().splitn(2, 'x').map(|dim| dim.trim().parse().ok());
+
+                    if let (Some(Some(width)), Some(Some(height))) = (dims.next(), dims.next()) {
+                        index.width = width;
+                        index.height = height;
+                    }
+                },
+
+                (Some("palette"), Some(palette)) => {
+                    index.palette = palette.split(',')
+                        .map(|colour| u32::from_str_radix(colour.trim(), 16).unwrap_or(0))
+                        .collect();
+
+                    index.palette.resize(16, 0);
+                },
+
+                _ => {},
+            };
+        }
+
+        index
+    }
+}
+
+/// Decoded subpicture.
+pub struct Subpicture {
+    /// Delays in nanoseconds from the timestamp of the block to the start and end of the display.
+    pub start: u64,
+    pub stop: Option<u64>,
+
+    /// Whether the subpicture must be displayed regardless of the user preferences.
+    pub forced: bool,
+
+    /// Position and dimensions of the bitmap in the video frame.
+    pub x: u64,
+    pub y: u64,
+    pub width: u64,
+    pub height: u64,
+
+    /// One byte per pixel, row by row, indexing `colours` and `alpha`.
+    pub pixels: Vec<u8>,
+
+    /// RGB colours and opacities from 0 (transparent) to 15 of the four pixel values.
+    pub colours: [u32; 4],
+    pub alpha: [u8; 4],
+}
+
+/// Decode a subpicture unit, using the palette of `index`.
+pub fn decode(data: &[u8], index: &Index) -> Result<Subpicture> {
+    if data.len() < 4 {
+        bail!(error::unexpected_eof());
+    }
+
+    let mut sub = Subpicture {
+        start: 0,
+        stop: None,
+        forced: false,
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+        pixels: Vec::new(),
+        colours: [0; 4],
+        alpha: [0; 4],
+    };
+
+    let mut fields = (0, 0);
+    let mut pos = read_u16(data, 2)?;
+
+    // Control sequences are chained in order, the last one pointing to itself.
+    loop {
+        let delay = read_u16(data, pos)? as u64 * 1024 * 1000000000 / 90000;
+        let next = read_u16(data, pos + 2)?;
+        let mut cmd = pos + 4;
+
+        loop {
+            let byte = *data.get(cmd).ok_or(error::unexpected_eof())?;
+            cmd += 1;
+
+            match byte {
+                0x00 => sub.forced = true,
+                0x01 => sub.start = delay,
+                0x02 => sub.stop = Some(delay),
+
+                // Nibbles of the four values, from the last to the first.
+                0x03 => {
+                    let value = read_u16(data, cmd)?;
+                    for i in 0..4 {
+                        sub.colours[i] = index.palette[(value >> (4 * i)) & 0x0F];
+                    }
+
+                    cmd += 2;
+                },
+
+                0x04 => {
+                    let value = read_u16(data, cmd)?;
+                    for i in 0..4 {
+                        sub.alpha[i] = ((value >> (4 * i)) & 0x0F) as u8;
+                    }
+
+                    cmd += 2;
+                },
+
+                0x05 => {
+                    if cmd + 6 > data.len() {
+                        bail!(error::unexpected_eof());
+                    }
+
+                    let c = &data[cmd..cmd + 6];
+                    let x1 = (c[0] as u64) << 4 | (c[1] as u64) >> 4;
+                    let x2 = (c[1] as u64 & 0x0F) << 8 | c[2] as u64;
+                    let y1 = (c[3] as u64) << 4 | (c[4] as u64) >> 4;
+                    let y2 = (c[4] as u64 & 0x0F) << 8 | c[5] as u64;
+
+                    sub.x = x1;
+                    sub.y = y1;
+                    sub.width = (x2 + 1).saturating_sub(x1);
+                    sub.height = (y2 + 1).saturating_sub(y1);
+
+                    cmd += 6;
+                },
+
+                0x06 => {
+                    fields = (read_u16(data, cmd)?, read_u16(data, cmd + 2)?);
+                    cmd += 4;
+                },
+
+                0xFF => break,
+
+                wtf => bail!(error::invalid_value(el::BLOCK, format!("VobSub command {:#x}", wtf))),
+            };
+        }
+
+        if next <= pos {
+            break;
+        }
+
+        pos = next;
+    }
+
+    sub.pixels = decode_rle(data, fields, sub.width as usize, sub.height as usize)?;
+    Ok(sub)
+}
+
+/// Decode the interlaced run-length encoded bitmap: even lines are read from the first field
+/// offset and odd lines from the second one.
+fn decode_rle(data: &[u8], fields: (usize, usize), width: usize, height: usize) -> Result<Vec<u8>> {
+    let mut pixels = vec![0u8; width * height];
+    let mut nibbles = [fields.0 * 2, fields.1 * 2];
+
+    for y in 0..height {
+        let pos = &mut nibbles[y % 2];
+        let mut x = 0;
+
+        while x < width {
+            // Runs are coded on 1 to 4 nibbles, depending on their leading zeros.
+            let mut value = read_nibble(data, pos)? as usize;
+            for threshold in &[0x04, 0x10, 0x40] {
+                if value >= *threshold {
+                    break;
+                }
+
+                value = (value << 4) | read_nibble(data, pos)? as usize;
+            }
+
+            // A run length of 0 fills the rest of the line.
+            let run = match value >> 2 {
+                0 => width - x,
+                run => run.min(width - x),
+            };
+
+            for pixel in &mut pixels[y * width + x..y * width + x + run] {
+                *pixel = (value & 0x03) as u8;
+            }
+
+            x += run;
+        }
+
+        // Lines are byte aligned.
+        *pos += *pos % 2;
+    }
+
+    Ok(pixels)
+}
+
+fn read_nibble(data: &[u8], pos: &mut usize) -> Result<u8> {
+    let byte = *data.get(*pos / 2).ok_or(error::unexpected_eof())?;
+    let nibble = if *pos % 2 == 0 { byte >> 4 } else { byte & 0x0F };
+    *pos += 1;
+
+    Ok(nibble)
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<usize> {
+    if pos + 2 > data.len() {
+        bail!(error::unexpected_eof());
+    }
+
+    Ok((data[pos] as usize) << 8 | data[pos + 1] as usize)
+}
@@ -0,0 +1,61 @@
+//! WebVTT cues, for the `S_TEXT/WEBVTT` codec.
+
+/// WebVTT cue, whose settings and preceding comments are stored in the block additions.
+pub struct Cue {
+    pub text: String,
+
+    /// Optional. Cue settings list, e.g. `align:start line:0`.
+    pub settings: Option<String>,
+
+    /// Comments preceding the cue in the original file, without the `NOTE` keyword.
+    pub comments: Vec<String>,
+}
+
+impl Cue {
+    /// Build a cue from the content of a block and of its block additional data, which stores
+    /// the settings list on its first line and the comments on the following ones.
+    pub fn parse(data: &[u8], additional: Option<&[u8]>) -> Cue {
+        let mut cue = Cue {
+            text: String::from_utf8_lossy(data).into_owned(),
+            settings: None,
+            comments: Vec::new(),
+        };
+
+        if let Some(additional) = additional {
+            let additional = String::from_utf8_lossy(additional);
+            let mut parts = additional.splitn(2, '\n');
+
+            cue.settings = parts.next()
+                .map(|settings| settings.trim().to_string())
+                .and_then(|settings| if settings.is_empty() { None } else { Some(settings) });
+
+            if let Some(comments) = parts.next() {
+                cue.comments = comments.split("\n\n")
+                    .map(|comment| comment.trim())
+                    .map(|comment| if comment.starts_with("NOTE") { comment[4..].trim() } else { comment })
+                    .map(|comment| comment.to_string())
+                    .filter(|comment| !comment.is_empty())
+                    .collect();
+            }
+        }
+
+        cue
+    }
+
+    /// Return the text of the cue without markup tags, with entities decoded.
+    pub fn plain_text(&self) -> String {
+        let mut text = String::new();
+        let mut tag = false;
+
+        for c in self.text.chars() {
+            match c {
+                '<' => tag = true,
+                '>' if tag => tag = false,
+                _ if !tag => text.push(c),
+                _ => {},
+            };
+        }
+
+        text.replace("&lt;", "<").replace("&gt;", ">").replace("&nbsp;", " ").replace("&amp;", "&")
+    }
+}
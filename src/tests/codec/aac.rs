@@ -0,0 +1,57 @@
+//! AAC configuration tests.
+
+use codec::{aac, CodecConfig};
+
+#[test]
+fn parse_lc() {
+    let config = aac::parse_config(&[0x12, 0x10]).unwrap();
+
+    assert_eq!(config.object_type, 2);
+    assert_eq!(config.sample_rate, 44100);
+    assert_eq!(config.sampling_frequency_index, Some(4));
+    assert_eq!(config.channels(), Some(2));
+    assert!(!config.sbr);
+    assert_eq!(config.output_sample_rate(), 44100);
+}
+
+#[test]
+fn parse_he_aac() {
+    // Explicit hierarchical signaling.
+    let config = aac::parse_config(&[0x2B, 0x11, 0x88]).unwrap();
+
+    assert_eq!(config.object_type, 2);
+    assert_eq!(config.sample_rate, 24000);
+    assert!(config.sbr && !config.ps);
+    assert_eq!(config.output_sample_rate(), 48000);
+
+    // Explicit backward compatible signaling.
+    let config = aac::parse_config(&[0x13, 0x10, 0x56, 0xE5, 0x98]).unwrap();
+
+    assert_eq!(config.object_type, 2);
+    assert_eq!(config.sample_rate, 24000);
+    assert!(config.sbr);
+    assert_eq!(config.output_sample_rate(), 48000);
+    assert_eq!(config.channels(), Some(2));
+}
+
+#[test]
+fn legacy_codec_ids() {
+    match CodecConfig::parse("A_AAC/MPEG4/LC", Some(&[0x12, 0x10])).unwrap() {
+        Some(CodecConfig::Aac(config)) => assert_eq!((config.object_type, config.sample_rate), (2, 44100)),
+        config => panic!("unexpected configuration: {:?}", config),
+    };
+
+    let config = aac::legacy_config("A_AAC/MPEG2/LC/SBR", 24000, 2).unwrap();
+
+    assert_eq!(config.object_type, 2);
+    assert_eq!(config.sampling_frequency_index, Some(6));
+    assert!(config.sbr);
+    assert_eq!(config.output_sample_rate(), 48000);
+    assert_eq!(config.channels(), Some(2));
+
+    assert_eq!(aac::legacy_config("A_AAC/MPEG4/MAIN", 48000, 6).unwrap().object_type, 1);
+    assert_eq!(aac::legacy_config("A_AAC/MPEG2/SSR", 48000, 2).unwrap().object_type, 3);
+    assert_eq!(aac::legacy_config("A_AAC/MPEG4/LTP", 48000, 2).unwrap().object_type, 4);
+    assert_eq!(aac::legacy_config("A_AAC", 48000, 2), None);
+    assert_eq!(aac::legacy_config("A_AC3", 48000, 2), None);
+}
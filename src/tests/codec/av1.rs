@@ -0,0 +1,52 @@
+//! AV1 configuration tests.
+
+use codec::av1;
+
+// Main profile, level 4.0, 1920x1080, 8 bits 4:2:0 with BT.709 colours.
+const SEQUENCE_HEADER: &'static [u8] = &[
+    0x00, 0x00, 0x00, 0x42, 0xAB, 0xBF, 0xC3, 0x73, 0xFF, 0xE6, 0x40, 0x40, 0x40, 0x41,
+];
+
+// The same sequence, as a still picture with a reduced header.
+const STILL_PICTURE_HEADER: &'static [u8] = &[0x1A, 0x2A, 0xBB, 0xFC, 0x37, 0x6C, 0x80, 0x80, 0x80, 0x82];
+
+#[test]
+fn parse_config() {
+    let mut data = vec![0x81, 0x08, 0x0C, 0x13, 0x0A, SEQUENCE_HEADER.len() as u8];
+    data.extend_from_slice(SEQUENCE_HEADER);
+
+    let config = av1::parse_config(&data).unwrap();
+
+    assert_eq!((config.profile, config.level, config.tier), (0, 8, false));
+    assert_eq!(config.bit_depth(), 8);
+    assert!(!config.monochrome);
+    assert!(config.subsampling_x && config.subsampling_y);
+    assert_eq!(config.initial_presentation_delay, Some(4));
+    assert_eq!(config.config_obus, data[4..].to_vec());
+
+    let header = config.sequence_header.unwrap();
+
+    assert_eq!((header.profile, header.level, header.tier), (0, 8, false));
+    assert!(!header.still_picture);
+    assert_eq!((header.max_width, header.max_height), (1920, 1080));
+    assert_eq!(header.bit_depth, 8);
+    assert!(!header.monochrome);
+    assert!(header.subsampling_x && header.subsampling_y);
+    assert_eq!((header.colour.primaries, header.colour.transfer_characteristics, header.colour.matrix_coefficients), (1, 1, 1));
+    assert!(!header.colour.full_range);
+
+    // A configuration record without OBUs, then with an OBU larger than the data.
+    assert_eq!(av1::parse_config(&data[..4]).unwrap().sequence_header, None);
+    assert!(av1::parse_config(&data[..10]).is_err());
+    assert!(av1::parse_config(&[0x01, 0x08, 0x0C, 0x00]).is_err());
+}
+
+#[test]
+fn parse_still_picture() {
+    let header = av1::parse_sequence_header(STILL_PICTURE_HEADER).unwrap();
+
+    assert!(header.still_picture);
+    assert_eq!((header.level, header.tier), (8, false));
+    assert_eq!((header.max_width, header.max_height), (1920, 1080));
+    assert_eq!(header.colour.primaries, 1);
+}
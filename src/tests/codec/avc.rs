@@ -0,0 +1,55 @@
+//! AVC configuration tests.
+
+use codec::avc;
+use elements as el;
+use error::ErrorKind;
+
+// High profile, level 4.0, 1920x1088 cropped to 1080 lines.
+const SPS: &'static [u8] = &[
+    0x67, 0x64, 0x00, 0x28, 0xAC, 0xD9, 0x40, 0x78, 0x02, 0x27, 0xE5, 0xC0, 0x44, 0x00, 0x00, 0x03,
+    0x00, 0x04, 0x00, 0x00, 0x03, 0x00, 0xF0, 0x3C, 0x60, 0xC6, 0x58,
+];
+
+const PPS: &'static [u8] = &[0x68, 0xEB, 0xE3, 0xCB, 0x22, 0xC0];
+
+#[test]
+fn parse_sps() {
+    let sps = avc::parse_sps(SPS).unwrap();
+
+    assert_eq!(sps.profile, 100);
+    assert_eq!(sps.level, 40);
+    assert_eq!(sps.chroma_format, 1);
+    assert_eq!(sps.bit_depth_luma, 8);
+    assert_eq!((sps.width, sps.height), (1920, 1080));
+}
+
+#[test]
+fn parse_config() {
+    let mut data = vec![0x01, 0x64, 0x00, 0x28, 0xFF, 0xE1, 0x00, SPS.len() as u8];
+    data.extend_from_slice(SPS);
+    data.extend_from_slice(&[0x01, 0x00, PPS.len() as u8]);
+    data.extend_from_slice(PPS);
+
+    let config = avc::parse_config(&data).unwrap();
+
+    assert_eq!(config.profile, 100);
+    assert_eq!(config.nal_length_size, 4);
+    assert_eq!(config.sps, vec![SPS.to_vec()]);
+    assert_eq!(config.pps, vec![PPS.to_vec()]);
+    assert_eq!(config.parse_sps().unwrap().height, 1080);
+
+    assert!(avc::parse_config(&data[..10]).is_err());
+}
+
+#[test]
+fn invalid_sps() {
+    // High profile with a luma bit depth of 15 bits, then with a chroma format of 4.
+    for sps in &[&[0x67, 0x64, 0x00, 0x28, 0xA1, 0x1F][..], &[0x67, 0x64, 0x00, 0x28, 0x97][..]] {
+        let err = avc::parse_sps(sps).unwrap_err();
+
+        match *err.base_kind() {
+            ErrorKind::InvalidElementValue(id, _) => assert_eq!(id, el::CODEC_PRIVATE),
+            _ => panic!("unexpected error: {}", err),
+        };
+    }
+}
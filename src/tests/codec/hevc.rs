@@ -0,0 +1,88 @@
+//! HEVC configuration tests.
+
+use codec::hevc;
+use elements as el;
+use error::ErrorKind;
+
+// NAL unit header and general profile of a Main profile SPS, up to its level.
+const PREFIX: &'static [u8] = &[
+    0x42, 0x01, 0x01, 0x01, 0x60, 0x00, 0x00, 0x03, 0x00, 0x90, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03,
+    0x00,
+];
+
+// Level 4.0, 4:2:0, 1920x1088 cropped to 1080 lines, 8 bits, one short-term reference picture set.
+const SPS: &'static [u8] = &[
+    0x78, 0xA0, 0x03, 0xC0, 0x80, 0x11, 0x07, 0xCB, 0x96, 0x57, 0xB9, 0x12, 0x64, 0xBB, 0x40,
+];
+
+/// Concatenate the common prefix and the rest of an SPS.
+fn sps(rest: &[u8]) -> Vec<u8> {
+    let mut data = PREFIX.to_vec();
+    data.extend_from_slice(rest);
+
+    data
+}
+
+#[test]
+fn parse_sps() {
+    let sps = hevc::parse_sps(&sps(SPS)).unwrap();
+
+    assert_eq!(sps.profile_space, 0);
+    assert!(!sps.tier);
+    assert_eq!(sps.profile, 1);
+    assert_eq!(sps.level, 120);
+    assert_eq!(sps.chroma_format, 1);
+    assert_eq!((sps.bit_depth_luma, sps.bit_depth_chroma), (8, 8));
+    assert_eq!((sps.width, sps.height), (1920, 1080));
+    assert_eq!(sps.colour, None);
+}
+
+#[test]
+fn parse_config() {
+    let sps = sps(SPS);
+
+    let mut data = vec![
+        0x01, 0x01, 0x60, 0x00, 0x00, 0x00, 0x90, 0x00, 0x00, 0x00, 0x00, 0x00, 0x78, 0xF0, 0x00,
+        0xFC, 0xFD, 0xF8, 0xF8, 0x00, 0x00, 0x0F, 0x01,
+    ];
+
+    data.extend_from_slice(&[0xA1, 0x00, 0x01, 0x00, sps.len() as u8]);
+    data.extend_from_slice(&sps);
+
+    let config = hevc::parse_config(&data).unwrap();
+
+    assert_eq!(config.profile, 1);
+    assert_eq!(config.level, 120);
+    assert_eq!(config.chroma_format, 1);
+    assert_eq!((config.bit_depth_luma, config.bit_depth_chroma), (8, 8));
+    assert_eq!(config.nal_length_size, 4);
+    assert_eq!(config.nal_units, vec![(hevc::NAL_SPS, sps.clone())]);
+    assert!(config.units(hevc::NAL_PPS).is_empty());
+    assert_eq!(config.parse_sps().unwrap().height, 1080);
+
+    // Truncated in the middle of the NAL unit.
+    assert!(hevc::parse_config(&data[..30]).is_err());
+}
+
+#[test]
+fn invalid_sps() {
+    // A chroma format of 4, a luma bit depth of 258 bits, then 2^32 short-term reference picture
+    // sets.
+    let invalid = [
+        sps(&[0x78, 0x96]),
+        sps(&[0x78, 0xA0, 0x03, 0xC0, 0x80, 0x11, 0x07, 0xCA, 0x03, 0xEE, 0x58]),
+        sps(&[
+            0x78, 0xA0, 0x03, 0xC0, 0x80, 0x11, 0x07, 0xCB, 0x96, 0x57, 0xB9, 0x12, 0x60, 0x00, 0x00,
+            0x03, 0x00, 0x08, 0x00, 0x00, 0x03, 0x00, 0x0C,
+        ]),
+    ];
+
+    for sps in &invalid {
+        let err = hevc::parse_sps(sps).unwrap_err();
+
+        match *err.base_kind() {
+            ErrorKind::InvalidElementValue(id, _) => assert_eq!(id, el::CODEC_PRIVATE),
+            _ => panic!("unexpected error: {}", err),
+        };
+    }
+}
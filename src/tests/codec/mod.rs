@@ -0,0 +1,8 @@
+//! Codec-private data tests.
+
+mod aac;
+mod av1;
+mod avc;
+mod hevc;
+mod vp8;
+mod vp9;
@@ -0,0 +1,19 @@
+//! VP8 frame header tests.
+
+use codec::vp8;
+
+#[test]
+fn parse_frame_header() {
+    // Version 0 keyframe, 640x480 with the width upscaled by 5/4.
+    let frame = [0x10, 0x02, 0x00, 0x9D, 0x01, 0x2A, 0x80, 0x42, 0xE0, 0x01, 0x00];
+    let header = vp8::parse_frame_header(&frame).unwrap().unwrap();
+
+    assert_eq!(header.version, 0);
+    assert_eq!((header.width, header.height), (640, 480));
+    assert_eq!((header.horizontal_scale, header.vertical_scale), (1, 0));
+
+    // Inter frame, truncated keyframe and invalid start code.
+    assert!(vp8::parse_frame_header(&[0x11, 0x02, 0x00]).unwrap().is_none());
+    assert!(vp8::parse_frame_header(&frame[..8]).is_err());
+    assert!(vp8::parse_frame_header(&[0x10, 0x02, 0x00, 0x9D, 0x01, 0x2B, 0x80, 0x02, 0xE0, 0x01]).is_err());
+}
@@ -0,0 +1,32 @@
+//! VP9 configuration tests.
+
+use codec::vp9;
+
+#[test]
+fn parse_features() {
+    let features = vp9::parse_features(&[0x01, 0x01, 0x02, 0x03, 0x01, 0x0A, 0x04, 0x01, 0x01]).unwrap();
+
+    assert_eq!(features.profile, Some(2));
+    assert_eq!(features.level, None);
+    assert_eq!(features.bit_depth, Some(10));
+    assert_eq!(features.chroma_subsampling, Some(1));
+
+    assert!(vp9::parse_features(&[0x01, 0x02, 0x00]).is_err());
+}
+
+#[test]
+fn parse_frame_header() {
+    // Profile 0 keyframe, BT.709, 1920x1080.
+    let frame = [0x82, 0x49, 0x83, 0x42, 0x40, 0x77, 0xF0, 0x43, 0x70];
+    let header = vp9::parse_frame_header(&frame).unwrap().unwrap();
+
+    assert_eq!(header.profile, 0);
+    assert_eq!(header.bit_depth, 8);
+    assert_eq!(header.colour_space, 2);
+    assert!(!header.full_range);
+    assert!(header.subsampling_x && header.subsampling_y);
+    assert_eq!((header.width, header.height), (1920, 1080));
+
+    // Inter frame.
+    assert!(vp9::parse_frame_header(&[0x86, 0x00]).unwrap().is_none());
+}
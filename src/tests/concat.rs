@@ -0,0 +1,91 @@
+//! Concatenation tests.
+
+use std::io::Cursor;
+
+use concat;
+use elements as el;
+use error::ErrorKind;
+use reader::Reader;
+use tests::fixture;
+use writer::element;
+
+fn input(duration: f64, track: Vec<u8>) -> Vec<u8> {
+    fixture::file(vec![
+        fixture::info(Some(duration)),
+        element::master(el::TRACKS, vec![track]),
+        fixture::cluster(0, vec![
+            fixture::simple_block(1, 0, true, &[1]),
+            fixture::simple_block(1, 1000, true, &[2]),
+        ]),
+    ])
+}
+
+fn concat(inputs: &[Vec<u8>]) -> ::error::Result<Vec<u8>> {
+    let mut readers: Vec<_> = inputs.iter().map(|data| Reader::new(&data[..]).unwrap()).collect();
+    concat::concat(&mut readers, Cursor::new(Vec::new())).map(|output| output.into_inner())
+}
+
+#[test]
+fn durations() {
+    // The trailing second without blocks of the first input is kept.
+    let output = concat(&[input(2000.0, fixture::video_track(1)), input(3000.0, fixture::video_track(1))]).unwrap();
+
+    let timecodes: Vec<i64> = fixture::blocks(&output).iter().map(|block| block.1).collect();
+    assert_eq!(timecodes, vec![0, 1000, 2000, 3000]);
+
+    let (info, _) = Reader::new(&output[..]).unwrap().header_info().unwrap();
+    assert_eq!(info.duration(), Some(5000.0));
+}
+
+#[test]
+fn incompatible_tracks() {
+    let wide = element::master(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, 1),
+        element::uint(el::TRACK_UID, 1),
+        element::uint(el::TRACK_TYPE, 1),
+        element::utf8(el::CODEC_ID, "V_VP9"),
+        element::master(el::VIDEO, vec![
+            element::uint(el::PIXEL_WIDTH, 1280),
+            element::uint(el::PIXEL_HEIGHT, 480),
+        ]),
+    ]);
+
+    let err = concat(&[input(2000.0, fixture::video_track(1)), input(2000.0, wide)]).unwrap_err();
+
+    match *err.kind() {
+        ErrorKind::IncompatibleTrack(input, track, ref field) => {
+            assert_eq!((input, track, field.as_str()), (1, 1, "PixelWidth"));
+        },
+
+        _ => panic!("unexpected error: {}", err),
+    };
+
+    let err = concat(&[input(2000.0, fixture::video_track(1)), input(2000.0, fixture::audio_track(1))]).unwrap_err();
+
+    match *err.kind() {
+        ErrorKind::IncompatibleTrack(_, _, ref field) => assert_eq!(field, "TrackType"),
+        _ => panic!("unexpected error: {}", err),
+    };
+
+    let err = concat(&[input(2000.0, fixture::video_track(1)), input(2000.0, fixture::video_track(2))]).unwrap_err();
+
+    match *err.kind() {
+        ErrorKind::IncompatibleTrack(_, track, ref field) => assert_eq!((track, field.as_str()), (1, "TrackNumber")),
+        _ => panic!("unexpected error: {}", err),
+    };
+
+    // Track numbers used twice are not rejected by the reader.
+    let duplicate = fixture::file(vec![
+        fixture::info(Some(2000.0)),
+        element::master(el::TRACKS, vec![fixture::video_track(1), fixture::video_track(1)]),
+    ]);
+
+    let err = concat(&[input(2000.0, fixture::video_track(1)), duplicate]).unwrap_err();
+
+    match *err.kind() {
+        ErrorKind::IncompatibleTrack(input, track, ref field) => assert_eq!((input, track, field.as_str()), (1, 1, "TrackNumber")),
+        _ => panic!("unexpected error: {}", err),
+    };
+
+    assert!(concat(&[]).is_err());
+}
@@ -0,0 +1,63 @@
+//! DASH manifest tests.
+
+use codec::CodecConfig;
+use codec::vp9::Features;
+use dash::{self, Media, Representation};
+
+fn representation(url: &str, media: Media, cue_times: Vec<u64>) -> Representation {
+    Representation {
+        url: url.to_string(),
+        track: 1,
+        media: media,
+        codecs: "vp9".to_string(),
+        bandwidth: 1000000,
+        duration: 10000000000,
+        init_range: (0, 299),
+        index_range: (1000, 1099),
+        cue_times: cue_times,
+    }
+}
+
+#[test]
+fn codec_strings() {
+    let features = CodecConfig::Vp9(Features {
+        profile: Some(0),
+        level: Some(31),
+        bit_depth: Some(8),
+        chroma_subsampling: None,
+    });
+
+    assert_eq!(dash::codec_string("V_VP9", Some(&features)), Some("vp09.00.31.08".to_string()));
+    assert_eq!(dash::codec_string("V_VP9", None), Some("vp9".to_string()));
+    assert_eq!(dash::codec_string("A_OPUS", None), Some("opus".to_string()));
+    assert_eq!(dash::codec_string("S_TEXT/UTF8", None), None);
+}
+
+#[test]
+fn manifest() {
+    let video = Media::Video { width: 1280, height: 720 };
+    let audio = Media::Audio { sampling_frequency: 48000, channels: 2 };
+
+    let representations = vec![
+        representation("video & more.webm", video.clone(), vec![0, 5000000000]),
+        representation("audio.webm", audio, vec![0]),
+        representation("video_low.webm", video.clone(), vec![0, 5000000000]),
+    ];
+
+    let mpd = dash::manifest(&representations).unwrap();
+
+    assert!(mpd.contains("mediaPresentationDuration=\"PT10.000S\""));
+    assert!(mpd.contains("<BaseURL>video &amp; more.webm</BaseURL>"));
+    assert!(mpd.contains("<SegmentBase indexRange=\"1000-1099\">"));
+    assert!(mpd.contains("<Initialization range=\"0-299\"/>"));
+    assert!(mpd.contains("width=\"1280\" height=\"720\""));
+    assert!(mpd.contains("audioSamplingRate=\"48000\""));
+    assert_eq!(mpd.matches("<AdaptationSet").count(), 2);
+
+    // The video representations are listed in the first adaptation set.
+    assert!(mpd.find("video_low.webm").unwrap() < mpd.find("audio.webm").unwrap());
+
+    let mut misaligned = representations.clone();
+    misaligned[2].cue_times = vec![0, 4000000000];
+    assert!(dash::manifest(&misaligned).is_err());
+}
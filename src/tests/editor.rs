@@ -0,0 +1,222 @@
+//! In-place edition tests.
+
+use std::io::Cursor;
+
+use ebml;
+
+use editor::Editor;
+use elements as el;
+use reader::{meta_seek, Info, Reader};
+use reader::tag::{SimpleTag, Tag, Targets};
+use tests::fixture;
+use writer::{self, element};
+
+/// Build a file whose `SeekHead` references `indexed` among the Info, the Tracks and the Tags,
+/// followed by `void` bytes of padding. The Info is also followed by 32 bytes of padding.
+fn file(indexed: &[u64], void: usize) -> Vec<u8> {
+    indexed_file(elements(), indexed, void)
+}
+
+/// Top-level elements of the files built by `file`, with their IDs.
+fn elements() -> Vec<(u64, Vec<u8>)> {
+    let tags = writer::encode_tags(&[tag("short")]);
+
+    vec![
+        (el::INFO, fixture::info(None)),
+        (el::VOID, element::void(32)),
+        (el::TRACKS, element::master(el::TRACKS, vec![fixture::video_track(1)])),
+        (el::TAGS, tags),
+        (el::CLUSTER, fixture::cluster(0, vec![fixture::simple_block(1, 0, true, &[1])])),
+    ]
+}
+
+/// Build a file made of the top-level `elements`, whose `SeekHead` references the ones with an ID
+/// in `indexed`, followed by `void` bytes of padding.
+fn indexed_file(mut elements: Vec<(u64, Vec<u8>)>, indexed: &[u64], void: usize) -> Vec<u8> {
+    if void > 0 {
+        elements.insert(0, (el::VOID, element::void(void)));
+    }
+
+    // The positions depend on the size of the `SeekHead`, which depends on them.
+    let mut seek_head = Vec::new();
+
+    loop {
+        let mut position = seek_head.len() as u64;
+        let mut entries = Vec::new();
+
+        for &(id, ref data) in &elements {
+            if indexed.contains(&id) {
+                entries.push(element::master(el::SEEK, vec![
+                    element::binary(el::SEEK_ID, &element::id(id)),
+                    element::uint(el::SEEK_POSITION, position),
+                ]));
+            }
+
+            position += data.len() as u64;
+        }
+
+        let encoded = element::master(el::SEEK_HEAD, entries);
+        let settled = encoded.len() == seek_head.len();
+        seek_head = encoded;
+
+        if settled {
+            break;
+        }
+    }
+
+    let mut children = vec![seek_head];
+    children.extend(elements.into_iter().map(|(_, data)| data));
+
+    fixture::file(children)
+}
+
+fn tag(value: &str) -> Tag {
+    Tag {
+        targets: Targets {
+            type_value: 50,
+            ..Targets::default()
+        },
+        simple_tags: vec![SimpleTag {
+            name: String::from("TITLE"),
+            language: String::from("und"),
+            default: true,
+            string: Some(value.to_string()),
+            binary: None,
+            children: Vec::new(),
+        }],
+    }
+}
+
+/// Read the metadata before the first cluster, and the tags after it.
+fn read(data: &[u8]) -> Vec<Info> {
+    let mut reader = Reader::new(data).unwrap();
+    let mut infos = reader.info().unwrap();
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            block.unwrap();
+        }
+    }
+
+    infos.extend(reader.info().unwrap());
+    infos
+}
+
+#[test]
+fn in_place() {
+    let data = file(&[el::INFO, el::TRACKS, el::TAGS], 0);
+
+    let mut editor = Editor::open(Cursor::new(data.clone())).unwrap();
+    editor.info_mut().set_title(Some(String::from("Title")));
+    editor.write_info().unwrap();
+
+    let output = editor.into_inner().into_inner();
+    assert_eq!(output.len(), data.len());
+
+    let title = read(&output).into_iter().filter_map(|info| match info {
+        Info::Segment(info) => info.title().map(|title| title.to_string()),
+        _ => None,
+    }).next();
+
+    assert_eq!(title, Some(String::from("Title")));
+    assert_eq!(fixture::blocks(&output), fixture::blocks(&data));
+}
+
+#[test]
+fn moved_to_end() {
+    let data = file(&[el::INFO, el::TRACKS, el::TAGS], 0);
+    let value = "long".repeat(20);
+
+    let mut editor = Editor::open(Cursor::new(data.clone())).unwrap();
+    editor.tags_mut()[0] = tag(&value);
+    editor.write_tags().unwrap();
+
+    let output = editor.into_inner().into_inner();
+    assert!(output.len() > data.len());
+
+    let infos = read(&output);
+
+    // The `SeekHead` references the tags at the end of the segment, which are read after the
+    // cluster, and their previous location is blanked out.
+    let seek_head = infos.iter().filter_map(|info| match *info {
+        Info::MetaSeek(ref info) => Some(info.clone()),
+        _ => None,
+    }).next().unwrap();
+
+    let segment_offset = Reader::new(&output[..]).unwrap().segment_offset();
+    let position = segment_offset + seek_head[&el::TAGS] as usize;
+    assert_eq!(&output[position..position + 4], &[0x12, 0x54, 0xC3, 0x67]);
+
+    let tags: Vec<Tag> = infos.into_iter().filter_map(|info| match info {
+        Info::Tags(tags) => Some(tags),
+        _ => None,
+    }).flat_map(|tags| tags).collect();
+
+    assert_eq!(tags, vec![tag(&value)]);
+    assert_eq!(fixture::blocks(&output), fixture::blocks(&data));
+}
+
+#[test]
+fn seek_head_overflow() {
+    // The tags are not referenced yet, and the `SeekHead` has no room for another entry.
+    let data = file(&[el::INFO, el::TRACKS], 0);
+
+    let mut editor = Editor::open(Cursor::new(data.clone())).unwrap();
+    editor.tags_mut()[0] = tag(&"long".repeat(20));
+    assert!(editor.write_tags().is_err());
+
+    assert_eq!(editor.into_inner().into_inner(), data);
+
+    // With some padding after the `SeekHead`, the entry can be added.
+    let data = file(&[el::INFO, el::TRACKS], 64);
+
+    let mut editor = Editor::open(Cursor::new(data.clone())).unwrap();
+    editor.tags_mut()[0] = tag(&"long".repeat(20));
+    editor.write_tags().unwrap();
+}
+
+/// Read the entries of the `SeekHead` at the start of the segment, in their order.
+fn seek_entries(data: &[u8]) -> Vec<(u64, u64)> {
+    let segment_offset = Reader::new(data).unwrap().segment_offset();
+    let (seek_head, _) = ebml::reader::read_element(&mut &data[segment_offset..]).unwrap();
+
+    assert_eq!(seek_head.id(), el::SEEK_HEAD);
+    meta_seek::read_entries(seek_head.content().children().unwrap()).unwrap()
+}
+
+#[test]
+fn duplicate_seek_entries() {
+    // Both clusters are referenced by the `SeekHead`.
+    let mut elements = elements();
+    elements.push((el::CLUSTER, fixture::cluster(1000, vec![fixture::simple_block(1, 0, true, &[2])])));
+
+    let data = indexed_file(elements, &[el::INFO, el::TRACKS, el::TAGS, el::CLUSTER], 0);
+    let entries = seek_entries(&data);
+    assert_eq!(entries.iter().filter(|entry| entry.0 == el::CLUSTER).count(), 2);
+
+    let tags = vec![tag(&"long".repeat(20))];
+
+    let mut editor = Editor::open(Cursor::new(data.clone())).unwrap();
+    *editor.tags_mut() = tags.clone();
+    editor.write_tags().unwrap();
+
+    let output = editor.into_inner().into_inner();
+    let segment_offset = Reader::new(&output[..]).unwrap().segment_offset();
+    let position = output.len() - segment_offset - writer::encode_tags(&tags).len();
+
+    // Only the position of the tags changes, and the entries keep their order.
+    let updated = seek_entries(&output);
+    assert_eq!(updated.len(), entries.len());
+
+    for (entry, previous) in updated.iter().zip(&entries) {
+        assert_eq!(entry.0, previous.0);
+
+        if entry.0 == el::TAGS {
+            assert_eq!(entry.1, position as u64);
+        } else {
+            assert_eq!(entry.1, previous.1);
+        }
+    }
+
+    assert_eq!(fixture::blocks(&output), fixture::blocks(&data));
+}
@@ -0,0 +1,27 @@
+//! Error location tests.
+
+use elements as el;
+use error::{self, ErrorKind};
+
+#[test]
+fn locate_error() {
+    let err = error::not_found(el::PIXEL_WIDTH);
+    assert_eq!((err.offset(), err.path()), (None, None));
+
+    let err = error::within(err, el::VIDEO, None);
+    let err = error::within(err, el::TRACK_ENTRY, Some(2));
+    let err = error::at(err, 120);
+
+    // A deeper position is kept when the error is located in its ancestors.
+    let err = error::within(err, el::TRACKS, None);
+    let err = error::at(err, 100);
+
+    assert_eq!(err.offset(), Some(120));
+    assert_eq!(err.path(), Some("Tracks/TrackEntry[2]/Video"));
+    assert_eq!(err.to_string(), "Element '0xB0' not found (in Tracks/TrackEntry[2]/Video at byte 120)");
+
+    match *err.base_kind() {
+        ErrorKind::ElementNotFound(id) => assert_eq!(id, el::PIXEL_WIDTH),
+        _ => panic!("The kind of the error was lost"),
+    };
+}
@@ -0,0 +1,153 @@
+use std::io::Cursor;
+
+use elements as el;
+use error::{ErrorKind, Result};
+use extract;
+use reader::Reader;
+use writer::element;
+
+use tests::fixture;
+
+/// Extract the track 1, described by `entry`, from a file holding a cluster made of `blocks`.
+fn extract_track(entry: Vec<u8>, blocks: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![entry]),
+        fixture::cluster(0, blocks),
+    ]);
+
+    let mut reader = Reader::new(&data[..])?;
+    let output = extract::extract(&mut reader, 1, Cursor::new(Vec::new()))?;
+
+    Ok(output.into_inner())
+}
+
+/// Encode a track entry with a `ContentEncodings` element compressing the frames with
+/// `algorithm`.
+fn compressed_track(algorithm: u64, settings: &[u8]) -> Vec<u8> {
+    element::master(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, 1),
+        element::uint(el::TRACK_UID, 1),
+        element::uint(el::TRACK_TYPE, 1),
+        element::utf8(el::CODEC_ID, "V_VP9"),
+        element::master(el::VIDEO, vec![
+            element::uint(el::PIXEL_WIDTH, 640),
+            element::uint(el::PIXEL_HEIGHT, 480),
+        ]),
+        element::master(el::CONTENT_ENCODINGS, vec![
+            element::master(el::CONTENT_ENCODING, vec![
+                element::uint(el::CONTENT_ENCODING_SCOPE, 1),
+                element::uint(el::CONTENT_ENCODING_TYPE, 0),
+                element::master(el::CONTENT_COMPRESSION, vec![
+                    element::uint(el::CONTENT_COMP_ALGO, algorithm),
+                    element::binary(el::CONTENT_COMP_SETTINGS, settings),
+                ]),
+            ]),
+        ]),
+    ])
+}
+
+#[test]
+fn ivf() {
+    let output = extract_track(fixture::video_track(1), vec![
+        fixture::simple_block(1, 0, true, &[0xAA, 0xBB]),
+        fixture::simple_block(1, 40, false, &[0xCC]),
+    ]).unwrap();
+
+    let mut expected = b"DKIF".to_vec();
+    expected.extend_from_slice(&[0x00, 0x00, 0x20, 0x00]);
+    expected.extend_from_slice(b"VP90");
+    expected.extend_from_slice(&[0x80, 0x02, 0xE0, 0x01]);
+    expected.extend_from_slice(&[0xE8, 0x03, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]);
+    expected.extend_from_slice(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+    expected.extend_from_slice(&[0x02, 0x00, 0x00, 0x00, 0, 0, 0, 0, 0, 0, 0, 0, 0xAA, 0xBB]);
+    expected.extend_from_slice(&[0x01, 0x00, 0x00, 0x00, 40, 0, 0, 0, 0, 0, 0, 0, 0xCC]);
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn adts() {
+    // AAC LC at 44100 Hz in stereo.
+    let entry = element::master(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, 1),
+        element::uint(el::TRACK_UID, 1),
+        element::uint(el::TRACK_TYPE, 2),
+        element::utf8(el::CODEC_ID, "A_AAC"),
+        element::binary(el::CODEC_PRIVATE, &[0x12, 0x10]),
+        element::master(el::AUDIO, vec![
+            element::float(el::SAMPLING_FREQUENCY, 44100.0),
+            element::uint(el::CHANNELS, 2),
+        ]),
+    ]);
+
+    let output = extract_track(entry, vec![fixture::simple_block(1, 0, true, &[0x01, 0x02, 0x03])]).unwrap();
+
+    assert_eq!(output, vec![0xFF, 0xF1, 0x50, 0x80, 0x01, 0x5F, 0xFC, 0x01, 0x02, 0x03]);
+}
+
+#[test]
+fn ogg() {
+    let mut head = b"OpusHead".to_vec();
+    head.extend_from_slice(&[0x01, 0x02, 0x38, 0x01, 0x80, 0xBB, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+    let entry = element::master(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, 1),
+        element::uint(el::TRACK_UID, 1),
+        element::uint(el::TRACK_TYPE, 2),
+        element::utf8(el::CODEC_ID, "A_OPUS"),
+        element::binary(el::CODEC_PRIVATE, &head),
+        element::master(el::AUDIO, vec![
+            element::float(el::SAMPLING_FREQUENCY, 48000.0),
+            element::uint(el::CHANNELS, 2),
+        ]),
+    ]);
+
+    let output = extract_track(entry, vec![fixture::simple_block(1, 0, true, &[0xF8, 0xFF, 0xFE])]).unwrap();
+
+    // The identification header page, whose CRC was computed independently.
+    let mut page = b"OggS".to_vec();
+    page.extend_from_slice(&[0x00, 0x02]);
+    page.extend_from_slice(&[0x00; 8]);
+    page.extend_from_slice(&[0x73, 0x76, 0x6B, 0x6D]);
+    page.extend_from_slice(&[0x00; 4]);
+    page.extend_from_slice(&[0x7A, 0xBC, 0x8A, 0x51]);
+    page.extend_from_slice(&[0x01, 19]);
+    page.extend_from_slice(&head);
+
+    assert_eq!(&output[..page.len()], &page[..]);
+
+    // The last page holds the audio packet, ends the stream and is positioned after the samples
+    // to skip.
+    let last = &output[output.len() - 31..];
+    assert_eq!(&last[..4], b"OggS");
+    assert_eq!(last[5], 0x04);
+    assert_eq!(&last[6..14], &[0x38, 0x01, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(&last[18..22], &[0x02, 0x00, 0x00, 0x00]);
+    assert_eq!(&last[26..], &[0x01, 0x03, 0xF8, 0xFF, 0xFE]);
+}
+
+#[test]
+fn header_stripping() {
+    let output = extract_track(compressed_track(3, &[0x82, 0x49, 0x83]), vec![
+        fixture::simple_block(1, 0, true, &[0x42, 0x00]),
+    ]).unwrap();
+
+    assert_eq!(&output[32..36], &[0x05, 0x00, 0x00, 0x00]);
+    assert_eq!(&output[44..], &[0x82, 0x49, 0x83, 0x42, 0x00]);
+}
+
+#[test]
+fn unsupported_compression() {
+    // zlib is not supported, and frames must not be written compressed.
+    let result = extract_track(compressed_track(0, &[]), vec![
+        fixture::simple_block(1, 0, true, &[0x78, 0x9C]),
+    ]);
+
+    let err = result.unwrap_err();
+    match *err.base_kind() {
+        ErrorKind::InvalidElementValue(id, ref value) => assert_eq!((id, value.as_str()), (el::CONTENT_COMP_ALGO, "0")),
+        _ => panic!("unexpected error: {}", err),
+    };
+}
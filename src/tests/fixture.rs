@@ -0,0 +1,104 @@
+//! Small matroska files built element by element, shared by the tests.
+
+use elements as el;
+use reader::{checksum, Reader};
+use writer::{self, element};
+
+/// Encode the segment information, with a timestamp scale of 1 ms.
+pub fn info(duration: Option<f64>) -> Vec<u8> {
+    let mut children = vec![
+        element::uint(el::TIMECODE_SCALE, 1000000),
+        element::utf8(el::MUXING_APP, "test"),
+        element::utf8(el::WRITING_APP, "test"),
+    ];
+
+    if let Some(duration) = duration {
+        children.push(element::float(el::DURATION, duration));
+    }
+
+    element::master(el::INFO, children)
+}
+
+/// Encode a VP9 video track entry, whose UID is its number.
+pub fn video_track(number: u64) -> Vec<u8> {
+    element::master(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, number),
+        element::uint(el::TRACK_UID, number),
+        element::uint(el::TRACK_TYPE, 1),
+        element::utf8(el::CODEC_ID, "V_VP9"),
+        element::master(el::VIDEO, vec![
+            element::uint(el::PIXEL_WIDTH, 640),
+            element::uint(el::PIXEL_HEIGHT, 480),
+        ]),
+    ])
+}
+
+/// Encode an Opus audio track entry, whose UID is its number.
+pub fn audio_track(number: u64) -> Vec<u8> {
+    element::master(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, number),
+        element::uint(el::TRACK_UID, number),
+        element::uint(el::TRACK_TYPE, 2),
+        element::utf8(el::CODEC_ID, "A_OPUS"),
+        element::master(el::AUDIO, vec![
+            element::float(el::SAMPLING_FREQUENCY, 48000.0),
+            element::uint(el::CHANNELS, 2),
+        ]),
+    ])
+}
+
+/// Encode a `SimpleBlock` without lacing, whose timecode is relative to its cluster.
+pub fn simple_block(track: u64, timecode: i16, keyframe: bool, data: &[u8]) -> Vec<u8> {
+    let mut content = element::size(track as usize);
+    content.push((timecode >> 8) as u8);
+    content.push(timecode as u8);
+    content.push(if keyframe { 0x80 } else { 0x00 });
+    content.extend_from_slice(data);
+
+    element::binary(el::SIMPLE_BLOCK, &content)
+}
+
+/// Encode a cluster starting at `timecode` and containing `blocks`.
+pub fn cluster(timecode: u64, blocks: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut children = vec![element::uint(el::TIMECODE, timecode)];
+    children.extend(blocks);
+
+    element::master(el::CLUSTER, children)
+}
+
+/// Encode the master element `id`, whose first child is the checksum of `children`, or a wrong
+/// one if `valid` is false.
+pub fn master_with_crc(id: u64, children: Vec<Vec<u8>>, valid: bool) -> Vec<u8> {
+    let crc = checksum::crc32(&children.concat()) ^ if valid { 0 } else { 1 };
+
+    let mut elements = vec![element::binary(el::CRC32, &(0..4).map(|i| (crc >> (8 * i)) as u8).collect::<Vec<u8>>())];
+    elements.extend(children);
+
+    element::master(id, elements)
+}
+
+/// Encode a complete WebM file, made of the EBML header and a segment containing `children`.
+pub fn file(children: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut data = writer::encode_header("webm");
+    data.extend(element::master(el::SEGMENT, children));
+
+    data
+}
+
+/// Read all the blocks of the first segment of `data`, as their track, absolute timecode,
+/// keyframe flag and data.
+pub fn blocks(data: &[u8]) -> Vec<(u64, i64, bool, Vec<u8>)> {
+    let mut reader = Reader::new(data).unwrap();
+    let mut blocks = Vec::new();
+
+    reader.info().unwrap();
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            let block = block.unwrap();
+            blocks.push((block.track(), block.absolute_timecode(), block.keyframe(), block.data()));
+        }
+    }
+
+    blocks
+}
@@ -0,0 +1,47 @@
+//! Sample table serialization tests.
+
+use index::Index;
+
+#[test]
+fn serialize() {
+    let mut data = b"MKVIDX\x01".to_vec();
+    data.extend_from_slice(&[0, 0, 0, 1]);
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 2]);
+
+    // Keyframe with a duration, then a frame without one and with a negative timestamp.
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 40, 0, 0, 0, 0, 0, 0, 1, 0]);
+    data.extend_from_slice(&[0, 0, 0, 16, 0, 0, 0, 0, 0x03]);
+    data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xF6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 16]);
+    data.extend_from_slice(&[0, 0, 0, 8, 0, 0, 0, 1, 0x00]);
+
+    let index = Index::read(&mut &data[..]).unwrap();
+
+    assert_eq!(index.tracks(), vec![2]);
+
+    let first = index.sample(2, 0).unwrap();
+    assert_eq!((first.pts, first.duration, first.offset, first.size), (0, Some(40), 256, 16));
+    assert!(first.keyframe);
+
+    let second = index.sample(2, 1).unwrap();
+    assert_eq!((second.pts, second.duration, second.cluster), (-10, None, 1));
+    assert!(!second.keyframe);
+
+    assert!(index.sample(2, 2).is_none());
+
+    let mut written = Vec::new();
+    index.write(&mut written).unwrap();
+    assert_eq!(written, data);
+
+    assert!(Index::read(&mut &b"MKVIDX\x02"[..]).is_err());
+}
+
+#[test]
+fn truncated() {
+    // A track declaring 2^32 - 1 samples, of which only one is present.
+    let mut data = b"MKVIDX\x01".to_vec();
+    data.extend_from_slice(&[0, 0, 0, 1]);
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1, 0xFF, 0xFF, 0xFF, 0xFF]);
+    data.extend_from_slice(&[0; 33]);
+
+    assert!(Index::read(&mut &data[..]).is_err());
+}
@@ -0,0 +1,61 @@
+//! Linked segments tests.
+
+use std::path::PathBuf;
+
+use link::{Chain, Link};
+use reader::segment;
+
+fn link(name: &str, uid: u8, prev: Option<u8>, next: Option<u8>) -> Link {
+    let mut info = segment::Info::default();
+    info.set_uid(Some(vec![uid]));
+    info.set_prev_uid(prev.map(|uid| vec![uid]));
+    info.set_next_uid(next.map(|uid| vec![uid]));
+
+    Link {
+        path: PathBuf::from(name),
+        info: info,
+    }
+}
+
+fn names(chain: &Chain) -> Vec<&str> {
+    chain.links().iter().map(|link| link.path.to_str().unwrap()).collect()
+}
+
+#[test]
+fn order() {
+    // The start of the chain is in the middle, and an unrelated file is among the candidates.
+    let links = vec![
+        link("b.mkv", 2, Some(1), Some(3)),
+        link("other.mkv", 9, None, None),
+        link("c.mkv", 3, Some(2), None),
+        link("a.mkv", 1, None, Some(2)),
+    ];
+
+    let chain = Chain::from_links(links, 0).unwrap();
+    assert_eq!(names(&chain), vec!["a.mkv", "b.mkv", "c.mkv"]);
+    assert!(chain.is_complete());
+
+    // A link referenced by only one side still counts, but the chain misses its last segment.
+    let links = vec![link("b.mkv", 2, None, Some(3)), link("a.mkv", 1, None, Some(2))];
+
+    let chain = Chain::from_links(links, 0).unwrap();
+    assert_eq!(names(&chain), vec!["a.mkv", "b.mkv"]);
+    assert!(!chain.is_complete());
+}
+
+#[test]
+fn prefer_named() {
+    let mut first = link("a.mkv", 1, None, Some(2));
+    first.info.set_filenames(None, Some(String::from("b2.mkv")));
+
+    let links = vec![first, link("b1.mkv", 2, Some(1), None), link("b2.mkv", 2, Some(1), None)];
+
+    let chain = Chain::from_links(links, 0).unwrap();
+    assert_eq!(names(&chain), vec!["a.mkv", "b2.mkv"]);
+}
+
+#[test]
+fn loop_error() {
+    let links = vec![link("a.mkv", 1, Some(2), Some(2)), link("b.mkv", 2, Some(1), Some(1))];
+    assert!(Chain::from_links(links, 0).is_err());
+}
@@ -1,3 +1,22 @@
 //! Unit tests.
 
+mod codec;
+mod concat;
+mod dash;
+mod editor;
+mod error;
+mod extract;
+mod fixture;
+mod index;
+mod link;
+mod playlist;
 mod reader;
+mod repair;
+mod split;
+#[cfg(feature = "serde")]
+mod serialization;
+mod stereo;
+mod subtitles;
+mod trick;
+mod validate;
+mod writer;
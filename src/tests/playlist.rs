@@ -0,0 +1,132 @@
+//! Ordered edition playback tests.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use elements as el;
+use link::Link;
+use playlist::Playlist;
+use reader::{Info, Reader};
+use writer::element;
+
+use tests::fixture;
+
+const MS: u64 = 1000000;
+
+/// Encode segment information with a timestamp scale of 1 ms and the UID `uid`.
+fn info(uid: u8) -> Vec<u8> {
+    element::master(el::INFO, vec![
+        element::binary(el::SEGMENT_UID, &[uid; 16]),
+        element::uint(el::TIMECODE_SCALE, 1000000),
+        element::utf8(el::MUXING_APP, "test"),
+        element::utf8(el::WRITING_APP, "test"),
+    ])
+}
+
+/// Encode a chapter playing `start` to `end` milliseconds of the segment `segment`, or of the
+/// segment of the edition.
+fn atom(uid: u64, start: u64, end: u64, enabled: bool, segment: Option<u8>) -> Vec<u8> {
+    let mut children = vec![
+        element::uint(el::CHAPTER_UID, uid),
+        element::uint(el::CHAPTER_TIME_START, start * MS),
+        element::uint(el::CHAPTER_TIME_END, end * MS),
+        element::uint(el::CHAPTER_FLAG_ENABLED, enabled as u64),
+    ];
+
+    if let Some(segment) = segment {
+        children.push(element::binary(el::CHAPTER_SEGMENT_UID, &[segment; 16]));
+    }
+
+    element::master(el::CHAPTER_ATOM, children)
+}
+
+/// Write `data` to a temporary file named after `name`.
+fn temporary(name: &str, data: &[u8]) -> PathBuf {
+    let path = env::temp_dir().join(format!("mkv-playlist-{}-{}.mkv", process::id(), name));
+    fs::write(&path, data).unwrap();
+
+    path
+}
+
+#[test]
+fn ordered_edition() {
+    let edition = element::master(el::EDITION_ENTRY, vec![
+        element::uint(el::EDITION_UID, 1),
+        element::uint(el::EDITION_FLAG_ORDERED, 1),
+        atom(1, 2000, 3000, true, None),
+        atom(2, 0, 1000, true, Some(1)),
+        atom(3, 1000, 2000, false, None),
+        atom(4, 0, 500, true, Some(2)),
+    ]);
+
+    // The cluster following the one after the end of the first range is damaged: it must not be
+    // read.
+    let main = fixture::file(vec![
+        info(1),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        element::master(el::CHAPTERS, vec![edition]),
+        fixture::cluster(0, vec![fixture::simple_block(1, 0, true, &[0]), fixture::simple_block(1, 500, false, &[1])]),
+        fixture::cluster(1000, vec![fixture::simple_block(1, 0, true, &[2])]),
+        fixture::cluster(2000, vec![fixture::simple_block(1, 0, true, &[3]), fixture::simple_block(1, 500, false, &[4])]),
+        fixture::cluster(3000, vec![fixture::simple_block(1, 0, true, &[5])]),
+        element::master(el::CLUSTER, vec![element::uint(el::TIMECODE, 4000), vec![0x00, 0x00]]),
+    ]);
+
+    let other = fixture::file(vec![
+        info(2),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        fixture::cluster(0, vec![fixture::simple_block(1, 0, true, &[6]), fixture::simple_block(1, 600, true, &[7])]),
+    ]);
+
+    let mut reader = Reader::new(&main[..]).unwrap();
+    let mut segment = None;
+    let mut editions = Vec::new();
+
+    for info in reader.info().unwrap() {
+        match info {
+            Info::Segment(info) => segment = Some(info),
+            Info::Chapters(chapters) => editions = chapters,
+            _ => {},
+        };
+    }
+
+    let playlist = Playlist::new(&editions[0], &segment.unwrap()).unwrap();
+
+    // The disabled chapter is skipped, and the chapter referencing the segment of the edition
+    // plays it.
+    let ranges: Vec<_> = playlist.ranges().iter()
+        .map(|range| (range.chapter, range.segment_uid.clone(), range.start / MS, range.end / MS, range.timestamp / MS))
+        .collect();
+
+    assert_eq!(ranges, vec![
+        (1, None, 2000, 3000, 0),
+        (2, None, 0, 1000, 1000),
+        (4, Some(vec![2; 16]), 0, 500, 2000),
+    ]);
+
+    assert_eq!(playlist.duration(), 2500 * MS);
+
+    let main_path = temporary("main", &main);
+    let other_path = temporary("other", &other);
+
+    // The other segment must be found among the links.
+    assert!(playlist.packets(&main_path, &[]).is_err());
+
+    let links = vec![Link::read(&other_path).unwrap()];
+    let packets: Vec<_> = playlist.packets(&main_path, &links).unwrap()
+        .map(|packet| packet.map(|packet| (packet.segment, packet.timestamp / MS as i64, packet.data)))
+        .collect();
+
+    fs::remove_file(&main_path).unwrap();
+    fs::remove_file(&other_path).unwrap();
+
+    assert_eq!(packets.into_iter().collect::<Result<Vec<_>, _>>().unwrap(), vec![
+        (0, 0, vec![3]),
+        (0, 500, vec![4]),
+        (1, 1000, vec![0]),
+        (1, 1500, vec![1]),
+        (2, 2000, vec![6]),
+    ]);
+}
@@ -0,0 +1,95 @@
+//! CRC-32 tests.
+
+use elements as el;
+use error::ErrorKind;
+use reader::{checksum, Reader};
+use tests::fixture;
+use writer::element;
+
+#[test]
+fn crc32() {
+    assert_eq!(checksum::crc32(b""), 0);
+    assert_eq!(checksum::crc32(b"123456789"), 0xCBF43926);
+    assert_eq!(checksum::decode(&[0x26, 0x39, 0xF4, 0xCB]), 0xCBF43926);
+}
+
+/// Encode a file whose first track entry and chapter atom have checksums, and whose track
+/// entry checksum is valid if `valid` is true.
+fn nested_file(valid: bool) -> Vec<u8> {
+    let track = fixture::master_with_crc(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, 1),
+        element::uint(el::TRACK_UID, 1),
+        element::uint(el::TRACK_TYPE, 1),
+        element::utf8(el::CODEC_ID, "V_VP9"),
+        element::master(el::VIDEO, vec![
+            element::uint(el::PIXEL_WIDTH, 640),
+            element::uint(el::PIXEL_HEIGHT, 480),
+        ]),
+    ], valid);
+
+    let atom = fixture::master_with_crc(el::CHAPTER_ATOM, vec![
+        element::uint(el::CHAPTER_UID, 1),
+        element::uint(el::CHAPTER_TIME_START, 0),
+    ], true);
+
+    fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![track]),
+        element::master(el::CHAPTERS, vec![
+            element::master(el::EDITION_ENTRY, vec![element::uint(el::EDITION_UID, 1), atom]),
+        ]),
+    ])
+}
+
+#[test]
+fn nested_valid() {
+    let data = nested_file(true);
+    let mut reader = Reader::new(&data[..]).unwrap();
+    reader.set_checksum_mode(checksum::Mode::Strict);
+
+    assert_eq!(reader.info().unwrap().len(), 3);
+    assert!(reader.checksum_mismatches().is_empty());
+}
+
+#[test]
+fn nested_report() {
+    let data = nested_file(false);
+
+    // Checksums are ignored by default.
+    let mut reader = Reader::new(&data[..]).unwrap();
+    assert_eq!(reader.info().unwrap().len(), 3);
+    assert!(reader.checksum_mismatches().is_empty());
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    reader.set_checksum_mode(checksum::Mode::Report);
+
+    assert_eq!(reader.info().unwrap().len(), 3);
+
+    let mismatches = reader.checksum_mismatches();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].parent, el::TRACK_ENTRY);
+    assert_eq!(mismatches[0].expected ^ mismatches[0].actual, 1);
+
+    // The content of the track entry follows the segment information and the headers of the
+    // tracks and of the entry.
+    let position = fixture::info(None).len() + 5 + 2;
+    assert_eq!(mismatches[0].position, position);
+}
+
+#[test]
+fn nested_strict() {
+    let data = nested_file(false);
+    let mut reader = Reader::new(&data[..]).unwrap();
+    reader.set_checksum_mode(checksum::Mode::Strict);
+
+    let err = reader.info().unwrap_err();
+
+    match *err.base_kind() {
+        ErrorKind::ChecksumMismatch(parent, expected, actual) => {
+            assert_eq!(parent, el::TRACK_ENTRY);
+            assert_eq!(expected ^ actual, 1);
+        },
+
+        _ => panic!("unexpected error: {}", err),
+    };
+}
@@ -0,0 +1,98 @@
+//! Cluster reading tests.
+
+use elements as el;
+use reader::Reader;
+use reader::cluster::TimeSlice;
+use writer::element;
+
+use tests::fixture;
+
+/// Encode the element `id` holding a block without lacing, whose timecode is relative to its
+/// cluster.
+fn block(id: u64, track: u64, timecode: i16, data: &[u8]) -> Vec<u8> {
+    let mut content = element::size(track as usize);
+    content.push((timecode >> 8) as u8);
+    content.push(timecode as u8);
+    content.push(0x00);
+    content.extend_from_slice(data);
+
+    element::binary(id, &content)
+}
+
+#[test]
+fn legacy_elements() {
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1), fixture::audio_track(2), fixture::audio_track(3)]),
+        element::master(el::CLUSTER, vec![
+            element::uint(el::TIMECODE, 1000),
+            element::master(el::SILENT_TRACKS, vec![
+                element::uint(el::SILENT_TRACK_NUMBER, 2),
+                element::uint(el::SILENT_TRACK_NUMBER, 3),
+            ]),
+            element::master(el::BLOCK_GROUP, vec![
+                block(el::BLOCK, 1, 0, &[0xAA, 0xBB]),
+                element::uint(el::BLOCK_DURATION, 40),
+                element::master(el::SLICES, vec![
+                    element::master(el::TIME_SLICE, vec![
+                        element::uint(el::LACE_NUMBER, 0),
+                        element::uint(el::FRAME_NUMBER, 7),
+                        element::uint(el::BLOCK_ADDITIONID, 0),
+                        element::uint(el::DELAY, 5),
+                        element::uint(el::SLICE_DURATION, 35),
+                    ]),
+                ]),
+            ]),
+            element::master(el::BLOCK_GROUP, vec![
+                block(el::BLOCK_VIRTUAL, 1, 80, &[]),
+                element::int(el::REFERENCE_BLOCK, -80),
+                element::int(el::REFERENCE_VIRTUAL, -40),
+            ]),
+        ]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    reader.info().unwrap();
+
+    // The timecode and the silent tracks of the cluster are only known once the elements before
+    // the first block are read.
+    let mut cluster = reader.next_cluster().unwrap().unwrap();
+    assert_eq!(cluster.timecode(), 0);
+    assert!(cluster.silent_tracks().is_empty());
+
+    let mut blocks = cluster.blocks();
+
+    let first = blocks.next().unwrap().unwrap();
+    assert_eq!(blocks.silent_tracks(), &[2, 3]);
+
+    assert_eq!(first.track(), 1);
+    assert_eq!(first.absolute_timecode(), 1000);
+    assert_eq!(first.duration(), Some(40));
+    assert!(first.keyframe());
+    assert!(!first.is_virtual());
+    assert_eq!(first.reference_virtual(), None);
+    assert_eq!(first.slices(), &[TimeSlice {
+        lace_number: 0,
+        frame_number: 7,
+        block_addition_id: 0,
+        delay: 5,
+        duration: 35,
+    }]);
+    assert_eq!(first.data(), vec![0xAA, 0xBB]);
+
+    // The virtual block has a header, but no data nor frames.
+    let second = blocks.next().unwrap().unwrap();
+
+    assert_eq!(second.track(), 1);
+    assert_eq!(second.absolute_timecode(), 1080);
+    assert!(!second.keyframe());
+    assert!(second.is_virtual());
+    assert_eq!(second.references(), &[-80]);
+    assert_eq!(second.reference_virtual(), Some(-40));
+    assert!(second.slices().is_empty());
+    assert_eq!(second.size(), 0);
+    assert!(second.frames().unwrap().is_empty());
+
+    assert!(blocks.next().is_none());
+    assert_eq!(blocks.silent_tracks(), &[2, 3]);
+}
@@ -0,0 +1,48 @@
+//! Reader limits tests.
+
+use elements as el;
+use error::ErrorKind;
+use reader::cluster;
+use reader::limits::ReaderLimits;
+use writer::element;
+
+#[test]
+fn nesting_depth() {
+    let mut atom = element::master(el::CHAPTER_ATOM, vec![element::uint(el::CHAPTER_UID, 1)]);
+    for _ in 0..3 {
+        atom = element::master(el::CHAPTER_ATOM, vec![atom]);
+    }
+
+    // Content of the `Chapters` element: an edition with 4 nested atoms, and a UID at depth 7.
+    let data = element::master(el::EDITION_ENTRY, vec![atom]);
+
+    let mut limits = ReaderLimits::default();
+    limits.max_depth = 7;
+    assert!(limits.check_master(&data, 1).is_ok());
+
+    limits.max_depth = 6;
+    match *limits.check_master(&data, 1).unwrap_err().base_kind() {
+        ErrorKind::LimitExceeded(_, depth, max) => assert_eq!((depth, max), (7, 6)),
+        ref kind => panic!("Unexpected error: {}", kind),
+    };
+}
+
+#[test]
+fn child_larger_than_parent() {
+    let mut data = element::master(el::EDITION_ENTRY, vec![element::uint(el::EDITION_UID, 1)]);
+
+    // Claim a size of 100 bytes for the UID.
+    let len = data.len();
+    data[len - 2] = 0x80 | 100;
+
+    assert!(ReaderLimits::default().check_master(&data, 1).is_err());
+}
+
+#[test]
+fn laced_frame_larger_than_block() {
+    // Two frames, the first one claiming 200 bytes out of the 3 remaining.
+    assert!(cluster::parse_xiph_frames(vec![1, 200, 1, 2, 3]).is_err());
+
+    let frames = cluster::parse_xiph_frames(vec![1, 2, 1, 2, 3]).unwrap();
+    assert_eq!(frames, vec![vec![1, 2], vec![3]]);
+}
@@ -1,3 +1,10 @@
 //! Reader unit tests.
 
+mod checksum;
+mod cluster;
+mod limits;
+mod multi_segment;
+mod resync;
 mod segment;
+mod track;
+mod tree;
@@ -0,0 +1,111 @@
+//! Tests of files made of several segments.
+
+use elements as el;
+use reader::{Info, Reader};
+use writer::element;
+
+use tests::fixture;
+
+/// Build a file made of two segments, each with its own EBML header. The first one holds a video
+/// track and two clusters, the second one an audio track and a single cluster.
+fn segments() -> (Vec<u8>, usize) {
+    let first = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        fixture::cluster(0, vec![fixture::simple_block(1, 0, true, &[1])]),
+        fixture::cluster(40, vec![fixture::simple_block(1, 0, false, &[2])]),
+    ]);
+
+    let second = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::audio_track(2)]),
+        fixture::cluster(0, vec![fixture::simple_block(2, 20, true, &[3])]),
+    ]);
+
+    let len = first.len();
+    let mut data = first;
+    data.extend(second);
+
+    (data, len)
+}
+
+/// Read the metadata of the current segment, and return the numbers and codecs of its tracks.
+fn tracks<R: ::std::io::Read>(reader: &mut Reader<R>) -> Vec<(u64, String)> {
+    let mut tracks = Vec::new();
+
+    for info in reader.info().unwrap() {
+        if let Info::Tracks(info) = info {
+            tracks.extend(info.iter().map(|track| (track.index(), track.codec().to_string())));
+        }
+    }
+
+    tracks
+}
+
+/// Read the blocks of the current segment, as their track, absolute timecode and data.
+fn blocks<R: ::std::io::Read>(reader: &mut Reader<R>) -> Vec<(u64, i64, Vec<u8>)> {
+    let mut blocks = Vec::new();
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            let block = block.unwrap();
+            blocks.push((block.track(), block.absolute_timecode(), block.data()));
+        }
+    }
+
+    blocks
+}
+
+#[test]
+fn read_each_segment() {
+    let (data, first_len) = segments();
+    let mut reader = Reader::new(&data[..]).unwrap();
+    let first_offset = reader.segment_offset();
+
+    assert_eq!(reader.segment_index(), 0);
+    assert_eq!(tracks(&mut reader), vec![(1, String::from("V_VP9"))]);
+    assert_eq!(blocks(&mut reader), vec![(1, 0, vec![1]), (1, 40, vec![2])]);
+
+    // The clusters of the first segment do not run into the second one.
+    assert!(reader.next_segment().unwrap());
+    assert_eq!(reader.segment_index(), 1);
+    assert_eq!(reader.segment_offset(), first_len + first_offset);
+
+    assert_eq!(tracks(&mut reader), vec![(2, String::from("A_OPUS"))]);
+    assert_eq!(blocks(&mut reader), vec![(2, 20, vec![3])]);
+
+    assert!(!reader.next_segment().unwrap());
+}
+
+#[test]
+fn skip_clusters() {
+    let (data, _) = segments();
+    let mut reader = Reader::new(&data[..]).unwrap();
+
+    // The clusters of the first segment are skipped, after its metadata or without reading
+    // anything.
+    assert_eq!(tracks(&mut reader), vec![(1, String::from("V_VP9"))]);
+    assert!(reader.next_segment().unwrap());
+
+    assert_eq!(tracks(&mut reader), vec![(2, String::from("A_OPUS"))]);
+    assert_eq!(blocks(&mut reader), vec![(2, 20, vec![3])]);
+    assert!(!reader.next_segment().unwrap());
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    assert!(reader.next_segment().unwrap());
+    assert_eq!(reader.segment_index(), 1);
+    assert_eq!(tracks(&mut reader), vec![(2, String::from("A_OPUS"))]);
+
+    // A cluster read partially is skipped as well.
+    let mut reader = Reader::new(&data[..]).unwrap();
+    tracks(&mut reader);
+
+    {
+        let mut cluster = reader.next_cluster().unwrap().unwrap();
+        cluster.blocks().next().unwrap().unwrap();
+    }
+
+    assert!(reader.next_segment().unwrap());
+    assert_eq!(tracks(&mut reader), vec![(2, String::from("A_OPUS"))]);
+    assert_eq!(blocks(&mut reader), vec![(2, 20, vec![3])]);
+}
@@ -0,0 +1,72 @@
+//! Resync tests.
+
+use std::io::{Cursor, Read};
+
+use elements as el;
+use reader::Reader;
+use reader::resync::{self, Input};
+use tests::fixture;
+use writer::element;
+
+#[test]
+fn find_cluster() {
+    // Garbage, a cluster ID with an implausible size, then a cluster with timecode 5.
+    let data = vec![
+        0x12, 0x34, 0x1F, 0x43, 0xB6, 0x75, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0x1F, 0x43, 0xB6, 0x75, 0x83, 0xE7, 0x81, 0x05,
+    ];
+
+    let mut input = Input::new(Cursor::new(data.clone()));
+    assert_eq!(resync::find_cluster(&mut input, 0, 16).unwrap(), Some(14));
+    assert_eq!(input.position(), 14);
+
+    let mut rest = Vec::new();
+    input.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, &data[14..]);
+
+    // A cluster whose timecode is lower than the last one read is not a resync point.
+    let mut input = Input::new(Cursor::new(data));
+    assert_eq!(resync::find_cluster(&mut input, 6, 16).unwrap(), None);
+}
+
+#[test]
+fn peek() {
+    let mut input = Input::new(Cursor::new(vec![1, 2, 3]));
+    assert_eq!(input.peek(2).unwrap(), &[1, 2]);
+    assert_eq!(input.position(), 0);
+
+    let mut byte = [0u8; 1];
+    input.read_exact(&mut byte).unwrap();
+    assert_eq!(input.peek(4).unwrap(), &[2, 3]);
+    assert_eq!(input.position(), 1);
+}
+
+#[test]
+fn damaged_timecode() {
+    // The second cluster has a timecode higher than the one of the next cluster, then an invalid
+    // element: its timecode must not prevent resuming at the next cluster.
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        fixture::cluster(10, vec![fixture::simple_block(1, 0, true, &[1])]),
+        element::master(el::CLUSTER, vec![element::uint(el::TIMECODE, 1000), vec![0x00, 0x00, 0x00]]),
+        fixture::cluster(500, vec![fixture::simple_block(1, 0, true, &[3])]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    reader.set_lenient(true);
+    reader.info().unwrap();
+
+    let mut blocks = Vec::new();
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            let block = block.unwrap();
+            blocks.push((block.absolute_timecode(), block.data()));
+        }
+    }
+
+    assert_eq!(blocks, vec![(10, vec![1]), (500, vec![3])]);
+    assert_eq!(reader.resyncs().len(), 1);
+    assert!(reader.resyncs()[0].resumed.is_some());
+}
@@ -0,0 +1,75 @@
+//! Track reading tests.
+
+use codec::CodecConfig;
+use elements as el;
+use reader::{Info, Reader};
+use writer::element;
+
+use tests::fixture;
+
+/// Encode an audio track entry with the codec `codec` and the codec private data `private`.
+fn audio_track(number: u64, codec: &str, private: Option<&[u8]>) -> Vec<u8> {
+    let mut children = vec![
+        element::uint(el::TRACK_NUMBER, number),
+        element::uint(el::TRACK_UID, number),
+        element::uint(el::TRACK_TYPE, 2),
+        element::utf8(el::CODEC_ID, codec),
+        element::master(el::AUDIO, vec![
+            element::float(el::SAMPLING_FREQUENCY, 24000.0),
+            element::uint(el::CHANNELS, 2),
+        ]),
+    ];
+
+    if let Some(private) = private {
+        children.push(element::binary(el::CODEC_PRIVATE, private));
+    }
+
+    element::master(el::TRACK_ENTRY, children)
+}
+
+#[test]
+fn legacy_aac() {
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![audio_track(1, "A_AAC/MPEG4/LC/SBR", None)]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    let (_, tracks) = reader.header_info().unwrap();
+
+    match tracks[0].codec_config().unwrap() {
+        Some(CodecConfig::Aac(config)) => {
+            assert_eq!(config.object_type, 2);
+            assert_eq!(config.output_sample_rate(), 48000);
+        },
+
+        config => panic!("unexpected configuration: {:?}", config),
+    };
+
+    assert!(reader.codec_errors().is_empty());
+}
+
+#[test]
+fn codec_errors() {
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![
+            audio_track(1, "A_OPUS", Some(b"OpusHead")),
+            audio_track(2, "A_AAC/MPEG4/LC", Some(&[0x12, 0x10])),
+        ]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    let tracks = reader.info().unwrap().into_iter().filter_map(|info| match info {
+        Info::Tracks(tracks) => Some(tracks),
+        _ => None,
+    }).next().unwrap();
+
+    // The damaged track is still read, with the audio parameters of its entry.
+    assert_eq!(tracks.len(), 2);
+
+    let errors = reader.codec_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].track, 1);
+    assert!(errors[0].error.contains("OpusHead"));
+}
@@ -0,0 +1,102 @@
+//! Element tree tests.
+
+use elements as el;
+use reader::tree::{self, Value};
+use writer::{self, element};
+
+#[test]
+fn read_tree() {
+    let info = element::master(el::INFO, vec![
+        element::uint(el::TIMECODE_SCALE, 1000000),
+        element::float(el::DURATION, 2.5),
+        element::utf8(el::MUXING_APP, "test"),
+    ]);
+
+    // A cluster of unknown size, ended by the next one.
+    let mut segment = info;
+    segment.extend(element::id(el::CLUSTER));
+    segment.extend(element::vint(element::UNKNOWN_SIZE, 8));
+    segment.extend(element::uint(el::TIMECODE, 0));
+    segment.extend(element::master(el::CLUSTER, vec![element::uint(el::TIMECODE, 20)]));
+
+    let mut data = writer::encode_header("matroska");
+    let segment_offset = data.len() as u64;
+    data.extend(element::element(el::SEGMENT, &segment));
+
+    let tree = tree::read(&data[..]);
+    assert!(tree.error.is_none());
+    assert_eq!(tree.elements.len(), 2);
+
+    let segment = &tree.elements[1];
+    assert_eq!((segment.name(), segment.offset), (String::from("Segment"), segment_offset));
+
+    let children = segment.children();
+    assert_eq!(children.len(), 3);
+    assert_eq!(children[0].children()[0].name(), "TimecodeScale");
+
+    match children[0].children()[1].value {
+        Value::Float(duration) => assert_eq!(duration, 2.5),
+        _ => panic!("Duration is not a float"),
+    };
+
+    assert_eq!(children[1].size, None);
+    assert_eq!(children[1].children().len(), 1);
+
+    match children[2].children()[0].value {
+        Value::Unsigned(timecode) => assert_eq!(timecode, 20),
+        _ => panic!("Timecode is not an unsigned integer"),
+    };
+
+    // Truncate the file in the middle of the last cluster.
+    let tree = tree::read(&data[..data.len() - 1]);
+    assert!(tree.error.is_some());
+    assert_eq!(tree.elements[1].children().len(), 3);
+}
+
+#[test]
+fn deeply_nested() {
+    let mut tags = element::master(el::TAGS, vec![]);
+    for _ in 0..1000 {
+        tags = element::master(el::TAGS, vec![tags]);
+    }
+
+    let mut data = writer::encode_header("matroska");
+    data.extend(element::master(el::SEGMENT, vec![tags]));
+
+    // The elements are read down to the depth limit, where the reading stops.
+    let tree = tree::read(&data[..]);
+    let (_, ref message) = tree.error.unwrap();
+    assert_eq!(message, "element nested deeper than 32 levels");
+
+    let mut depth = 0;
+    let mut nodes = tree.elements[1].children();
+
+    while !nodes.is_empty() {
+        depth += 1;
+        nodes = nodes[0].children();
+    }
+
+    assert_eq!(depth, 32);
+}
+
+#[test]
+fn find_element() {
+    let entry = |number| element::master(el::TRACK_ENTRY, vec![
+        element::uint(el::TRACK_NUMBER, number),
+        element::master(el::VIDEO, vec![element::uint(el::PIXEL_WIDTH, 640)]),
+    ]);
+
+    let first = entry(1);
+    let mut data = first.clone();
+    data.extend(entry(2));
+
+    // The video element of the second entry follows its header and track number.
+    let video = first.len() + 2 + 3;
+
+    assert_eq!(tree::find(&data, "TrackEntry[2]/Video"), Some(video));
+    assert_eq!(tree::find(&data, "TrackEntry"), Some(0));
+
+    // The deepest element found is returned.
+    assert_eq!(tree::find(&data, "TrackEntry[2]/Audio"), Some(first.len()));
+    assert_eq!(tree::find(&data, "TrackEntry[3]/Video"), None);
+}
@@ -0,0 +1,166 @@
+//! Repair tests.
+
+use std::io::Cursor;
+
+use elements as el;
+use error::ErrorKind;
+use reader::{checksum, Info, Reader};
+use repair::{self, Report};
+use writer::element;
+
+use tests::fixture;
+
+/// Encode an edition holding a single chapter, with a checksum that is valid if `valid` is true.
+fn edition(uid: u64, valid: bool) -> Vec<u8> {
+    fixture::master_with_crc(el::EDITION_ENTRY, vec![
+        element::uint(el::EDITION_UID, uid),
+        element::master(el::CHAPTER_ATOM, vec![
+            element::uint(el::CHAPTER_UID, uid),
+            element::uint(el::CHAPTER_TIME_START, 0),
+        ]),
+    ], valid)
+}
+
+/// Encode an attached file, with a checksum that is valid if `valid` is true.
+fn attached_file(uid: u64, valid: bool) -> Vec<u8> {
+    fixture::master_with_crc(el::ATTACHED_FILE, vec![
+        element::utf8(el::FILE_NAME, "cover.jpg"),
+        element::utf8(el::FILE_MIME_TYPE, "image/jpeg"),
+        element::binary(el::FILE_DATA, &[0xFF, 0xD8, 0xFF, 0xD9]),
+        element::uint(el::FILE_UID, uid),
+    ], valid)
+}
+
+/// Repair `data`, and return the output with the report.
+fn repair(data: &[u8]) -> (Vec<u8>, Report) {
+    let mut reader = Reader::new(data).unwrap();
+    let (output, report) = repair::repair(&mut reader, Cursor::new(Vec::new())).unwrap();
+
+    (output.into_inner(), report)
+}
+
+/// Returns whether `data` contains `part`.
+fn contains(data: &[u8], part: &[u8]) -> bool {
+    data.windows(part.len()).any(|window| window == part)
+}
+
+#[test]
+fn clean() {
+    let data = fixture::file(vec![
+        fixture::info(Some(40.0)),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        fixture::cluster(0, vec![
+            fixture::simple_block(1, 0, true, &[1]),
+            fixture::simple_block(1, 40, false, &[2]),
+        ]),
+    ]);
+
+    let (output, report) = repair(&data);
+
+    assert_eq!(report.blocks, 2);
+    assert_eq!(report.declared_size, report.actual_size);
+    assert!(report.clean(), "{:?}", report);
+    assert_eq!(fixture::blocks(&output), fixture::blocks(&data));
+
+    // A duration rounded by the muxer is still clean, unlike a wrong one.
+    let report = Report { declared_duration: Some(40.4), ..report };
+    assert!(report.clean());
+
+    let report = Report { declared_duration: Some(42.0), ..report };
+    assert!(!report.clean());
+}
+
+#[test]
+fn metadata() {
+    let first = edition(1, true);
+    let intact = attached_file(1, true);
+    let damaged = attached_file(2, false);
+
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        element::master(el::CHAPTERS, vec![first.clone(), edition(2, false)]),
+        element::master(el::ATTACHEMENTS, vec![intact.clone(), damaged.clone()]),
+        fixture::cluster(0, vec![fixture::simple_block(1, 0, true, &[1])]),
+    ]);
+
+    let (output, report) = repair(&data);
+
+    // The damaged edition and attached file are rebuilt without their wrong checksums, while the
+    // others are copied with theirs.
+    assert_eq!(report.rebuilt_elements, 2);
+    assert!(!report.clean());
+
+    assert!(contains(&output, &first));
+    assert!(contains(&output, &intact));
+    assert!(!contains(&output, &damaged));
+
+    let mut reader = Reader::new(&output[..]).unwrap();
+    reader.set_checksum_mode(checksum::Mode::Strict);
+
+    let mut editions = Vec::new();
+    let mut files = Vec::new();
+
+    // The metadata is written after the clusters.
+    reader.info().unwrap();
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            block.unwrap();
+        }
+    }
+
+    for info in reader.info().unwrap() {
+        match info {
+            Info::Chapters(chapters) => editions.extend(chapters),
+            Info::Attachments(attachments) => files.extend(attachments),
+            _ => {},
+        };
+    }
+
+    assert_eq!(editions.iter().map(|edition| edition.uid()).collect::<Vec<_>>(), vec![Some(1), Some(2)]);
+    assert_eq!(files.iter().map(|file| file.uid).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(files[1].data, vec![0xFF, 0xD8, 0xFF, 0xD9]);
+}
+
+#[test]
+fn damaged_metadata() {
+    // Tags whose only child extends past their end, then an element that is not allowed at the
+    // top level, both before the clusters.
+    let mut tags = element::id(el::TAGS);
+    tags.extend(element::size(3));
+    tags.extend(&[0x73, 0x73, 0x88]);
+
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        tags,
+        element::binary(el::BLOCK_GROUP, &[0x00, 0x00]),
+        element::master(el::CHAPTERS, vec![edition(1, true)]),
+        fixture::cluster(0, vec![fixture::simple_block(1, 0, true, &[1])]),
+    ]);
+
+    let (output, report) = repair(&data);
+
+    // The damaged elements are dropped, and the ones that follow them are still copied.
+    assert_eq!(report.dropped_elements.len(), 2);
+    assert!(!report.clean());
+
+    assert_eq!(report.blocks, 1);
+    assert!(contains(&output, &edition(1, true)));
+    assert_eq!(fixture::blocks(&output), vec![(1, 0, true, vec![1])]);
+
+    // The segment information and the tracks are still required.
+    let data = fixture::file(vec![
+        fixture::info(None),
+        fixture::cluster(0, vec![fixture::simple_block(1, 0, true, &[1])]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    let err = repair::repair(&mut reader, Cursor::new(Vec::new())).unwrap_err();
+
+    match *err.base_kind() {
+        ErrorKind::ElementNotFound(id) => assert_eq!(id, el::TRACKS),
+        _ => panic!("unexpected error: {}", err),
+    };
+}
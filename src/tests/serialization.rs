@@ -0,0 +1,43 @@
+//! Serialization tests, run with the `serde` feature.
+
+use serde_json;
+
+use elements as el;
+use reader::tag::{SimpleTag, Tag, Targets};
+use reader::tree::{self, Tree};
+use writer::element;
+
+#[test]
+fn tag_round_trip() {
+    let tag = Tag {
+        targets: Targets {
+            track_uids: vec![1],
+            ..Targets::default()
+        },
+        simple_tags: vec![SimpleTag {
+            name: String::from("TITLE"),
+            language: String::from("und"),
+            default: true,
+            string: Some(String::from("Intro")),
+            binary: None,
+            children: Vec::new(),
+        }],
+    };
+
+    let json = serde_json::to_string(&tag).unwrap();
+    assert_eq!(serde_json::from_str::<Tag>(&json).unwrap(), tag);
+}
+
+#[test]
+fn tree_round_trip() {
+    let data = element::master(el::INFO, vec![
+        element::uint(el::TIMECODE_SCALE, 1000000),
+        element::utf8(el::MUXING_APP, "test"),
+    ]);
+
+    let tree = tree::read(&data[..]);
+    let json = serde_json::to_value(&tree).unwrap();
+
+    assert_eq!(json["elements"][0]["value"]["Master"][0]["value"]["Unsigned"], 1000000);
+    assert_eq!(serde_json::from_value::<Tree>(json).unwrap(), tree);
+}
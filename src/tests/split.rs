@@ -0,0 +1,66 @@
+//! Splitting tests.
+
+use std::io::Cursor;
+
+use elements as el;
+use reader::Reader;
+use split::{self, Mode};
+use tests::fixture;
+use writer::element;
+
+#[test]
+fn timestamps() {
+    // The audio block at 990 ms follows the video keyframe at 1000 ms in the input.
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1), fixture::audio_track(2)]),
+        fixture::cluster(0, vec![
+            fixture::simple_block(1, 0, true, &[1]),
+            fixture::simple_block(2, 10, true, &[2]),
+            fixture::simple_block(1, 500, false, &[3]),
+            fixture::simple_block(1, 1000, true, &[4]),
+            fixture::simple_block(2, 990, true, &[5]),
+            fixture::simple_block(1, 1500, false, &[6]),
+            fixture::simple_block(2, 1510, true, &[7]),
+        ]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    let parts = split::split(&mut reader, &Mode::Timestamps(vec![1000000000]), false, |_| Ok(Cursor::new(Vec::new()))).unwrap();
+
+    assert_eq!(parts.len(), 2);
+
+    let blocks = |part: &Cursor<Vec<u8>>| -> Vec<(u64, i64, u8)> {
+        fixture::blocks(part.get_ref()).into_iter().map(|(track, timecode, _, data)| (track, timecode, data[0])).collect()
+    };
+
+    assert_eq!(blocks(&parts[0]), vec![(1, 0, 1), (2, 10, 2), (1, 500, 3), (2, 990, 5)]);
+    assert_eq!(blocks(&parts[1]), vec![(1, 0, 4), (1, 500, 6), (2, 510, 7)]);
+}
+
+#[test]
+fn linked_parts() {
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+        fixture::cluster(0, vec![
+            fixture::simple_block(1, 0, true, &[1]),
+            fixture::simple_block(1, 1000, true, &[2]),
+            fixture::simple_block(1, 2000, true, &[3]),
+        ]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    let parts = split::split(&mut reader, &Mode::Duration(1000000000), true, |_| Ok(Cursor::new(Vec::new()))).unwrap();
+
+    assert_eq!(parts.len(), 3);
+
+    let infos: Vec<_> = parts.iter()
+        .map(|part| Reader::new(&part.get_ref()[..]).unwrap().header_info().unwrap().0)
+        .collect();
+
+    assert_eq!(infos[0].prev_uid(), None);
+    assert_eq!(infos[1].prev_uid(), infos[0].uid());
+    assert_eq!(infos[1].next_uid(), infos[2].uid());
+    assert_eq!(infos[2].next_uid(), None);
+}
@@ -0,0 +1,33 @@
+//! Stereoscopic plane combining tests.
+
+use reader::track::PlaneType;
+use stereo::Combiner;
+
+#[test]
+fn combine() {
+    let mut combiner = Combiner::new(vec![(1, PlaneType::Left), (2, PlaneType::Right)]);
+
+    combiner.push(1, 0, vec![1]);
+    combiner.push(3, 0, vec![0]);
+    assert_eq!(combiner.pop(), None);
+
+    // The right view at 0 completes the first set. The left view at 40 has no right view.
+    combiner.push(2, 0, vec![2]);
+    combiner.push(1, 40, vec![3]);
+
+    let set = combiner.pop().unwrap();
+    assert_eq!((set.timestamp, set.left, set.right, set.background), (0, Some(vec![1]), Some(vec![2]), None));
+    assert_eq!(combiner.pop(), None);
+
+    // A later complete set makes the incomplete one ready first.
+    combiner.push(2, 80, vec![4]);
+    combiner.push(1, 80, vec![5]);
+
+    let set = combiner.pop().unwrap();
+    assert_eq!((set.timestamp, set.left, set.right), (40, Some(vec![3]), None));
+    assert_eq!(combiner.pop().unwrap().timestamp, 80);
+
+    combiner.push(2, 120, vec![6]);
+    combiner.finish();
+    assert_eq!(combiner.pop().unwrap().right, Some(vec![6]));
+}
@@ -0,0 +1,27 @@
+//! ASS event tests.
+
+use subtitles::ass;
+
+const SCRIPT: &'static [u8] = b"[Script Info]\nScriptType: v4.00+\n\n[Events]\n\
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n";
+
+#[test]
+fn parse_header() {
+    let header = ass::Header::parse(SCRIPT);
+
+    assert_eq!(header.format().len(), 10);
+    assert_eq!(header.format()[3], "Style");
+}
+
+#[test]
+fn parse_event() {
+    let header = ass::Header::parse(SCRIPT);
+    let event = header.parse_event(b"12,0,Default,Alice,0,0,0,,{\\i1}Hello,{\\i0} world\\Nagain");
+
+    assert_eq!(event.read_order, 12);
+    assert_eq!(event.field("style"), Some("Default"));
+    assert_eq!(event.field("Name"), Some("Alice"));
+    assert_eq!(event.field("Start"), None);
+    assert_eq!(event.text(), "{\\i1}Hello,{\\i0} world\\Nagain");
+    assert_eq!(event.plain_text(), "Hello, world\nagain");
+}
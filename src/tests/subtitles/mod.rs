@@ -0,0 +1,6 @@
+//! Subtitle decoding tests.
+
+mod ass;
+mod pgs;
+mod vobsub;
+mod webvtt;
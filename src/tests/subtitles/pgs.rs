@@ -0,0 +1,65 @@
+//! PGS display set tests.
+
+use subtitles::pgs;
+
+/// Encode a segment of type `kind`.
+fn segment(kind: u8, content: &[u8]) -> Vec<u8> {
+    let mut data = vec![kind, (content.len() >> 8) as u8, content.len() as u8];
+    data.extend_from_slice(content);
+
+    data
+}
+
+#[test]
+fn decode() {
+    let mut data = Vec::new();
+
+    // Epoch start of a 1920x1080 video, showing the forced object 1 at (100, 900) and the object
+    // 2 cropped.
+    data.extend(segment(0x16, &[
+        0x07, 0x80, 0x04, 0x38, 0x10, 0x00, 0x01, 0x80, 0x00, 0x03, 0x02,
+        0x00, 0x01, 0x00, 0x40, 0x00, 0x64, 0x03, 0x84,
+        0x00, 0x02, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04,
+    ]));
+
+    data.extend(segment(0x17, &[0x01, 0x00, 0x00, 0x64, 0x03, 0x84, 0x00, 0x04, 0x00, 0x02]));
+    data.extend(segment(0x14, &[0x03, 0x00, 0x01, 0xEB, 0x80, 0x80, 0xFF, 0x02, 0x51, 0x5A, 0xF0, 0x80]));
+
+    // A 4x2 object split in two segments. Line 0: one pixel of colour 1, then a run of three
+    // pixels of colour 2. Line 1: a long run of colour 0.
+    data.extend(segment(0x15, &[0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x10, 0x00, 0x04, 0x00, 0x02, 0x01, 0x00, 0x83]));
+    data.extend(segment(0x15, &[0x00, 0x01, 0x00, 0x40, 0x02, 0x00, 0x00, 0x00, 0x40, 0x04, 0x00, 0x00]));
+    data.extend(segment(0x80, &[]));
+
+    let set = pgs::decode(&data).unwrap();
+
+    assert_eq!((set.width, set.height), (1920, 1080));
+    assert_eq!(set.state, 0x80);
+    assert_eq!(set.palette_id, 3);
+
+    assert_eq!(set.compositions.len(), 2);
+    assert_eq!(set.compositions[0].object_id, 1);
+    assert!(set.compositions[0].forced);
+    assert_eq!((set.compositions[0].x, set.compositions[0].y), (100, 900));
+    assert_eq!(set.compositions[0].crop, None);
+    assert_eq!(set.compositions[1].crop, Some((1, 2, 3, 4)));
+
+    assert_eq!(set.palettes.len(), 1);
+    assert_eq!(set.palettes[0].id, 3);
+    assert_eq!(set.palettes[0].entries, vec![(1, 0xEB, 0x80, 0x80, 0xFF), (2, 0x51, 0x5A, 0xF0, 0x80)]);
+
+    assert_eq!(set.objects.len(), 1);
+    assert_eq!((set.objects[0].id, set.objects[0].width, set.objects[0].height), (1, 4, 2));
+    assert_eq!(set.objects[0].pixels, vec![1, 2, 2, 2, 0, 0, 0, 0]);
+}
+
+#[test]
+fn invalid() {
+    // Unknown segment type, then a segment longer than the data.
+    assert!(pgs::decode(&segment(0x42, &[])).is_err());
+    assert!(pgs::decode(&[0x14, 0x00, 0x10, 0x00]).is_err());
+
+    // Object whose bitmap ends before its last line.
+    let object = segment(0x15, &[0x00, 0x01, 0x00, 0xC0, 0x00, 0x00, 0x10, 0x00, 0x04, 0x00, 0x02, 0x01, 0x00, 0x00]);
+    assert!(pgs::decode(&object).is_err());
+}
@@ -0,0 +1,69 @@
+//! VobSub subpicture tests.
+
+use subtitles::vobsub::{self, Index};
+
+const IDX: &'static [u8] = b"# VobSub index file, v7\nsize: 720x576\n\
+palette: 000000, ffffff, 808080, ff0000, 00ff00, 0000ff, 000000, 000000, 000000, 000000, 000000, \
+000000, 000000, 000000, 000000, 000000\n";
+
+/// Build a 4x2 subpicture unit, displayed 100 ticks of 1024/90000 s, with two control sequences.
+fn subpicture() -> Vec<u8> {
+    // Line 0, in the first field: runs of two pixels of values 1 and 2. Line 1, in the second
+    // field: a run filling the line with value 3.
+    let mut data = vec![0x00, 0x00, 0x00, 0x07, 0x9A, 0x00, 0x03];
+
+    // First control sequence: forced, start, colours, alpha, area and field offsets.
+    data.extend_from_slice(&[0x00, 0x00, 0x00, 0x20]);
+    data.extend_from_slice(&[0x00, 0x01]);
+    data.extend_from_slice(&[0x03, 0x01, 0x23]);
+    data.extend_from_slice(&[0x04, 0xF0, 0xA0]);
+    data.extend_from_slice(&[0x05, 0x00, 0xA0, 0x0D, 0x01, 0x40, 0x15]);
+    data.extend_from_slice(&[0x06, 0x00, 0x04, 0x00, 0x05]);
+    data.push(0xFF);
+
+    // Last control sequence, pointing to itself: stop.
+    data.extend_from_slice(&[0x00, 0x64, 0x00, 0x20, 0x02, 0xFF]);
+
+    let len = data.len();
+    data[0] = (len >> 8) as u8;
+    data[1] = len as u8;
+
+    data
+}
+
+#[test]
+fn parse_index() {
+    let index = Index::parse(IDX);
+
+    assert_eq!((index.width, index.height), (720, 576));
+    assert_eq!(index.palette.len(), 16);
+    assert_eq!(&index.palette[..4], &[0x000000, 0xFFFFFF, 0x808080, 0xFF0000]);
+}
+
+#[test]
+fn decode() {
+    let sub = vobsub::decode(&subpicture(), &Index::parse(IDX)).unwrap();
+
+    assert!(sub.forced);
+    assert_eq!(sub.start, 0);
+    assert_eq!(sub.stop, Some(100 * 1024 * 1000000000 / 90000));
+
+    assert_eq!((sub.x, sub.y, sub.width, sub.height), (10, 20, 4, 2));
+    assert_eq!(sub.colours, [0xFF0000, 0x808080, 0xFFFFFF, 0x000000]);
+    assert_eq!(sub.alpha, [0x0, 0xA, 0x0, 0xF]);
+
+    // The lines are taken from the two fields in turn.
+    assert_eq!(sub.pixels, vec![1, 1, 2, 2, 3, 3, 3, 3]);
+}
+
+#[test]
+fn truncated() {
+    let data = subpicture();
+
+    // Cut in the middle of the control sequences, then with the second field past the end.
+    assert!(vobsub::decode(&data[..20], &Index::parse(IDX)).is_err());
+
+    let mut data = data;
+    data[29] = 0x01;
+    assert!(vobsub::decode(&data, &Index::parse(IDX)).is_err());
+}
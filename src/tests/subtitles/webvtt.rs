@@ -0,0 +1,25 @@
+//! WebVTT cue tests.
+
+use subtitles::webvtt::Cue;
+
+#[test]
+fn parse() {
+    let cue = Cue::parse(b"Hello <b>world</b> &amp; all", Some(b"align:start line:0\nNOTE first comment\n\nNOTE\nsecond\ncomment\n"));
+
+    assert_eq!(cue.text, "Hello <b>world</b> &amp; all");
+    assert_eq!(cue.plain_text(), "Hello world & all");
+    assert_eq!(cue.settings, Some(String::from("align:start line:0")));
+    assert_eq!(cue.comments, vec![String::from("first comment"), String::from("second\ncomment")]);
+}
+
+#[test]
+fn without_settings() {
+    // Comments without settings start with an empty line.
+    let cue = Cue::parse(b"text", Some(b"\nNOTE only comment"));
+    assert_eq!(cue.settings, None);
+    assert_eq!(cue.comments, vec![String::from("only comment")]);
+
+    let cue = Cue::parse(b"text", None);
+    assert_eq!(cue.settings, None);
+    assert!(cue.comments.is_empty());
+}
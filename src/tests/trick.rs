@@ -0,0 +1,40 @@
+//! Trick play tests.
+
+use std::io::Cursor;
+
+use index::Index;
+use trick::TrickPlay;
+
+/// Serialized index of the track 3, with a one-byte frame per second at the start of the file.
+fn index() -> Index {
+    let mut data = b"MKVIDX\x01".to_vec();
+    data.extend_from_slice(&[0, 0, 0, 1]);
+    data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 4]);
+
+    for i in 0..4u64 {
+        let pts = i * 1000000000;
+
+        data.extend((0..8).rev().map(|shift| (pts >> (8 * shift)) as u8));
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, i as u8, 0, 0, 0, 1, 0, 0, 0, 0, 0x01]);
+    }
+
+    Index::read(&mut &data[..]).unwrap()
+}
+
+fn frames(play: &mut TrickPlay<Cursor<Vec<u8>>>, start: i64, speed: f64) -> Vec<u8> {
+    play.frames(start, speed, 1.0).map(|frame| frame.unwrap().data[0]).collect()
+}
+
+#[test]
+fn speeds() {
+    let mut play = TrickPlay::from_index(Cursor::new(vec![10, 11, 12, 13]), &index(), 3).unwrap();
+    assert_eq!(play.timestamps(), vec![0, 1000000000, 2000000000, 3000000000]);
+
+    // Frames are not repeated, and playback stops at either end.
+    assert_eq!(frames(&mut play, 0, 2.0), vec![10, 12, 13]);
+    assert_eq!(frames(&mut play, 2500000000, -1.0), vec![12, 11, 10]);
+    assert_eq!(frames(&mut play, 0, 0.5), vec![10, 11, 12, 13]);
+
+    assert!(TrickPlay::from_index(Cursor::new(Vec::new()), &index(), 1).is_err());
+}
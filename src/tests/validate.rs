@@ -0,0 +1,100 @@
+//! Structural validation tests.
+
+use elements as el;
+use validate::{self, Severity};
+use writer::{self, element};
+
+/// Build a WebM file with a single video track using `codec`, and a cluster whose only block is a
+/// keyframe if `keyframe` is set.
+fn file(codec: &str, keyframe: bool) -> Vec<u8> {
+    let info = element::master(el::INFO, vec![
+        element::uint(el::TIMECODE_SCALE, 1000000),
+        element::utf8(el::MUXING_APP, "test"),
+        element::utf8(el::WRITING_APP, "test"),
+    ]);
+
+    let tracks = element::master(el::TRACKS, vec![
+        element::master(el::TRACK_ENTRY, vec![
+            element::uint(el::TRACK_NUMBER, 1),
+            element::uint(el::TRACK_UID, 1),
+            element::uint(el::TRACK_TYPE, 1),
+            element::utf8(el::CODEC_ID, codec),
+            element::master(el::VIDEO, vec![
+                element::uint(el::PIXEL_WIDTH, 640),
+                element::uint(el::PIXEL_HEIGHT, 480),
+            ]),
+        ]),
+    ]);
+
+    let cluster_position = (info.len() + tracks.len()) as u64;
+    let flags = if keyframe { 0x80 } else { 0x00 };
+
+    let cluster = element::master(el::CLUSTER, vec![
+        element::uint(el::TIMECODE, 0),
+        element::binary(el::SIMPLE_BLOCK, &[0x81, 0x00, 0x00, flags, 0x2A]),
+    ]);
+
+    let cues = element::master(el::CUES, vec![
+        element::master(el::CUE_POINT, vec![
+            element::uint(el::CUE_TIME, 0),
+            element::master(el::CUE_TRACK_POSITIONS, vec![
+                element::uint(el::CUE_TRACK, 1),
+                element::uint(el::CUE_CLUSTER_POSITION, cluster_position),
+            ]),
+        ]),
+    ]);
+
+    let mut segment = info;
+    segment.extend(tracks);
+    segment.extend(cluster);
+    segment.extend(cues);
+
+    let mut data = writer::encode_header("webm");
+    data.extend(element::element(el::SEGMENT, &segment));
+
+    data
+}
+
+#[test]
+fn valid_file() {
+    let findings = validate::validate(&file("V_VP9", true)[..]).unwrap();
+    assert!(findings.is_empty());
+}
+
+#[test]
+fn invalid_files() {
+    let findings = validate::validate(&file("V_MPEG4/ISO/AVC", false)[..]).unwrap();
+    assert_eq!(findings.len(), 2);
+
+    assert!(findings[0].severity == Severity::Error);
+    assert_eq!(findings[0].element, el::CODEC_ID);
+
+    assert!(findings[1].severity == Severity::Warning);
+    assert_eq!(findings[1].element, el::SIMPLE_BLOCK);
+
+    // Truncate the file in the middle of the cluster.
+    let mut data = file("V_VP9", true);
+    let len = data.len() - 30;
+    data.truncate(len);
+
+    let findings = validate::validate(&data[..]).unwrap();
+    assert!(findings.iter().any(|finding| finding.message == "truncated file"));
+}
+
+#[test]
+fn deeply_nested() {
+    let mut tags = element::master(el::TAGS, vec![]);
+    for _ in 0..1000 {
+        tags = element::master(el::TAGS, vec![tags]);
+    }
+
+    let mut data = writer::encode_header("webm");
+    data.extend(element::master(el::SEGMENT, vec![tags]));
+
+    // Elements nested deeper than the reader limit are reported instead of being validated.
+    let findings = validate::validate(&data[..]).unwrap();
+    let nested: Vec<_> = findings.iter().filter(|finding| finding.message == "element nested deeper than 32 levels").collect();
+
+    assert_eq!(nested.len(), 1);
+    assert_eq!(nested[0].element, el::TAGS);
+}
@@ -0,0 +1,60 @@
+//! EBML element encoding tests.
+
+use writer::element;
+
+#[test]
+fn encode_ids() {
+    assert_eq!(element::id(0xEC), vec![0xEC]);
+    assert_eq!(element::id(0x4286), vec![0x42, 0x86]);
+    assert_eq!(element::id(0x18538067), vec![0x18, 0x53, 0x80, 0x67]);
+}
+
+#[test]
+fn encode_sizes() {
+    assert_eq!(element::size(0), vec![0x80]);
+    assert_eq!(element::size(126), vec![0xFE]);
+    assert_eq!(element::size(127), vec![0x40, 0x7F]);
+    assert_eq!(element::size(0x3FFE), vec![0x7F, 0xFE]);
+    assert_eq!(element::vint(2, 8), vec![0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02]);
+}
+
+#[test]
+fn encode_values() {
+    assert_eq!(element::uint(0xD7, 1), vec![0xD7, 0x81, 0x01]);
+    assert_eq!(element::uint(0x2AD7B1, 1000000), vec![0x2A, 0xD7, 0xB1, 0x83, 0x0F, 0x42, 0x40]);
+    assert_eq!(element::int(0xFB, -1), vec![0xFB, 0x81, 0xFF]);
+    assert_eq!(element::int(0xFB, 128), vec![0xFB, 0x82, 0x00, 0x80]);
+    assert_eq!(element::utf8(0x4282, "webm"), vec![0x42, 0x82, 0x84, 0x77, 0x65, 0x62, 0x6D]);
+    assert_eq!(element::float(0x4489, 1.0), vec![0x44, 0x89, 0x88, 0x3F, 0xF0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn encode_voids() {
+    assert_eq!(element::void(2), vec![0xEC, 0x80]);
+    assert_eq!(element::void(127).len(), 127);
+    assert_eq!(element::void(128).len(), 128);
+    assert_eq!(element::void(256)[1], 0x01);
+}
+
+#[test]
+fn encode_attachments() {
+    use reader::attachment::File;
+    use writer;
+
+    let file = File {
+        uid: 1,
+        name: String::from("a"),
+        mime_type: String::from("b"),
+        description: None,
+        data: vec![0x2A],
+    };
+
+    assert_eq!(writer::encode_attachments(&[file]), vec![
+        0x19, 0x41, 0xA4, 0x69, 0x93,
+        0x61, 0xA7, 0x90,
+        0x46, 0x6E, 0x81, 0x61,
+        0x46, 0x60, 0x81, 0x62,
+        0x46, 0x5C, 0x81, 0x2A,
+        0x46, 0xAE, 0x81, 0x01,
+    ]);
+}
@@ -0,0 +1,174 @@
+//! Tests reading back the files written by `Writer` and `remux`.
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use elements as el;
+use reader::{Info, Reader};
+use remux;
+use tests::fixture;
+use writer::{element, Writer};
+
+fn input() -> Vec<u8> {
+    fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1), fixture::audio_track(2)]),
+        fixture::cluster(0, vec![
+            fixture::simple_block(1, 0, true, &[1]),
+            fixture::simple_block(2, 0, true, &[2]),
+            fixture::simple_block(1, 40, false, &[3]),
+        ]),
+        fixture::cluster(6000, vec![
+            fixture::simple_block(1, 0, true, &[4]),
+            fixture::simple_block(2, 20, true, &[5]),
+        ]),
+    ])
+}
+
+/// Copy the blocks of `data` into a new file with `Writer`.
+fn rewrite(data: &[u8]) -> Vec<u8> {
+    let mut reader = Reader::new(data).unwrap();
+    let (info, tracks) = reader.header_info().unwrap();
+    let mut writer = Writer::new(Cursor::new(Vec::new()), "webm", &info, &tracks).unwrap();
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            let block = block.unwrap();
+            let timecode = block.absolute_timecode();
+
+            writer.write_block(block, timecode).unwrap();
+        }
+    }
+
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn round_trip() {
+    let data = input();
+    let output = rewrite(&data);
+
+    assert_eq!(fixture::blocks(&output), fixture::blocks(&data));
+
+    let mut reader = Reader::new(&output[..]).unwrap();
+    let segment_offset = reader.segment_offset();
+
+    let mut seek_head = None;
+    let mut duration = None;
+
+    for info in reader.info().unwrap() {
+        match info {
+            Info::MetaSeek(info) => seek_head = Some(info),
+            Info::Segment(info) => duration = info.duration(),
+            _ => {},
+        };
+    }
+
+    // The duration is the end of the last block.
+    assert_eq!(duration, Some(6020.0));
+
+    let seek_head = seek_head.unwrap();
+    assert!(seek_head.contains_key(&el::INFO));
+    assert!(seek_head.contains_key(&el::TRACKS));
+
+    let cues_position = segment_offset + seek_head[&el::CUES] as usize;
+    assert_eq!(&output[cues_position..cues_position + 4], &[0x1C, 0x53, 0xBB, 0x6B]);
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            block.unwrap();
+        }
+    }
+
+    let cues = match reader.info().unwrap().into_iter().next() {
+        Some(Info::Cues(cues)) => cues,
+        _ => panic!("no cues after the clusters"),
+    };
+
+    // The keyframes of the video track are indexed, and each cue point references its cluster.
+    assert_eq!(cues.iter().map(|cue| cue.time).collect::<Vec<_>>(), vec![0, 6000]);
+
+    for cue in &cues {
+        assert_eq!(cue.positions[0].track, 1);
+
+        let position = segment_offset + cue.positions[0].cluster_position as usize;
+        assert_eq!(&output[position..position + 4], &[0x1F, 0x43, 0xB6, 0x75]);
+    }
+}
+
+#[test]
+fn zero_timecode_scale() {
+    let data = fixture::file(vec![
+        element::master(el::INFO, vec![element::uint(el::TIMECODE_SCALE, 0)]),
+        element::master(el::TRACKS, vec![fixture::video_track(1)]),
+    ]);
+
+    let (info, tracks) = Reader::new(&data[..]).unwrap().header_info().unwrap();
+    assert!(Writer::new(Cursor::new(Vec::new()), "webm", &info, &tracks).is_err());
+}
+
+#[test]
+fn remux_metadata() {
+    let tag = |track_uid: u64| element::master(el::TAG, vec![
+        element::master(el::TARGETS, vec![element::uint(el::TAG_TRACK_UID, track_uid)]),
+        element::master(el::SIMPLE_TAG, vec![
+            element::utf8(el::TAG_NAME, "TITLE"),
+            element::utf8(el::TAG_STRING, "x"),
+        ]),
+    ]);
+
+    let chapters = element::master(el::CHAPTERS, vec![
+        element::master(el::EDITION_ENTRY, vec![
+            element::master(el::CHAPTER_ATOM, vec![
+                element::uint(el::CHAPTER_UID, 7),
+                element::uint(el::CHAPTER_TIME_START, 0),
+            ]),
+        ]),
+    ]);
+
+    // Chapters and tags located after the clusters.
+    let data = fixture::file(vec![
+        fixture::info(None),
+        element::master(el::TRACKS, vec![fixture::video_track(1), fixture::audio_track(2)]),
+        fixture::cluster(0, vec![
+            fixture::simple_block(1, 0, true, &[1]),
+            fixture::simple_block(2, 0, true, &[2]),
+        ]),
+        chapters,
+        element::master(el::TAGS, vec![tag(1), tag(2)]),
+    ]);
+
+    let mut reader = Reader::new(&data[..]).unwrap();
+    let output = remux::remux(&mut reader, Cursor::new(Vec::new()), &[1], &HashMap::new()).unwrap().into_inner();
+
+    let blocks = fixture::blocks(&output);
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].0, 1);
+
+    let mut reader = Reader::new(&output[..]).unwrap();
+    reader.info().unwrap();
+
+    while let Some(mut cluster) = reader.next_cluster().unwrap() {
+        for block in cluster.blocks() {
+            block.unwrap();
+        }
+    }
+
+    let mut chapters = None;
+    let mut tags = None;
+
+    for info in reader.info().unwrap() {
+        match info {
+            Info::Chapters(info) => chapters = Some(info),
+            Info::Tags(info) => tags = Some(info),
+            _ => {},
+        };
+    }
+
+    assert_eq!(chapters.unwrap()[0].atoms()[0].uid(), 7);
+
+    // The tag of the dropped audio track is not copied.
+    let tags = tags.unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].targets.track_uids, vec![1]);
+}
@@ -0,0 +1,4 @@
+//! Writer unit tests.
+
+mod element;
+mod file;
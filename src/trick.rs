@@ -0,0 +1,351 @@
+//! Smooth fast-forward and rewind, or trick play, using the trick track of a video track when
+//! there is one, and its keyframes referenced by the cues otherwise.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use elements as el;
+use error::{self, Result};
+use index::Index;
+use reader::{Info, Reader};
+use reader::cluster::Block;
+use reader::cues::{CuePoint, TrackPosition};
+use reader::limits::ReaderLimits;
+use reader::track;
+use reader::tree;
+
+/// A frame shown during trick play.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrickFrame {
+    /// Presentation timestamp in nanoseconds.
+    pub timestamp: i64,
+
+    pub data: Vec<u8>,
+}
+
+/// Location of a frame that can be shown during trick play.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Location {
+    /// Position of the frame data in the file, and its size in bytes.
+    Frame(u64, u32),
+
+    /// Keyframe referenced by a cue point, with the time of the cue point.
+    Cue(TrackPosition, u64),
+}
+
+/// Frames of a track that can be shown in any order, at any speed.
+pub struct TrickPlay<R: Read + Seek> {
+    r: R,
+    track: u64,
+    segment_offset: u64,
+    limits: ReaderLimits,
+
+    // Frames in the order of their timestamps, in nanoseconds.
+    entries: Vec<(i64, Location)>,
+}
+
+/// Find the trick track of the track `master` among `tracks`, which are those of the segment
+/// whose UID is `segment_uid`. Trick tracks of other segments are ignored.
+pub fn trick_track<'a>(master: &track::Info, tracks: &'a [track::Info], segment_uid: Option<&[u8]>) -> Option<&'a track::Info> {
+    let local = |uid: &Option<Vec<u8>>| uid.as_ref().map_or(true, |uid| Some(uid.as_slice()) == segment_uid);
+    let trick = master.trick();
+
+    match trick.track_uid {
+        Some(uid) if local(&trick.track_segment_uid) => tracks.iter().find(|track| track.uid() == uid),
+        Some(_) => None,
+
+        None => tracks.iter().find(|track| {
+            let trick = track.trick();
+            trick.trick_track && trick.master_track_uid == Some(master.uid()) && local(&trick.master_track_segment_uid)
+        }),
+    }
+}
+
+impl<R: Read + Seek> TrickPlay<R> {
+    /// Read the file `r` to prepare the trick play of its track number `master`. The frames of
+    /// its trick track are indexed if it has one. Otherwise, its keyframes are found in the cues,
+    /// which may require reading the whole file when they are located after the clusters.
+    pub fn open(mut r: R, master: u64) -> Result<TrickPlay<R>> {
+        r.seek(SeekFrom::Start(0))?;
+
+        let (segment, tracks, cues, segment_offset) = {
+            let mut reader = Reader::new(&mut r)?;
+            let mut segment = None;
+            let mut tracks = None;
+            let mut cues = None;
+
+            for info in reader.info()? {
+                match info {
+                    Info::Segment(info) => segment = Some(info),
+                    Info::Tracks(info) => tracks = Some(info),
+                    Info::Cues(info) => cues = Some(info),
+                    _ => {},
+                };
+            }
+
+            let segment = segment.ok_or(error::not_found(el::INFO))?;
+            let tracks = tracks.ok_or(error::not_found(el::TRACKS))?;
+
+            let master = tracks.iter().find(|track| track.index() == master)
+                .ok_or(error::invalid_value(el::TRACK_NUMBER, master))?;
+
+            if trick_track(master, &tracks, segment.uid()).is_some() {
+                (segment, tracks, None, reader.segment_offset())
+            } else {
+                // Read through the clusters to the cues that follow them.
+                while cues.is_none() {
+                    while let Some(mut cluster) = reader.next_cluster()? {
+                        for block in cluster.blocks() {
+                            block?;
+                        }
+                    }
+
+                    let info = reader.info()?;
+
+                    if info.is_empty() {
+                        break;
+                    }
+
+                    for info in info {
+                        if let Info::Cues(info) = info {
+                            cues = Some(info);
+                        }
+                    }
+                }
+
+                (segment, tracks, cues, reader.segment_offset())
+            }
+        };
+
+        match cues {
+            Some(cues) => Ok(TrickPlay::from_cues(r, &cues, master, segment_offset as u64, segment.timecode_scale())),
+
+            None => {
+                let master = tracks.iter().find(|track| track.index() == master).unwrap();
+
+                let trick = match trick_track(master, &tracks, segment.uid()) {
+                    Some(trick) => trick.index(),
+                    None => bail!(error::not_found(el::CUES)),
+                };
+
+                r.seek(SeekFrom::Start(0))?;
+                let index = Index::build(&mut Reader::new(&mut r)?)?;
+
+                TrickPlay::from_index(r, &index, trick)
+            },
+        }
+    }
+
+    /// Play the frames of the track number `track`, usually a trick track, from the sample table
+    /// `index` of the file `r`.
+    pub fn from_index(r: R, index: &Index, track: u64) -> Result<TrickPlay<R>> {
+        let samples = index.samples(track).ok_or(error::invalid_value(el::TRACK_NUMBER, track))?;
+
+        let mut entries: Vec<(i64, Location)> = samples.iter()
+            .map(|sample| (sample.pts, Location::Frame(sample.offset, sample.size)))
+            .collect();
+
+        entries.sort_by_key(|&(timestamp, _)| timestamp);
+
+        Ok(TrickPlay {
+            r: r,
+            track: track,
+            segment_offset: 0,
+            limits: ReaderLimits::default(),
+            entries: entries,
+        })
+    }
+
+    /// Play the keyframes of the track number `track` referenced by `cues`. `segment_offset` is
+    /// the position of the segment data in the file `r`, and `scale` the timestamp scale of the
+    /// segment.
+    pub fn from_cues(r: R, cues: &[CuePoint], track: u64, segment_offset: u64, scale: u64) -> TrickPlay<R> {
+        let mut entries: Vec<(i64, Location)> = cues.iter()
+            .flat_map(|cue| cue.positions.iter()
+                .filter(|position| position.track == track)
+                .map(move |position| ((cue.time * scale) as i64, Location::Cue(*position, cue.time))))
+            .collect();
+
+        entries.sort_by_key(|&(timestamp, _)| timestamp);
+
+        TrickPlay {
+            r: r,
+            track: track,
+            segment_offset: segment_offset,
+            limits: ReaderLimits::default(),
+            entries: entries,
+        }
+    }
+
+    /// Returns the timestamps of the frames that can be shown, in nanoseconds.
+    pub fn timestamps(&self) -> Vec<i64> {
+        self.entries.iter().map(|&(timestamp, _)| timestamp).collect()
+    }
+
+    /// Returns the frames to show when playing from `start`, in nanoseconds, at `speed` times the
+    /// normal speed, which is negative to rewind, with `rate` frames shown per second. Each frame
+    /// is the last one at or before the playback position, and is not repeated.
+    pub fn frames<'a>(&'a mut self, start: i64, speed: f64, rate: f64) -> TrickFrames<'a, R> {
+        TrickFrames {
+            play: self,
+            position: start as f64,
+            step: speed * 1e9 / rate,
+            last: None,
+        }
+    }
+
+    /// Read the frame at `index` in the entries.
+    fn read(&mut self, index: usize) -> Result<TrickFrame> {
+        let (timestamp, location) = self.entries[index];
+
+        let data = match location {
+            Location::Frame(offset, size) => {
+                self.r.seek(SeekFrom::Start(offset))?;
+                tree::read_data(&mut self.r, size as u64)?
+            },
+
+            Location::Cue(position, time) => self.read_cue(&position, time)?,
+        };
+
+        Ok(TrickFrame {
+            timestamp: timestamp,
+            data: data,
+        })
+    }
+
+    /// Read the first frame of the block referenced by a cue point at `time`.
+    fn read_cue(&mut self, position: &TrackPosition, time: u64) -> Result<Vec<u8>> {
+        self.r.seek(SeekFrom::Start(self.segment_offset + position.cluster_position))?;
+
+        let (id, size, _) = tree::read_header(&mut self.r)?.ok_or(error::unexpected_eof())?;
+        if id != el::CLUSTER {
+            bail!(error::unexpected(el::CLUSTER, id));
+        }
+
+        if let Some(relative) = position.relative_position {
+            self.r.seek(SeekFrom::Current(relative as i64))?;
+
+            let (id, size, _) = tree::read_header(&mut self.r)?.ok_or(error::unexpected_eof())?;
+
+            return match self.read_block(id, size)? {
+                Some(block) => first_frame(block),
+                None => bail!(error::unexpected(el::SIMPLE_BLOCK, id)),
+            };
+        }
+
+        // Without a relative position, the block is searched in the cluster by its timecode.
+        let mut remaining = size;
+        let mut cluster_timecode = 0;
+
+        while remaining.map_or(true, |remaining| remaining > 0) {
+            let (id, size, c) = match tree::read_header(&mut self.r)? {
+                Some(header) => header,
+                None => break,
+            };
+
+            let size = size.ok_or(error::invalid_value(id, "unknown size"))?;
+            remaining = remaining.map(|remaining| remaining.saturating_sub(c as u64 + size));
+
+            match id {
+                el::TIMECODE => cluster_timecode = tree::uint(&tree::read_data(&mut self.r, size)?),
+                el::CLUSTER | el::CUES => break,
+
+                _ => match self.read_block(id, Some(size))? {
+                    Some(block) => {
+                        if block.track() == self.track && cluster_timecode as i64 + block.timecode() as i64 == time as i64 {
+                            return first_frame(block);
+                        }
+                    },
+
+                    None => {
+                        self.r.seek(SeekFrom::Current(size as i64))?;
+                    },
+                },
+            };
+        }
+
+        bail!(error::not_found(el::SIMPLE_BLOCK));
+    }
+
+    /// Read the block in the element `id` of `size` bytes, if it is a `SimpleBlock` or a
+    /// `BlockGroup`. Other elements are left unread.
+    fn read_block(&mut self, id: u64, size: Option<u64>) -> Result<Option<Block>> {
+        if id != el::SIMPLE_BLOCK && id != el::BLOCK_GROUP {
+            return Ok(None);
+        }
+
+        let size = size.ok_or(error::invalid_value(id, "unknown size"))?;
+        self.limits.check("block size", size as usize, self.limits.max_block_size)?;
+
+        let data = tree::read_data(&mut self.r, size)?;
+
+        if id == el::SIMPLE_BLOCK {
+            return Ok(Some(Block::from_binary(id, data)?));
+        }
+
+        let mut r = &data[..];
+
+        while let Some((id, size, _)) = tree::read_header(&mut r)? {
+            let size = size.map_or(r.len(), |size| size as usize);
+
+            if size > r.len() {
+                bail!(error::invalid_value(id, "element larger than its parent"));
+            }
+
+            if id == el::BLOCK {
+                return Ok(Some(Block::from_binary(id, r[..size].to_vec())?));
+            }
+
+            r = &r[size..];
+        }
+
+        bail!(error::not_found(el::BLOCK));
+    }
+}
+
+/// Return the first frame of `block`.
+fn first_frame(block: Block) -> Result<Vec<u8>> {
+    block.frames()?.into_iter().next().ok_or(error::unexpected_eof())
+}
+
+/// Iterator over the frames of a trick play at a given speed.
+pub struct TrickFrames<'a, R: Read + Seek + 'a> {
+    play: &'a mut TrickPlay<R>,
+
+    // Playback position and its increment between two frames shown, in nanoseconds.
+    position: f64,
+    step: f64,
+
+    last: Option<usize>,
+}
+
+impl<'a, R: Read + Seek + 'a> Iterator for TrickFrames<'a, R> {
+    type Item = Result<TrickFrame>;
+
+    fn next(&mut self) -> Option<Result<TrickFrame>> {
+        let len = self.play.entries.len();
+
+        if len == 0 || self.step == 0.0 {
+            return None;
+        }
+
+        loop {
+            let position = self.position;
+            self.position += self.step;
+
+            // The frame at or before the position, or the first frame before the start.
+            let count = self.play.entries.iter().take_while(|&&(timestamp, _)| timestamp as f64 <= position).count();
+            let index = count.saturating_sub(1);
+
+            if Some(index) != self.last {
+                self.last = Some(index);
+                return Some(self.play.read(index));
+            }
+
+            // The same frame is not shown twice, which ends the playback at either end.
+            if (self.step > 0.0 && index == len - 1) || (self.step < 0.0 && index == 0) {
+                return None;
+            }
+        }
+    }
+}
@@ -0,0 +1,587 @@
+//! Structural validation of matroska and WebM files, in the manner of mkvalidator.
+
+use std::io::{self, Read};
+
+use ebml::types::*;
+
+use elements as el;
+use error::Result;
+use reader::limits::ReaderLimits;
+use reader::resync::Input;
+use reader::tree::{eof, invalid, read_data, read_header, read_vint, uint};
+use schema::{self, Type};
+
+/// Severity of a finding. Errors make the file invalid, warnings point at likely problems, such as
+/// a file that cannot be seeked into.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// Problem found in a file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Finding {
+    pub severity: Severity,
+
+    /// Position of the offending element in the file, in bytes, and its ID.
+    pub offset: u64,
+    pub element: ElementId,
+
+    pub message: String,
+}
+
+/// Set of elements and codecs that a file may use, chosen from its document type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Profile {
+    Matroska,
+    WebM,
+}
+
+/// Boolean flags, which must be 0 or 1.
+const FLAGS: &'static [ElementId] = &[
+    el::FLAG_ENABLED, el::FLAG_DEFAULT, el::FLAG_FORCED, el::FLAG_LACING, el::EDITION_FLAG_HIDDEN,
+    el::EDITION_FLAG_DEFAULT, el::EDITION_FLAG_ORDERED, el::CHAPTER_FLAG_HIDDEN,
+    el::CHAPTER_FLAG_ENABLED, el::TAG_DEFAULT,
+];
+
+/// Unsigned integers that must not be 0.
+const NON_ZERO: &'static [ElementId] = &[
+    el::TRACK_NUMBER, el::TRACK_UID, el::TIMECODE_SCALE, el::DEFAULT_DURATION, el::PIXEL_WIDTH,
+    el::PIXEL_HEIGHT, el::CHANNELS, el::CUE_TRACK, el::FILE_UID, el::CHAPTER_UID,
+];
+
+const FLOATS: &'static [ElementId] = &[el::DURATION, el::SAMPLING_FREQUENCY, el::OUTPUT_SAMPLING_FREQUENCY];
+
+const TRACK_TYPES: &'static [u64] = &[0x01, 0x02, 0x03, 0x10, 0x11, 0x12, 0x20, 0x21];
+
+/// Elements that are not part of the WebM subset of matroska.
+const NOT_WEBM: &'static [ElementId] = &[
+    el::SEGMENT_FAMILY, el::PREV_UID, el::PREV_FILENAME, el::NEXT_UID, el::NEXT_FILENAME,
+    el::CHAPTER_TRANSLATE, el::TRACK_TRANSLATE, el::TRACK_OFFSET, el::TRACK_OVERLAY,
+    el::ATTACHMENT_LINK, el::CODEC_SETTINGS, el::CODEC_INFO_URL, el::CODEC_DOWNLOAD_URL,
+    el::CODEC_DECODE_ALL, el::CONTENT_COMPRESSION, el::ATTACHEMENTS, el::SILENT_TRACKS,
+    el::BLOCK_VIRTUAL, el::REFERENCE_VIRTUAL, el::CODEC_STATE, el::SLICES, el::ENCRYPTED_BLOCK,
+];
+
+const WEBM_CODECS: &'static [&'static str] = &[
+    "V_VP8", "V_VP9", "V_AV1", "A_VORBIS", "A_OPUS", "D_WEBVTT/SUBTITLES", "D_WEBVTT/CAPTIONS",
+    "D_WEBVTT/DESCRIPTIONS", "D_WEBVTT/METADATA",
+];
+
+/// Element located in a buffer.
+struct Node<'a> {
+    id: ElementId,
+
+    // Absolute position of the element, and of its content.
+    offset: u64,
+    data_offset: u64,
+    data: &'a [u8],
+}
+
+struct Track {
+    number: u64,
+    video: bool,
+    last_timecode: Option<i64>,
+}
+
+/// Validate the file read from `r` against the matroska specification, or against the WebM
+/// profile if its document type is `webm`. Returns the findings in the order of the file. Only
+/// I/O errors fail the validation: a truncated file results in a finding, as do elements nested
+/// deeper than the default `ReaderLimits::max_depth`.
+pub fn validate<R: Read>(r: R) -> Result<Vec<Finding>> {
+    let mut input = Input::new(r);
+    let mut validator = Validator {
+        profile: Profile::Matroska,
+        max_depth: ReaderLimits::default().max_depth,
+        findings: Vec::new(),
+        segment_offset: 0,
+        top_level: Vec::new(),
+        clusters: Vec::new(),
+        tracks: Vec::new(),
+        seeks: Vec::new(),
+        cues: Vec::new(),
+    };
+
+    match validator.run(&mut input) {
+        Ok(()) => {},
+        Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+            validator.error(input.position() as u64, el::SEGMENT, "truncated file");
+        },
+        Err(ref err) if err.kind() == io::ErrorKind::InvalidData => {
+            validator.error(input.position() as u64, el::SEGMENT, err.to_string());
+        },
+        Err(err) => return Err(err.into()),
+    };
+
+    validator.check_references();
+    Ok(validator.findings)
+}
+
+struct Validator {
+    profile: Profile,
+    max_depth: usize,
+    findings: Vec<Finding>,
+
+    // Absolute position of the segment data, and positions of the top-level elements relative to
+    // it.
+    segment_offset: u64,
+    top_level: Vec<(u64, ElementId)>,
+    clusters: Vec<u64>,
+
+    tracks: Vec<Track>,
+
+    // Entries of the `SeekHead` and the cues, with their absolute position, to check once all the
+    // top-level elements are known.
+    seeks: Vec<(u64, ElementId, u64)>,
+    cues: Vec<(u64, u64, u64)>,
+}
+
+impl Validator {
+    fn run<R: Read>(&mut self, input: &mut Input<R>) -> io::Result<()> {
+        let (id, size, len) = read_header(input)?.ok_or(eof())?;
+        if id != el::EBML {
+            self.error(0, id, "missing EBML header");
+            return Ok(());
+        }
+
+        let data = read_data(input, size.ok_or(invalid("the EBML header must have a known size"))?)?;
+        let doc_type = parse_children(&data, len as u64, &mut self.findings).into_iter()
+            .find(|node| node.id == el::DOC_TYPE)
+            .map(|node| String::from_utf8_lossy(node.data).trim_end_matches('\0').to_string());
+
+        match doc_type.as_ref().map(|doc_type| doc_type.as_str()) {
+            Some("matroska") => self.profile = Profile::Matroska,
+            Some("webm") => self.profile = Profile::WebM,
+            Some(doc_type) => self.error(0, el::DOC_TYPE, format!("unknown document type {}", doc_type)),
+            None => self.error(0, el::DOC_TYPE, "missing DocType"),
+        };
+
+        let offset = input.position() as u64;
+        let (id, size, _) = read_header(input)?.ok_or(eof())?;
+        if id != el::SEGMENT {
+            self.error(offset, id, "expected a Segment");
+            return Ok(());
+        }
+
+        self.segment_offset = input.position() as u64;
+        let end = size.map(|size| self.segment_offset + size);
+
+        let mut queued = None;
+
+        loop {
+            let (id, size, offset) = match queued.take() {
+                Some(header) => header,
+
+                None => {
+                    let offset = input.position() as u64;
+                    if end.map_or(false, |end| offset >= end) {
+                        break;
+                    }
+
+                    match read_header(input)? {
+                        Some((id, size, _)) => (id, size, offset),
+                        None if end.is_none() => break,
+                        None => return Err(eof()),
+                    }
+                },
+            };
+
+            self.top_level.push((offset - self.segment_offset, id));
+
+            match id {
+                el::CLUSTER => {
+                    self.clusters.push(offset - self.segment_offset);
+                    queued = self.cluster(input, offset, size)?;
+                },
+
+                _ => {
+                    let size = match size {
+                        Some(size) => size,
+                        None => {
+                            self.error(offset, id, "only clusters may have an unknown size");
+                            return Ok(());
+                        },
+                    };
+
+                    let data_offset = input.position() as u64;
+                    let data = read_data(input, size)?;
+
+                    self.top_level_element(&Node { id: id, offset: offset, data_offset: data_offset, data: &data });
+                },
+            };
+        }
+
+        if !self.top_level.iter().any(|&(_, id)| id == el::INFO) {
+            self.error(self.segment_offset, el::INFO, "missing Info");
+        }
+
+        if !self.top_level.iter().any(|&(_, id)| id == el::TRACKS) {
+            self.error(self.segment_offset, el::TRACKS, "missing Tracks");
+        }
+
+        if !self.clusters.is_empty() && !self.top_level.iter().any(|&(_, id)| id == el::CUES) {
+            self.warning(self.segment_offset, el::CUES, "missing Cues: the file cannot be seeked into");
+        }
+
+        Ok(())
+    }
+
+    fn top_level_element(&mut self, node: &Node) {
+        let known = schema::find(node.id).map_or(false, |definition| definition.parent == "\\Segment" || definition.parent == "*");
+        if !known {
+            self.error(node.offset, node.id, format!("unknown top-level element 0x{:X}", node.id));
+            return;
+        }
+
+        if (node.id == el::INFO || node.id == el::TRACKS) && !self.clusters.is_empty() {
+            self.error(node.offset, node.id, "must precede the clusters");
+        }
+
+        if node.id == el::INFO || node.id == el::TRACKS {
+            let count = self.top_level.iter().filter(|&&(_, id)| id == node.id).count();
+            if count > 1 {
+                self.error(node.offset, node.id, "duplicate element");
+            }
+        }
+
+        self.element(node, 1);
+    }
+
+    /// Validate an element at `depth` and its children recursively. Top-level elements have a
+    /// depth of 1, and the children nested deeper than the limit are reported instead of
+    /// validated.
+    fn element(&mut self, node: &Node, depth: usize) {
+        if self.profile == Profile::WebM && NOT_WEBM.contains(&node.id) {
+            self.error(node.offset, node.id, format!("element 0x{:X} is not allowed in WebM", node.id));
+        }
+
+        let definition = match schema::find(node.id) {
+            Some(definition) if definition.kind == Type::Master => definition,
+            _ => return self.value(node),
+        };
+
+        let children = parse_children(node.data, node.data_offset, &mut self.findings);
+
+        // Mandatory elements with a default value may be left out.
+        let mandatory = schema::SCHEMA.iter()
+            .filter(|child| child.mandatory && child.default.is_none())
+            .filter(|child| child.parent.rsplit('\\').next() == Some(definition.name));
+
+        for child in mandatory {
+            if !children.iter().any(|node| node.id == child.id) {
+                self.error(node.offset, node.id, format!("missing mandatory element {}", child.name));
+            }
+        }
+
+        for (i, child) in children.iter().enumerate() {
+            if child.id == el::CRC32 && i != 0 {
+                self.error(child.offset, child.id, "CRC-32 must be the first child");
+            }
+
+            if depth + 1 > self.max_depth {
+                self.error(child.offset, child.id, format!("element nested deeper than {} levels", self.max_depth));
+                break;
+            }
+
+            self.element(child, depth + 1);
+        }
+
+        match node.id {
+            el::TRACK_ENTRY => self.track_entry(node, &children),
+            el::SEEK => {
+                let id = children.iter().find(|child| child.id == el::SEEK_ID).map(|child| uint(child.data));
+                let position = children.iter().find(|child| child.id == el::SEEK_POSITION).map(|child| uint(child.data));
+
+                if let (Some(id), Some(position)) = (id, position) {
+                    self.seeks.push((node.offset, id, position));
+                }
+            },
+
+            el::CUE_TRACK_POSITIONS => {
+                let track = children.iter().find(|child| child.id == el::CUE_TRACK).map(|child| uint(child.data));
+                let position = children.iter().find(|child| child.id == el::CUE_CLUSTER_POSITION).map(|child| uint(child.data));
+
+                if let (Some(track), Some(position)) = (track, position) {
+                    self.cues.push((node.offset, track, position));
+                }
+            },
+
+            _ => {},
+        };
+    }
+
+    /// Check the range of a value.
+    fn value(&mut self, node: &Node) {
+        if FLOATS.contains(&node.id) {
+            let value = match node.data.len() {
+                4 => f32::from_bits(uint(node.data) as u32) as f64,
+                8 => f64::from_bits(uint(node.data)),
+                _ => return self.error(node.offset, node.id, "floats must be 4 or 8 bytes long"),
+            };
+
+            if !(value > 0.0) {
+                self.error(node.offset, node.id, format!("value {} must be positive", value));
+            }
+
+            return;
+        }
+
+        let integer = FLAGS.contains(&node.id) || NON_ZERO.contains(&node.id) || node.id == el::TRACK_TYPE;
+        if integer && node.data.len() > 8 {
+            return self.error(node.offset, node.id, "integers must be at most 8 bytes long");
+        }
+
+        let value = uint(node.data);
+
+        if FLAGS.contains(&node.id) && value > 1 {
+            self.error(node.offset, node.id, format!("flag value {} must be 0 or 1", value));
+        }
+
+        if NON_ZERO.contains(&node.id) && value == 0 {
+            self.error(node.offset, node.id, "value must not be 0");
+        }
+
+        if node.id == el::TRACK_TYPE && !TRACK_TYPES.contains(&value) {
+            self.error(node.offset, node.id, format!("invalid track type {}", value));
+        }
+
+        if node.id == el::CODEC_ID && self.profile == Profile::WebM {
+            let codec = String::from_utf8_lossy(node.data);
+
+            if !WEBM_CODECS.contains(&codec.trim_end_matches('\0')) {
+                self.error(node.offset, node.id, format!("codec {} is not allowed in WebM", codec));
+            }
+        }
+    }
+
+    fn track_entry(&mut self, node: &Node, children: &[Node]) {
+        let number = match children.iter().find(|child| child.id == el::TRACK_NUMBER) {
+            Some(child) => uint(child.data),
+            None => return,
+        };
+
+        if self.tracks.iter().any(|track| track.number == number) {
+            self.error(node.offset, el::TRACK_NUMBER, format!("duplicate track number {}", number));
+            return;
+        }
+
+        let video = children.iter().any(|child| child.id == el::TRACK_TYPE && uint(child.data) == 0x01);
+
+        self.tracks.push(Track {
+            number: number,
+            video: video,
+            last_timecode: None,
+        });
+    }
+
+    /// Validate the children of a cluster, which are read one by one. Returns the header of the
+    /// next top-level element if the size of the cluster is unknown.
+    fn cluster<R: Read>(&mut self, input: &mut Input<R>, offset: u64, size: Option<u64>) -> io::Result<Option<(ElementId, Option<u64>, u64)>> {
+        let start = input.position() as u64;
+        let mut timecode = None;
+        let mut started = Vec::new();
+        let mut first = true;
+
+        loop {
+            let child_offset = input.position() as u64;
+            if size.map_or(false, |size| child_offset >= start + size) {
+                break;
+            }
+
+            let (id, child_size) = match read_header(input)? {
+                Some((id, child_size, _)) => (id, child_size),
+                None if size.is_none() => break,
+                None => return Err(eof()),
+            };
+
+            let top_level = schema::find(id).map_or(false, |definition| definition.parent == "\\Segment");
+
+            if size.is_none() && top_level {
+                return Ok(Some((id, child_size, child_offset)));
+            }
+
+            let child_size = child_size.ok_or(invalid("only clusters may have an unknown size"))?;
+
+            if let Some(size) = size {
+                if input.position() as u64 + child_size > start + size {
+                    self.error(child_offset, id, "element extends past the end of its cluster");
+                }
+            }
+
+            let data_offset = input.position() as u64;
+            let data = read_data(input, child_size)?;
+            let node = Node { id: id, offset: child_offset, data_offset: data_offset, data: &data };
+
+            if id == el::CRC32 && !first {
+                self.error(child_offset, id, "CRC-32 must be the first child");
+            }
+
+            if id != el::CRC32 && id != el::VOID {
+                first = false;
+            }
+
+            match id {
+                el::TIMECODE => timecode = Some(uint(&data)),
+
+                el::SIMPLE_BLOCK | el::BLOCK_GROUP => {
+                    if timecode.is_none() {
+                        self.error(child_offset, id, "block before the cluster Timecode");
+                    }
+
+                    self.element(&node, 2);
+                    self.block(&node, timecode.unwrap_or(0), &mut started);
+                },
+
+                el::CRC32 | el::VOID | el::POSITION | el::PREV_SIZE | el::SILENT_TRACKS | el::ENCRYPTED_BLOCK => {
+                    self.element(&node, 2);
+                },
+
+                _ => self.error(child_offset, id, format!("unexpected element 0x{:X} in cluster", id)),
+            };
+        }
+
+        if timecode.is_none() {
+            self.error(offset, el::CLUSTER, "missing mandatory element Timecode");
+        }
+
+        Ok(None)
+    }
+
+    /// Check the track, the timestamp and the keyframe flag of a block. `started` holds the tracks
+    /// that already have a block in the cluster.
+    fn block(&mut self, node: &Node, cluster_timecode: u64, started: &mut Vec<u64>) {
+        // Blocks in groups are keyframes if they do not reference other blocks. Malformed groups
+        // have already been reported.
+        let (data, keyframe) = if node.id == el::SIMPLE_BLOCK {
+            (node.data, None)
+        } else {
+            let children = parse_children(node.data, node.data_offset, &mut Vec::new());
+
+            match children.iter().find(|child| child.id == el::BLOCK) {
+                Some(block) => (block.data, Some(!children.iter().any(|child| child.id == el::REFERENCE_BLOCK))),
+                None => return,
+            }
+        };
+
+        let (track, len) = match read_vint(data) {
+            Some((track, len)) if data.len() >= len + 3 => (track, len),
+            _ => return self.error(node.offset, node.id, "block header is truncated"),
+        };
+
+        let keyframe = keyframe.unwrap_or(data[len + 2] & 0x80 != 0);
+        let timecode = cluster_timecode as i64 + (((data[len] as u16) << 8) | data[len + 1] as u16) as i16 as i64;
+
+        let mut findings = Vec::new();
+
+        match self.tracks.iter_mut().find(|t| t.number == track) {
+            Some(track) => {
+                if track.video && !started.contains(&track.number) && !keyframe {
+                    findings.push((Severity::Warning, format!("cluster does not start with a keyframe of track {}", track.number)));
+                }
+
+                // Video frames are stored in decoding order, so only keyframes are in presentation
+                // order.
+                if !track.video || keyframe {
+                    if let Some(last) = track.last_timecode {
+                        if timecode < last {
+                            findings.push((Severity::Error, format!("timestamp {} of track {} is lower than the previous one, {}", timecode, track.number, last)));
+                        }
+                    }
+
+                    track.last_timecode = Some(timecode);
+                }
+
+                if !started.contains(&track.number) {
+                    started.push(track.number);
+                }
+            },
+
+            None => findings.push((Severity::Error, format!("block of unknown track {}", track))),
+        };
+
+        for (severity, message) in findings {
+            self.push(severity, node.offset, node.id, message);
+        }
+    }
+
+    /// Check that the `SeekHead` entries and the cues point at the right elements.
+    fn check_references(&mut self) {
+        for (offset, id, position) in ::std::mem::replace(&mut self.seeks, Vec::new()) {
+            match self.top_level.iter().find(|&&(p, _)| p == position) {
+                Some(&(_, found)) if found == id => {},
+                Some(&(_, found)) => self.error(offset, el::SEEK, format!("points at 0x{:X} instead of 0x{:X}", found, id)),
+                None => self.error(offset, el::SEEK, format!("position {} of 0x{:X} is not the start of an element", position, id)),
+            };
+        }
+
+        for (offset, track, position) in ::std::mem::replace(&mut self.cues, Vec::new()) {
+            if !self.clusters.contains(&position) {
+                self.error(offset, el::CUE_CLUSTER_POSITION, format!("position {} is not the start of a cluster", position));
+            }
+
+            if !self.tracks.iter().any(|t| t.number == track) {
+                self.error(offset, el::CUE_TRACK, format!("unknown track {}", track));
+            }
+        }
+    }
+
+    fn error<S: Into<String>>(&mut self, offset: u64, element: ElementId, message: S) {
+        self.push(Severity::Error, offset, element, message.into());
+    }
+
+    fn warning<S: Into<String>>(&mut self, offset: u64, element: ElementId, message: S) {
+        self.push(Severity::Warning, offset, element, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, offset: u64, element: ElementId, message: String) {
+        self.findings.push(Finding {
+            severity: severity,
+            offset: offset,
+            element: element,
+            message: message,
+        });
+    }
+}
+
+/// Parse the children of a master element whose content is `data`, located at `offset`.
+/// Malformed children are reported and end the parsing.
+fn parse_children<'a>(data: &'a [u8], offset: u64, findings: &mut Vec<Finding>) -> Vec<Node<'a>> {
+    let mut children = Vec::new();
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let child_offset = offset + (data.len() - rest.len()) as u64;
+
+        let (id, size, len) = match read_header(&mut rest) {
+            Ok(Some((id, Some(size), len))) => (id, size as usize, len),
+            Ok(Some((id, None, _))) => {
+                findings.push(Finding { severity: Severity::Error, offset: child_offset, element: id, message: String::from("only clusters may have an unknown size") });
+                break;
+            },
+            _ => {
+                findings.push(Finding { severity: Severity::Error, offset: child_offset, element: 0, message: String::from("invalid element header") });
+                break;
+            },
+        };
+
+        if size > rest.len() {
+            findings.push(Finding { severity: Severity::Error, offset: child_offset, element: id, message: String::from("element extends past the end of its parent") });
+            break;
+        }
+
+        children.push(Node {
+            id: id,
+            offset: child_offset,
+            data_offset: child_offset + len as u64,
+            data: &rest[..size],
+        });
+
+        rest = &rest[size..];
+    }
+
+    children
+}
@@ -0,0 +1,112 @@
+//! Low-level encoding of EBML elements.
+
+use ebml::types::*;
+
+use elements as el;
+
+/// Size value reserved by EBML to represent an element of unknown size.
+pub const UNKNOWN_SIZE: u64 = 0x00FFFFFFFFFFFFFF;
+
+/// Encode an element ID. IDs are stored as-is, marker bits included, on as few bytes as possible.
+pub fn id(id: ElementId) -> Vec<u8> {
+    let mut len = 1;
+    while len < 8 && id >> (8 * len) != 0 {
+        len += 1;
+    }
+
+    (0..len).rev().map(|i| (id >> (8 * i)) as u8).collect()
+}
+
+/// Encode a VINT on exactly `len` bytes.
+pub fn vint(value: u64, len: usize) -> Vec<u8> {
+    let value = value | (1 << (7 * len));
+    (0..len).rev().map(|i| (value >> (8 * i)) as u8).collect()
+}
+
+/// Encode an element size as a VINT, on as few bytes as possible.
+pub fn size(size: usize) -> Vec<u8> {
+    // A VINT of length `n` cannot hold 2^(7n) - 1 because it is reserved for unknown sizes.
+    let mut len = 1;
+    while len < 8 && (size as u64) >= (1 << (7 * len)) - 1 {
+        len += 1;
+    }
+
+    vint(size as u64, len)
+}
+
+/// Encode a complete element from its ID and its encoded content.
+pub fn element(elem: ElementId, content: &[u8]) -> Vec<u8> {
+    let mut data = id(elem);
+    data.extend(size(content.len()));
+    data.extend_from_slice(content);
+    data
+}
+
+/// Return the content of an encoded element, without its ID and size.
+pub fn content(data: &[u8]) -> &[u8] {
+    let id_len = data[0].leading_zeros() as usize + 1;
+    let size_len = data[id_len].leading_zeros() as usize + 1;
+
+    &data[id_len + size_len..]
+}
+
+/// Encode a master element from the concatenation of its encoded children.
+pub fn master(elem: ElementId, children: Vec<Vec<u8>>) -> Vec<u8> {
+    element(elem, &children.concat())
+}
+
+/// Encode an unsigned integer element.
+pub fn uint(elem: ElementId, value: UnsignedInt) -> Vec<u8> {
+    let mut len = 1;
+    while len < 8 && value >> (8 * len) != 0 {
+        len += 1;
+    }
+
+    let content: Vec<u8> = (0..len).rev().map(|i| (value >> (8 * i)) as u8).collect();
+    element(elem, &content)
+}
+
+/// Encode a signed integer element.
+pub fn int(elem: ElementId, value: SignedInt) -> Vec<u8> {
+    let mut len = 1;
+    while len < 8 && (value >> (8 * len - 1) != 0 && value >> (8 * len - 1) != -1) {
+        len += 1;
+    }
+
+    let content: Vec<u8> = (0..len).rev().map(|i| (value >> (8 * i)) as u8).collect();
+    element(elem, &content)
+}
+
+/// Encode a floating-point element. Always stored on 8 bytes.
+pub fn float(elem: ElementId, value: Float) -> Vec<u8> {
+    let bits = value.to_bits();
+    let content: Vec<u8> = (0..8).rev().map(|i| (bits >> (8 * i)) as u8).collect();
+
+    element(elem, &content)
+}
+
+/// Encode a UTF-8 string element.
+pub fn utf8(elem: ElementId, value: &str) -> Vec<u8> {
+    element(elem, value.as_bytes())
+}
+
+/// Encode a binary element.
+pub fn binary(elem: ElementId, value: &[u8]) -> Vec<u8> {
+    element(elem, value)
+}
+
+/// Encode a `Void` element occupying exactly `len` bytes. `len` must be at least 2.
+pub fn void(len: usize) -> Vec<u8> {
+    // With a single byte for the ID, the size VINT takes 1 byte for small voids, or a fixed 8
+    // bytes otherwise so that any length can be reached.
+    let mut data = id(el::VOID);
+
+    if len < 2 + 126 {
+        data.extend(vint((len - 2) as u64, 1));
+    } else {
+        data.extend(vint((len - 9) as u64, 8));
+    }
+
+    data.resize(len, 0);
+    data
+}
@@ -0,0 +1,767 @@
+//! This module provides the functionality for writing matroska files.
+
+pub mod element;
+
+use std::collections::HashMap;
+use std::io::{Seek, SeekFrom, Write};
+
+use ebml::types::*;
+
+use elements as el;
+use error::{self, Result};
+use reader::{attachment, chapter, segment, tag, track};
+use reader::cluster::{Block, Lacing};
+
+/// Number of bytes reserved at the beginning of the segment for the `SeekHead`, which can only be
+/// written once the positions of all the other top-level elements are known.
+const SEEK_HEAD_RESERVED: usize = 256;
+
+/// Maximum duration of a cluster in nanoseconds. A new cluster is started at the first keyframe
+/// after that duration.
+const CLUSTER_DURATION: u64 = 5000000000;
+
+/// Maximum size of a cluster in bytes.
+const CLUSTER_SIZE: usize = 5 * 1024 * 1024;
+
+/// Position of a keyframe to be indexed in the `Cues`.
+struct CuePoint {
+    time: u64,
+    track: u64,
+    cluster_position: u64,
+    relative_position: u64,
+}
+
+/// Cluster being built in memory. It is written to the output once complete, so that its size is
+/// known beforehand.
+struct PendingCluster {
+    timecode: u64,
+    data: Vec<u8>,
+    cues: Vec<CuePoint>,
+    tracks: Vec<u64>,
+}
+
+/// High-level object that writes a matroska file, block by block.
+pub struct Writer<W: Write + Seek> {
+    w: W,
+
+    // Absolute position of the segment data in the output, and current position relative to it.
+    segment_offset: u64,
+    position: u64,
+
+    // Positions of the top-level elements written so far, relative to the segment data.
+    positions: Vec<(ElementId, u64)>,
+
+    // Position of the `Duration` value, so that it can be updated when the file is finished.
+    duration_position: u64,
+
+    // Position and size of the `NextUID` element, and wether it should be removed.
+    next_uid_position: Option<(u64, usize)>,
+    unlink_next: bool,
+
+    timecode_scale: u64,
+    cluster_duration: u64,
+
+    // Default duration of each track in segment ticks, and numbers of the video tracks.
+    default_durations: HashMap<u64, u64>,
+    video_tracks: Vec<u64>,
+
+    cluster: Option<PendingCluster>,
+    cues: Vec<CuePoint>,
+
+    // End of the last block written, and duration of the segment if it is not that end, in
+    // segment ticks.
+    end_timecode: u64,
+    duration: Option<f64>,
+
+    // Top-level elements to write after the clusters.
+    elements: Vec<(ElementId, Vec<u8>)>,
+}
+
+impl<W: Write + Seek> Writer<W> {
+    /// Initialize a new matroska writer. This function writes the EBML header, the segment
+    /// information and the tracks of the file.
+    pub fn new(mut w: W, doc_type: &str, info: &segment::Info, tracks: &[track::Info]) -> Result<Writer<W>> {
+        let timecode_scale = info.timecode_scale();
+        if timecode_scale == 0 {
+            bail!(error::invalid_value(el::TIMECODE_SCALE, timecode_scale));
+        }
+
+        w.write_all(&encode_header(doc_type))?;
+
+        // The segment size is not known yet: reserve 8 bytes so that it can be updated later.
+        w.write_all(&element::id(el::SEGMENT))?;
+        w.write_all(&element::vint(element::UNKNOWN_SIZE, 8))?;
+
+        let segment_offset = w.seek(SeekFrom::Current(0))?;
+
+        let mut default_durations = HashMap::new();
+        let mut video_tracks = Vec::new();
+
+        for track in tracks {
+            if let Some(duration) = track.default_duration() {
+                default_durations.insert(track.index(), duration / timecode_scale);
+            }
+
+            if let track::Kind::Video(_) = *track.kind() {
+                video_tracks.push(track.index());
+            }
+        }
+
+        let mut info = info.clone();
+        info.set_muxing_app(Some(String::from("mkv-rs")));
+
+        let mut writer = Writer {
+            w: w,
+            segment_offset: segment_offset,
+            position: 0,
+            positions: Vec::new(),
+            duration_position: 0,
+            next_uid_position: None,
+            unlink_next: false,
+            timecode_scale: timecode_scale,
+            cluster_duration: CLUSTER_DURATION / timecode_scale,
+            default_durations: default_durations,
+            video_tracks: video_tracks,
+            cluster: None,
+            cues: Vec::new(),
+            end_timecode: 0,
+            duration: None,
+            elements: Vec::new(),
+        };
+
+        writer.write(&element::void(SEEK_HEAD_RESERVED))?;
+
+        // The next segment UID and the duration are written last in the segment information, so
+        // that their positions are easy to compute.
+        let mut children = info_children(&info);
+
+        let next_uid = info.next_uid().map(|uid| element::binary(el::NEXT_UID, uid));
+        let next_uid_len = next_uid.as_ref().map_or(0, |elem| elem.len());
+
+        children.extend(next_uid);
+        children.push(element::float(el::DURATION, 0.0));
+
+        let info = element::master(el::INFO, children);
+        let info_end = writer.position + info.len() as u64;
+
+        writer.duration_position = info_end - 8;
+
+        if next_uid_len > 0 {
+            let duration_len = element::float(el::DURATION, 0.0).len() as u64;
+            writer.next_uid_position = Some((info_end - duration_len - next_uid_len as u64, next_uid_len));
+        }
+
+        writer.positions.push((el::INFO, writer.position));
+        writer.write(&info)?;
+
+        writer.positions.push((el::TRACKS, writer.position));
+        writer.write(&encode_tracks(tracks))?;
+
+        Ok(writer)
+    }
+
+    /// Write a block, with the specified absolute timecode in segment ticks. Blocks must be
+    /// written in timecode order.
+    pub fn write_block(&mut self, block: Block, timecode: i64) -> Result<()> {
+        if timecode < 0 {
+            bail!(error::invalid_value(el::TIMECODE, timecode));
+        }
+
+        // Legacy virtual blocks have no data to write.
+        if block.is_virtual() {
+            return Ok(());
+        }
+
+        let timecode = timecode as u64;
+        let track = block.track();
+
+        let cue_track = self.video_tracks.is_empty() || self.video_tracks.contains(&track);
+
+        let new_cluster = match self.cluster {
+            Some(ref cluster) => {
+                let delta = timecode as i64 - cluster.timecode as i64;
+
+                delta < i16::min_value() as i64 || delta > i16::max_value() as i64
+                    || cluster.data.len() >= CLUSTER_SIZE
+                    || (block.keyframe() && cue_track && delta >= self.cluster_duration as i64)
+            },
+
+            None => true,
+        };
+
+        if new_cluster {
+            self.flush_cluster()?;
+
+            self.cluster = Some(PendingCluster {
+                timecode: timecode,
+                data: element::uint(el::TIMECODE, timecode),
+                cues: Vec::new(),
+                tracks: Vec::new(),
+            });
+        }
+
+        let duration = block.duration()
+            .or_else(|| self.default_durations.get(&track).map(|d| *d))
+            .unwrap_or(0);
+
+        if timecode + duration > self.end_timecode {
+            self.end_timecode = timecode + duration;
+        }
+
+        let cluster = self.cluster.as_mut().unwrap();
+
+        // Index keyframes of video tracks, or the first keyframe of each track in each cluster
+        // if there is no video.
+        let first = !cluster.tracks.contains(&track);
+
+        if block.keyframe() && cue_track && (first || !self.video_tracks.is_empty()) {
+            cluster.cues.push(CuePoint {
+                time: timecode,
+                track: track,
+                cluster_position: 0,
+                relative_position: cluster.data.len() as u64,
+            });
+        }
+
+        if first {
+            cluster.tracks.push(track);
+        }
+
+        let relative = (timecode as i64 - cluster.timecode as i64) as i16;
+        cluster.data.extend(encode_block(block, relative));
+
+        Ok(())
+    }
+
+    /// Queue a top-level element to be written after the clusters. `content` is the encoded
+    /// content of the element, without its ID and size.
+    pub fn add_element(&mut self, id: ElementId, content: Vec<u8>) {
+        self.elements.push((id, content));
+    }
+
+    /// Remove the `NextUID` element from the segment information when the file is finished. This
+    /// is used when the segment turns out to be the last one of a linked chain.
+    pub fn unlink_next(&mut self) {
+        self.unlink_next = true;
+    }
+
+    /// Return the number of bytes written in the segment so far, including the current cluster.
+    pub fn position(&self) -> u64 {
+        self.position + self.cluster.as_ref().map_or(0, |c| c.data.len() as u64)
+    }
+
+    /// Return the end of the last written block, in segment ticks.
+    pub fn end_timecode(&self) -> u64 {
+        self.end_timecode
+    }
+
+    /// Set the duration of the segment, in segment ticks. By default, it is the end of the last
+    /// block written.
+    pub fn set_duration(&mut self, duration: f64) {
+        self.duration = Some(duration);
+    }
+
+    /// Write the last cluster, the cues and the queued elements, then update the segment size,
+    /// the segment duration and the `SeekHead`. Returns the underlying output.
+    pub fn finish(mut self) -> Result<W> {
+        self.flush_cluster()?;
+
+        if !self.cues.is_empty() {
+            let cues = encode_cues(&self.cues);
+
+            self.positions.push((el::CUES, self.position));
+            self.write(&cues)?;
+        }
+
+        for (id, content) in ::std::mem::replace(&mut self.elements, Vec::new()) {
+            self.positions.push((id, self.position));
+            self.write(&element::element(id, &content))?;
+        }
+
+        let end = self.position;
+
+        // Fill the space reserved at the beginning of the segment with the `SeekHead`.
+        let seek_head = encode_seek_head(&self.positions);
+        if seek_head.len() + 2 > SEEK_HEAD_RESERVED {
+            bail!(error::invalid_value(el::SEEK_HEAD, "too many entries"));
+        }
+
+        self.w.seek(SeekFrom::Start(self.segment_offset))?;
+        self.w.write_all(&seek_head)?;
+        self.w.write_all(&element::void(SEEK_HEAD_RESERVED - seek_head.len()))?;
+
+        let duration = self.duration.unwrap_or(self.end_timecode as f64).to_bits();
+        let duration: Vec<u8> = (0..8).rev().map(|i| (duration >> (8 * i)) as u8).collect();
+
+        self.w.seek(SeekFrom::Start(self.segment_offset + self.duration_position))?;
+        self.w.write_all(&duration)?;
+
+        if let (true, Some((position, len))) = (self.unlink_next, self.next_uid_position) {
+            self.w.seek(SeekFrom::Start(self.segment_offset + position))?;
+            self.w.write_all(&element::void(len))?;
+        }
+
+        self.w.seek(SeekFrom::Start(self.segment_offset - 8))?;
+        self.w.write_all(&element::vint(end, 8))?;
+
+        self.w.seek(SeekFrom::Start(self.segment_offset + end))?;
+        self.w.flush()?;
+
+        Ok(self.w)
+    }
+
+    /// Return the timestamp scale of the segment being written, in nanoseconds.
+    pub fn timecode_scale(&self) -> u64 {
+        self.timecode_scale
+    }
+
+    fn flush_cluster(&mut self) -> Result<()> {
+        if let Some(cluster) = self.cluster.take() {
+            let position = self.position;
+
+            let mut data = element::id(el::CLUSTER);
+            data.extend(element::size(cluster.data.len()));
+            data.extend(cluster.data);
+
+            self.write(&data)?;
+
+            // Relative positions are already relative to the start of the cluster data.
+            for mut cue in cluster.cues {
+                cue.cluster_position = position;
+                self.cues.push(cue);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.w.write_all(data)?;
+        self.position += data.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Generate a random 128-bit segment UID.
+pub fn generate_uid() -> Vec<u8> {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+    let mut uid = Vec::with_capacity(16);
+
+    // Every `RandomState` is seeded with different random keys.
+    for i in 0..2 {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(now);
+        hasher.write_u32(i);
+
+        let value = hasher.finish();
+        uid.extend((0..8).rev().map(|i| (value >> (8 * i)) as u8));
+    }
+
+    uid
+}
+
+/// Encode the EBML header of a matroska file with the specified document type.
+pub fn encode_header(doc_type: &str) -> Vec<u8> {
+    element::master(el::EBML, vec![
+        element::uint(el::EBML_VERSION, 1),
+        element::uint(el::EBML_READ_VERSION, 1),
+        element::uint(el::EBML_MAX_ID_LENGTH, 4),
+        element::uint(el::EBML_MAX_SIZE_LENGTH, 8),
+        element::utf8(el::DOC_TYPE, doc_type),
+        element::uint(el::DOC_TYPE_VERSION, 4),
+        element::uint(el::DOC_TYPE_READ_VERSION, 2),
+    ])
+}
+
+/// Encode the `Info` element from segment information.
+pub fn encode_info(info: &segment::Info) -> Vec<u8> {
+    let mut children = info_children(info);
+
+    if let Some(uid) = info.next_uid() {
+        children.push(element::binary(el::NEXT_UID, uid));
+    }
+
+    if let Some(duration) = info.duration() {
+        children.push(element::float(el::DURATION, duration));
+    }
+
+    element::master(el::INFO, children)
+}
+
+/// Encode the `Tracks` element from track information.
+pub fn encode_tracks(tracks: &[track::Info]) -> Vec<u8> {
+    element::master(el::TRACKS, tracks.iter().map(encode_track_entry).collect())
+}
+
+/// Encode the children of the `Info` element, except for `NextUID` and `Duration` which the
+/// callers append last.
+fn info_children(info: &segment::Info) -> Vec<Vec<u8>> {
+    let mut children = Vec::new();
+
+    if let Some(uid) = info.uid() {
+        children.push(element::binary(el::SEGMENT_UID, uid));
+    }
+
+    if let Some(uid) = info.prev_uid() {
+        children.push(element::binary(el::PREV_UID, uid));
+    }
+
+    if let Some(filename) = info.filename() {
+        children.push(element::utf8(el::SEGMENT_FILENAME, filename));
+    }
+
+    if let Some(filename) = info.prev_filename() {
+        children.push(element::utf8(el::PREV_FILENAME, filename));
+    }
+
+    if let Some(filename) = info.next_filename() {
+        children.push(element::utf8(el::NEXT_FILENAME, filename));
+    }
+
+    children.push(element::uint(el::TIMECODE_SCALE, info.timecode_scale()));
+
+    if let Some(title) = info.title() {
+        children.push(element::utf8(el::TITLE, title));
+    }
+
+    children.push(element::utf8(el::MUXING_APP, info.muxing_app().unwrap_or("mkv-rs")));
+    children.push(element::utf8(el::WRITING_APP, info.writing_app().unwrap_or("mkv-rs")));
+
+    for &(id, ref content) in info.extra() {
+        children.push(element::binary(id, content));
+    }
+
+    children
+}
+
+fn encode_track_entry(track: &track::Info) -> Vec<u8> {
+    let mut children = vec![
+        element::uint(el::TRACK_NUMBER, track.index()),
+        element::uint(el::TRACK_UID, track.uid()),
+        element::uint(el::TRACK_TYPE, track.kind().track_type()),
+        element::uint(el::FLAG_ENABLED, track.enabled() as u64),
+        element::uint(el::FLAG_DEFAULT, track.default() as u64),
+        element::uint(el::FLAG_FORCED, track.forced() as u64),
+        element::uint(el::FLAG_LACING, track.lacing() as u64),
+    ];
+
+    if let Some(duration) = track.default_duration() {
+        children.push(element::uint(el::DEFAULT_DURATION, duration));
+    }
+
+    if let Some(name) = track.name() {
+        children.push(element::utf8(el::NAME, name));
+    }
+
+    children.push(element::utf8(el::LANGUAGE, track.language()));
+    children.push(element::utf8(el::CODEC_ID, track.codec()));
+
+    if let Some(data) = track.codec_private() {
+        children.push(element::binary(el::CODEC_PRIVATE, data));
+    }
+
+    if track.codec_delay() != 0 {
+        children.push(element::uint(el::CODEC_DELAY, track.codec_delay()));
+    }
+
+    if track.seek_pre_roll() != 0 {
+        children.push(element::uint(el::SEEK_PRE_ROLL, track.seek_pre_roll()));
+    }
+
+    match *track.kind() {
+        track::Kind::Video(ref video) => {
+            let mut video_children = vec![
+                element::uint(el::PIXEL_WIDTH, video.width()),
+                element::uint(el::PIXEL_HEIGHT, video.height()),
+            ];
+
+            if let Some(width) = video.display_width() {
+                video_children.push(element::uint(el::DISPLAY_WIDTH, width));
+            }
+
+            if let Some(height) = video.display_height() {
+                video_children.push(element::uint(el::DISPLAY_HEIGHT, height));
+            }
+
+            for &(id, ref content) in video.extra() {
+                video_children.push(element::binary(id, content));
+            }
+
+            children.push(element::master(el::VIDEO, video_children));
+        },
+
+        track::Kind::Audio(ref audio) => {
+            let mut audio_children = vec![
+                element::float(el::SAMPLING_FREQUENCY, audio.sampling_frequency()),
+                element::uint(el::CHANNELS, audio.channels()),
+            ];
+
+            if audio.output_sampling_frequenct() != audio.sampling_frequency() {
+                audio_children.push(element::float(el::OUTPUT_SAMPLING_FREQUENCY, audio.output_sampling_frequenct()));
+            }
+
+            if let Some(depth) = audio.bit_depth() {
+                audio_children.push(element::uint(el::BIT_DEPTH, depth));
+            }
+
+            for &(id, ref content) in audio.extra() {
+                audio_children.push(element::binary(id, content));
+            }
+
+            children.push(element::master(el::AUDIO, audio_children));
+        },
+
+        _ => {},
+    };
+
+    if let Some(operation) = track.operation() {
+        let mut operation_children = Vec::new();
+
+        if !operation.planes.is_empty() {
+            operation_children.push(element::master(el::TRACK_COMBINE_PLANES, operation.planes.iter().map(|plane| {
+                element::master(el::TRACK_PLANE, vec![
+                    element::uint(el::TRACK_PLANE_UID, plane.uid),
+                    element::uint(el::TRACK_PLANE_TYPE, plane.kind.value()),
+                ])
+            }).collect()));
+        }
+
+        if !operation.join_uids.is_empty() {
+            operation_children.push(element::master(el::TRACK_JOIN_BLOCKS, operation.join_uids.iter()
+                .map(|&uid| element::uint(el::TRACK_JOIN_UID, uid))
+                .collect()));
+        }
+
+        children.push(element::master(el::TRACK_OPERATION, operation_children));
+    }
+
+    let trick = track.trick();
+
+    if let Some(uid) = trick.track_uid {
+        children.push(element::uint(el::TRICK_TRACK_UID, uid));
+    }
+
+    if let Some(ref uid) = trick.track_segment_uid {
+        children.push(element::binary(el::TRICK_TRACK_SEGMENT_UID, uid));
+    }
+
+    if trick.trick_track {
+        children.push(element::uint(el::TRICK_TRACK_FLAG, 1));
+    }
+
+    if let Some(uid) = trick.master_track_uid {
+        children.push(element::uint(el::TRICK_MASTER_TRACK_UID, uid));
+    }
+
+    if let Some(ref uid) = trick.master_track_segment_uid {
+        children.push(element::binary(el::TRICK_MASTER_TRACK_SEGMENT_UID, uid));
+    }
+
+    for &(id, ref content) in track.extra() {
+        children.push(element::binary(id, content));
+    }
+
+    element::master(el::TRACK_ENTRY, children)
+}
+
+/// Encode the `Tags` element.
+pub fn encode_tags(tags: &[tag::Tag]) -> Vec<u8> {
+    element::master(el::TAGS, tags.iter().map(|tag| {
+        let targets = &tag.targets;
+        let mut target_children = vec![element::uint(el::TARGET_TYPE_VALUE, targets.type_value)];
+
+        if let Some(ref target_type) = targets.target_type {
+            target_children.push(element::utf8(el::TARGET_TYPE, target_type));
+        }
+
+        target_children.extend(targets.track_uids.iter().map(|uid| element::uint(el::TAG_TRACK_UID, *uid)));
+        target_children.extend(targets.edition_uids.iter().map(|uid| element::uint(el::TAG_EDITION_UID, *uid)));
+        target_children.extend(targets.chapter_uids.iter().map(|uid| element::uint(el::TAG_CHAPTER_UID, *uid)));
+        target_children.extend(targets.attachment_uids.iter().map(|uid| element::uint(el::TAG_ATTACHMENT_UID, *uid)));
+
+        let mut children = vec![element::master(el::TARGETS, target_children)];
+        children.extend(tag.simple_tags.iter().map(encode_simple_tag));
+
+        element::master(el::TAG, children)
+    }).collect())
+}
+
+/// Encode the `Chapters` element.
+pub fn encode_chapters(editions: &[chapter::Edition]) -> Vec<u8> {
+    element::master(el::CHAPTERS, editions.iter().map(|edition| {
+        let mut children = Vec::new();
+
+        if let Some(uid) = edition.uid() {
+            children.push(element::uint(el::EDITION_UID, uid));
+        }
+
+        children.push(element::uint(el::EDITION_FLAG_HIDDEN, edition.hidden() as u64));
+        children.push(element::uint(el::EDITION_FLAG_DEFAULT, edition.default() as u64));
+        children.push(element::uint(el::EDITION_FLAG_ORDERED, edition.ordered() as u64));
+        children.extend(edition.atoms().iter().map(encode_chapter_atom));
+
+        element::master(el::EDITION_ENTRY, children)
+    }).collect())
+}
+
+fn encode_chapter_atom(atom: &chapter::Atom) -> Vec<u8> {
+    let mut children = vec![
+        element::uint(el::CHAPTER_UID, atom.uid()),
+        element::uint(el::CHAPTER_TIME_START, atom.time_start()),
+    ];
+
+    if let Some(end) = atom.time_end() {
+        children.push(element::uint(el::CHAPTER_TIME_END, end));
+    }
+
+    children.push(element::uint(el::CHAPTER_FLAG_HIDDEN, atom.hidden() as u64));
+    children.push(element::uint(el::CHAPTER_FLAG_ENABLED, atom.enabled() as u64));
+
+    if let Some(uid) = atom.segment_uid() {
+        children.push(element::binary(el::CHAPTER_SEGMENT_UID, uid));
+    }
+
+    if let Some(uid) = atom.segment_edition_uid() {
+        children.push(element::uint(el::CHAPTER_SEGMENT_EDITION_UID, uid));
+    }
+
+    for display in atom.displays() {
+        let mut display_children = vec![element::utf8(el::CHAP_STRING, display.string())];
+        display_children.extend(display.languages().iter().map(|language| element::utf8(el::CHAP_LANGUAGE, language)));
+
+        children.push(element::master(el::CHAPTER_DISPLAY, display_children));
+    }
+
+    children.extend(atom.children().iter().map(encode_chapter_atom));
+
+    element::master(el::CHAPTER_ATOM, children)
+}
+
+/// Encode the `Attachments` element.
+pub fn encode_attachments(files: &[attachment::File]) -> Vec<u8> {
+    element::master(el::ATTACHEMENTS, files.iter().map(|file| {
+        let mut children = Vec::new();
+
+        if let Some(ref description) = file.description {
+            children.push(element::utf8(el::FILE_DESCRIPTION, description));
+        }
+
+        children.push(element::utf8(el::FILE_NAME, &file.name));
+        children.push(element::utf8(el::FILE_MIME_TYPE, &file.mime_type));
+        children.push(element::binary(el::FILE_DATA, &file.data));
+        children.push(element::uint(el::FILE_UID, file.uid));
+
+        element::master(el::ATTACHED_FILE, children)
+    }).collect())
+}
+
+fn encode_simple_tag(tag: &tag::SimpleTag) -> Vec<u8> {
+    let mut children = vec![
+        element::utf8(el::TAG_NAME, &tag.name),
+        element::utf8(el::TAG_LANGUAGE, &tag.language),
+        element::uint(el::TAG_DEFAULT, tag.default as u64),
+    ];
+
+    if let Some(ref string) = tag.string {
+        children.push(element::utf8(el::TAG_STRING, string));
+    }
+
+    if let Some(ref binary) = tag.binary {
+        children.push(element::binary(el::TAG_BINARY, binary));
+    }
+
+    children.extend(tag.children.iter().map(encode_simple_tag));
+
+    element::master(el::SIMPLE_TAG, children)
+}
+
+/// Encode a block as a `SimpleBlock`, or as a `BlockGroup` if it carries a duration or
+/// references. `timecode` is relative to the cluster.
+fn encode_block(block: Block, timecode: i16) -> Vec<u8> {
+    let group = block.duration().is_some() || !block.references().is_empty() || !block.additions().is_empty();
+
+    let mut flags = match *block.lacing() {
+        Lacing::None => 0b000,
+        Lacing::Xiph => 0b010,
+        Lacing::FixedSize => 0b100,
+        Lacing::Ebml => 0b110,
+    };
+
+    if block.invisible() {
+        flags |= 0x08;
+    }
+
+    // The keyframe and discardable flags only exist in simple blocks.
+    if !group {
+        if block.keyframe() {
+            flags |= 0x80;
+        }
+
+        if block.discardable() {
+            flags |= 0x01;
+        }
+    }
+
+    let mut data = element::size(block.track() as usize);
+    data.push((timecode >> 8) as u8);
+    data.push(timecode as u8);
+    data.push(flags);
+
+    let duration = block.duration();
+    let references = block.references().to_vec();
+    let additions = block.additions().to_vec();
+
+    data.extend(block.data());
+
+    if !group {
+        return element::binary(el::SIMPLE_BLOCK, &data);
+    }
+
+    let mut children = vec![element::binary(el::BLOCK, &data)];
+
+    if let Some(duration) = duration {
+        children.push(element::uint(el::BLOCK_DURATION, duration));
+    }
+
+    for reference in references {
+        children.push(element::int(el::REFERENCE_BLOCK, reference));
+    }
+
+    if !additions.is_empty() {
+        children.push(element::master(el::BLOCK_ADDITIONS, additions.into_iter().map(|(id, data)| {
+            element::master(el::BLOCK_MORE, vec![
+                element::uint(el::BLOCK_ADD_ID, id),
+                element::binary(el::BLOCK_ADDITIONAL, &data),
+            ])
+        }).collect()));
+    }
+
+    element::master(el::BLOCK_GROUP, children)
+}
+
+fn encode_cues(cues: &[CuePoint]) -> Vec<u8> {
+    element::master(el::CUES, cues.iter().map(|cue| {
+        element::master(el::CUE_POINT, vec![
+            element::uint(el::CUE_TIME, cue.time),
+            element::master(el::CUE_TRACK_POSITIONS, vec![
+                element::uint(el::CUE_TRACK, cue.track),
+                element::uint(el::CUE_CLUSTER_POSITION, cue.cluster_position),
+                element::uint(el::CUE_RELATIVE_POSITION, cue.relative_position),
+            ]),
+        ])
+    }).collect())
+}
+
+fn encode_seek_head(positions: &[(ElementId, u64)]) -> Vec<u8> {
+    element::master(el::SEEK_HEAD, positions.iter().map(|&(id, position)| {
+        element::master(el::SEEK, vec![
+            element::binary(el::SEEK_ID, &element::id(id)),
+            element::uint(el::SEEK_POSITION, position),
+        ])
+    }).collect())
+}